@@ -0,0 +1,165 @@
+/// Format of the access log `fastn serve` writes to stdout, one line per request
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AccessLogFormat {
+    /// Apache combined log format, followed by the time taken in seconds
+    Combined,
+    /// A json object per line
+    Json,
+}
+
+impl std::str::FromStr for AccessLogFormat {
+    type Err = fastn_core::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "combined" => Ok(AccessLogFormat::Combined),
+            "json" => Ok(AccessLogFormat::Json),
+            t => Err(fastn_core::Error::UsageError {
+                message: format!(
+                    "unknown access log format: {}, expected combined or json",
+                    t
+                ),
+            }),
+        }
+    }
+}
+
+#[derive(Debug, serde::Serialize)]
+pub(crate) struct Entry {
+    pub time: chrono::DateTime<chrono::Local>,
+    pub ip: Option<String>,
+    pub method: String,
+    pub path: String,
+    pub query: String,
+    pub version: String,
+    pub status: u16,
+    pub bytes: Option<u64>,
+    pub latency_ms: f64,
+    /// `<provider>:<username>` of the logged in user, comma separated
+    pub identity: Option<String>,
+    pub package: Option<String>,
+    pub route: &'static str,
+    pub referer: Option<String>,
+    pub user_agent: Option<String>,
+}
+
+impl Entry {
+    pub(crate) fn write(&self, format: AccessLogFormat) {
+        match self.line(format) {
+            Ok(line) => println!("{}", line),
+            Err(e) => tracing::error!(msg = "access-log-error", error = %e),
+        }
+    }
+
+    fn line(&self, format: AccessLogFormat) -> serde_json::Result<String> {
+        match format {
+            AccessLogFormat::Json => serde_json::to_string(self),
+            AccessLogFormat::Combined => {
+                let or_dash = |v: &Option<String>| v.clone().unwrap_or_else(|| "-".to_string());
+                Ok(format!(
+                    "{} - {} [{}] \"{} {}{} {}\" {} {} \"{}\" \"{}\" {:.6}",
+                    or_dash(&self.ip),
+                    or_dash(&self.identity),
+                    self.time.format("%d/%b/%Y:%H:%M:%S %z"),
+                    self.method,
+                    self.path,
+                    if self.query.is_empty() {
+                        "".to_string()
+                    } else {
+                        format!("?{}", self.query)
+                    },
+                    self.version,
+                    self.status,
+                    self.bytes
+                        .map(|b| b.to_string())
+                        .unwrap_or_else(|| "-".to_string()),
+                    or_dash(&self.referer),
+                    or_dash(&self.user_agent),
+                    self.latency_ms / 1000.0,
+                ))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    fn entry() -> super::Entry {
+        use chrono::TimeZone;
+
+        super::Entry {
+            time: chrono::Local.timestamp_opt(1_000_000_000, 0).unwrap(),
+            ip: Some("10.0.0.1".to_string()),
+            method: "GET".to_string(),
+            path: "/blog/".to_string(),
+            query: "page=2".to_string(),
+            version: "HTTP/1.1".to_string(),
+            status: 200,
+            bytes: Some(512),
+            latency_ms: 12.5,
+            identity: Some("github:amitu".to_string()),
+            package: Some("www.amitu.com".to_string()),
+            route: "serve",
+            referer: None,
+            user_agent: Some("curl/8.0".to_string()),
+        }
+    }
+
+    #[test]
+    fn combined() {
+        let entry = entry();
+        assert_eq!(
+            entry.line(super::AccessLogFormat::Combined).unwrap(),
+            format!(
+                "10.0.0.1 - github:amitu [{}] \"GET /blog/?page=2 HTTP/1.1\" 200 512 \"-\" \
+                \"curl/8.0\" 0.012500",
+                entry.time.format("%d/%b/%Y:%H:%M:%S %z")
+            )
+        );
+
+        let entry = super::Entry {
+            ip: None,
+            identity: None,
+            query: "".to_string(),
+            bytes: None,
+            ..entry
+        };
+        assert!(entry
+            .line(super::AccessLogFormat::Combined)
+            .unwrap()
+            .starts_with("- - - ["));
+        assert!(entry
+            .line(super::AccessLogFormat::Combined)
+            .unwrap()
+            .contains("\"GET /blog/ HTTP/1.1\" 200 - \"-\""));
+    }
+
+    #[test]
+    fn json() {
+        let line = entry().line(super::AccessLogFormat::Json).unwrap();
+        assert!(!line.contains('\n'));
+        let value: serde_json::Value = serde_json::from_str(line.as_str()).unwrap();
+        assert_eq!(value["method"], "GET");
+        assert_eq!(value["path"], "/blog/");
+        assert_eq!(value["status"], 200);
+        assert_eq!(value["latency_ms"], 12.5);
+        assert_eq!(value["identity"], "github:amitu");
+        assert_eq!(value["package"], "www.amitu.com");
+        assert_eq!(value["referer"], serde_json::Value::Null);
+    }
+
+    #[test]
+    fn from_str() {
+        use std::str::FromStr;
+
+        assert_eq!(
+            super::AccessLogFormat::from_str("json").unwrap(),
+            super::AccessLogFormat::Json
+        );
+        assert_eq!(
+            super::AccessLogFormat::from_str("combined").unwrap(),
+            super::AccessLogFormat::Combined
+        );
+        assert!(super::AccessLogFormat::from_str("common").is_err());
+    }
+}
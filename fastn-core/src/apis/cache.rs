@@ -57,6 +57,7 @@ pub async fn clear(req: &fastn_core::http::Request) -> fastn_core::http::Respons
             err
         );
     }
+    fastn_core::render_cache::clear();
     dbg!("cache-cleared");
     fastn_core::http::ok("Done".into())
 }
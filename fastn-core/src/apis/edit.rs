@@ -52,6 +52,24 @@ pub async fn edit(
         }
     };
 
+    match fastn_core::package::app::can_write(&config, req_data.path.as_str()).await {
+        Ok(can_write) => {
+            if !can_write {
+                return Ok(fastn_core::unauthorised!(
+                    "You are unauthorized to access: {}",
+                    req_data.path.as_str()
+                ));
+            }
+        }
+        Err(e) => {
+            return Ok(fastn_core::server_error!(
+                "fastn-Error: app::can_write error: {}, {:?}",
+                req_data.path.as_str(),
+                e
+            ));
+        }
+    };
+
     match edit_worker(config, req_data).await {
         Ok(data) => fastn_core::http::api_ok(data),
         Err(err) => fastn_core::http::api_error(err.to_string()),
@@ -5,6 +5,7 @@ pub(crate) mod edit;
 pub(crate) mod edit_source;
 pub(crate) mod sync;
 pub(crate) mod sync2;
+pub(crate) mod translation_status;
 pub(crate) mod view_source;
 
 pub(crate) use self::edit::edit;
@@ -12,4 +13,5 @@ pub(crate) use clone::clone;
 pub(crate) use edit_source::edit_source;
 pub(crate) use sync::sync;
 pub(crate) use sync2::sync2;
+pub(crate) use translation_status::translation_status;
 pub(crate) use view_source::view_source;
@@ -0,0 +1,125 @@
+/// `/-/translation-status/`: the translation status of the documents of the package, as a page,
+/// or as json with `?format=json`
+pub async fn translation_status(
+    req: fastn_core::http::Request,
+) -> fastn_core::Result<fastn_core::http::Response> {
+    match translation_status_worker(req).await {
+        Ok(response) => Ok(response),
+        Err(err) => fastn_core::http::api_error(err.to_string()),
+    }
+}
+
+async fn translation_status_worker(
+    req: fastn_core::http::Request,
+) -> fastn_core::Result<fastn_core::http::Response> {
+    let mut config = fastn_core::Config::read(None, false, Some(&req)).await?;
+    if !config.is_translation_package() {
+        return Ok(fastn_core::not_found!(
+            "{} is not a translation package",
+            config.package.name
+        ));
+    }
+    let report =
+        fastn_core::commands::translation_status::get_translation_status_report(&config).await?;
+    if req
+        .query()
+        .get("format")
+        .and_then(|v| v.as_str())
+        .eq(&Some("json"))
+    {
+        return fastn_core::http::api_ok(report);
+    }
+
+    let main_document = fastn_core::Document {
+        id: "-/translation-status.ftd".to_string(),
+        content: page(&config, &report),
+        parent_path: config.root.as_str().to_string(),
+        package_name: config.package.name.clone(),
+    };
+    let body =
+        fastn_core::package::package_doc::read_ftd(&mut config, &main_document, "/", false, false)
+            .await?;
+    Ok(fastn_core::http::ok(body))
+}
+
+fn page(
+    config: &fastn_core::Config,
+    report: &fastn_core::commands::translation_status::TranslationStatusReport,
+) -> String {
+    let mut documents = String::new();
+    for document in report.documents.iter() {
+        let marked = match document.last_marked_on {
+            Some(ref last_marked_on) => format!(", last marked on {}", last_marked_on),
+            None => String::new(),
+        };
+        let diff = match document.diff {
+            Some(ref diff) => format!(
+                indoc::indoc! {"
+                    -- ftd.code:
+                    lang: diff
+
+                    {diff}
+                "},
+                diff = fastn_core::utils::escape_ftd(diff.trim_end()),
+            ),
+            None => String::new(),
+        };
+        documents = format!(
+            indoc::indoc! {"
+                {documents}
+
+                -- ftd.column:
+                spacing.fixed.px: 4
+                width: fill-container
+
+                -- ftd.text: {file}
+                role: $inherited.types.heading-tiny
+                color: $inherited.colors.text-strong
+
+                -- ftd.text: {status}{marked}
+                color: $inherited.colors.text
+
+                {diff}
+
+                -- end: ftd.column
+            "},
+            documents = documents,
+            file = document.file,
+            status = document.status.as_str(),
+            marked = marked,
+            diff = diff,
+        );
+    }
+
+    format!(
+        indoc::indoc! {"
+            {body_prefix}
+
+            -- ftd.column:
+            padding.px: 40
+            spacing.fixed.px: 20
+            width: fill-container
+
+            -- ftd.text: Translation status of {package}
+            role: $inherited.types.heading-large
+            color: $inherited.colors.text-strong
+
+            -- ftd.text: Missing: {missing}, never marked: {never_marked}, out-dated: {out_dated}, up to date: {upto_date}
+            color: $inherited.colors.text
+
+            {documents}
+
+            -- end: ftd.column
+        "},
+        body_prefix = config
+            .package
+            .generate_prefix_string(false)
+            .unwrap_or_default(),
+        package = report.package,
+        missing = report.summary.missing,
+        never_marked = report.summary.never_marked,
+        out_dated = report.summary.out_dated,
+        upto_date = report.summary.upto_date,
+        documents = documents,
+    )
+}
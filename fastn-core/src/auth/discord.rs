@@ -109,6 +109,8 @@ pub async fn callback(req: actix_web::HttpRequest) -> fastn_core::Result<actix_w
                     ))
                     .path("/")
                     .permanent()
+                    .secure(fastn_core::auth::utils::is_secure(&req))
+                    .http_only(true)
                     .finish(),
                 )
                 .append_header((actix_web::http::header::LOCATION, "/".to_string()))
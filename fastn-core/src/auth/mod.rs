@@ -135,7 +135,8 @@ pub fn secret_key() -> String {
     match std::env::var("SECRET_KEY") {
         Ok(secret) => secret,
         Err(_e) => {
-            println!("WARN: SECRET_KEY not set");
+            static WARNED: std::sync::Once = std::sync::Once::new();
+            WARNED.call_once(|| println!("WARN: SECRET_KEY not set"));
             // TODO: Need to change this approach later
             "FASTN_TEMP_SECRET".to_string()
         }
@@ -215,6 +216,29 @@ pub async fn get_user_data_from_cookies(
     Ok(None)
 }
 
+/// Returns the username the user is logged in with, keyed by the auth provider, for every
+/// provider whose cookie is present and readable
+pub async fn get_logged_in_identities(
+    cookies: &std::collections::HashMap<String, String>,
+) -> std::collections::BTreeMap<String, String> {
+    let mut identities = std::collections::BTreeMap::new();
+    for provider in [
+        fastn_core::auth::AuthProviders::GitHub,
+        fastn_core::auth::AuthProviders::TeleGram,
+        fastn_core::auth::AuthProviders::Discord,
+    ] {
+        if !cookies.contains_key(provider.as_str()) {
+            continue;
+        }
+        if let Ok(Some(username)) =
+            get_user_data_from_cookies(provider.as_str(), "username", cookies).await
+        {
+            identities.insert(provider.as_str().to_string(), username);
+        }
+    }
+    identities
+}
+
 // TODO: rename the method later
 // bridge between fastn_core to auth to check
 pub async fn get_auth_identities(
@@ -7,6 +7,12 @@ pub fn domain(host: &str) -> String {
         None => host.to_string(),
     }
 }
+
+/// `true` if the request came over https, directly or through a proxy, in which case the auth
+/// cookies are only sent back over https
+pub fn is_secure(req: &actix_web::HttpRequest) -> bool {
+    req.connection_info().scheme() == "https"
+}
 pub async fn get_api<T: serde::de::DeserializeOwned>(
     url: &str,
     token: &str,
@@ -42,6 +48,9 @@ pub async fn encrypt_str(user_detail_str: &String) -> String {
         .to_owned()
 }
 pub async fn decrypt_str(encrypted_str: &String) -> Result<String, MagicCryptError> {
+    decrypt(encrypted_str)
+}
+pub fn decrypt(encrypted_str: &str) -> Result<String, MagicCryptError> {
     use magic_crypt::MagicCryptTrait;
     let secret_key = fastn_core::auth::secret_key();
     let mc_obj = magic_crypt::new_magic_crypt!(&secret_key, 256);
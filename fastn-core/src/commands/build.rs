@@ -4,6 +4,7 @@ pub async fn build(
     base_url: &str,
     ignore_failed: bool,
     test: bool,
+    compress: bool,
 ) -> fastn_core::Result<()> {
     fastn_core::utils::enable_parse_caching(true);
 
@@ -92,10 +93,15 @@ pub async fn build(
                         continue;
                     }
                 }
+                config.pagination = None;
                 let resp = fastn_core::package::package_doc::process_ftd(
                     config, doc, base_url, no_static, test,
                 )
                 .await;
+                let resp = match resp {
+                    Ok(_) => process_pages(config, doc, base_url, no_static, test).await,
+                    Err(e) => Err(e),
+                };
                 match (resp, ignore_failed) {
                     (Ok(_), _) => (),
                     (_, true) => {
@@ -190,9 +196,202 @@ pub async fn build(
         );
     }
 
+    if let Some(dynamic_urls) = config.package.dynamic_urls.clone() {
+        prerender_dynamic_urls(config, &dynamic_urls, file, base_url, ignore_failed, test).await?;
+    }
+
     if !no_static {
         config.download_fonts().await?;
     }
+
+    // `.br` and `.gz` siblings, for servers which send precompressed files
+    if compress {
+        fastn_core::static_asset::write_precompressed(config.build_dir().as_path()).await?;
+    }
+    Ok(())
+}
+
+/// `fastn build --multilingual`: builds every translation of the package, from its sources in
+/// `.packages/<translation>`, at `<base-url><language>/` and copies it to `.build/<language>/`,
+/// so the package and its translations are one site
+pub async fn build_translations(
+    config: &fastn_core::Config,
+    base_url: &str,
+    ignore_failed: bool,
+    test: bool,
+    compress: bool,
+) -> fastn_core::Result<()> {
+    if let Some(original) = config.package.translation_of.as_ref() {
+        return Err(fastn_core::Error::UsageError {
+            message: format!(
+                "{} is a translation, build the original package {} with `--multilingual`",
+                config.package.name, original.name
+            ),
+        });
+    }
+
+    for translation in config.package.translations.iter() {
+        let translation = config.resolve_package(translation).await?;
+        let language =
+            translation
+                .language
+                .clone()
+                .ok_or_else(|| fastn_core::Error::PackageError {
+                    message: format!(
+                        "translation {} has no `language` in its FASTN.ftd",
+                        translation.name
+                    ),
+                })?;
+        let root = config.get_root_for_package(&translation);
+        if !root.join("index.ftd").exists() && !root.join("index.md").exists() {
+            return Err(fastn_core::Error::UsageError {
+                message: format!(
+                    "sources of the translation {} not found, check them out in {}",
+                    translation.name, root
+                ),
+            });
+        }
+
+        println!("Building translation {} ({})", translation.name, language);
+        let mut translation_config = fastn_core::Config::read(Some(root.to_string()), true, None)
+            .await?
+            .add_external_js(config.ftd_external_js.clone())
+            .add_inline_js(config.ftd_inline_js.clone())
+            .add_external_css(config.ftd_external_css.clone())
+            .add_inline_css(config.ftd_inline_css.clone());
+        translation_config.ftd_edition = config.ftd_edition.clone();
+        translation_config.multilingual_base_url = config.multilingual_base_url.clone();
+
+        build(
+            &mut translation_config,
+            None,
+            format!("{}/{}/", base_url.trim_end_matches('/'), language).as_str(),
+            ignore_failed,
+            test,
+            compress,
+        )
+        .await?;
+        fastn_core::copy_dir_all(
+            translation_config.build_dir(),
+            config.build_dir().join(language.as_str()),
+        )
+        .await?;
+    }
+    Ok(())
+}
+
+/// Renders a page for every parameter value declared in `fastn.dynamic-urls`, see
+/// `fastn_core::sitemap::DynamicUrls::prerender`
+async fn prerender_dynamic_urls(
+    config: &mut fastn_core::Config,
+    dynamic_urls: &fastn_core::sitemap::DynamicUrls,
+    file: Option<&str>,
+    base_url: &str,
+    ignore_failed: bool,
+    test: bool,
+) -> fastn_core::Result<()> {
+    for page in dynamic_urls.prerender(&config.root)? {
+        let id = format!("{}/index.ftd", page.url.trim_matches('/'));
+        if file.is_some() && file != Some(id.as_str()) {
+            continue;
+        }
+        let start = std::time::Instant::now();
+        print!(
+            "Processing {}/{} ... ",
+            config.package.name.as_str(),
+            id.as_str()
+        );
+
+        let mut main = match fastn_core::get_file(
+            config.package.name.to_string(),
+            &config.root.join(page.document.as_str()),
+            &config.root,
+        )
+        .await?
+        {
+            fastn_core::File::Ftd(main) => main,
+            _ => {
+                return Err(fastn_core::Error::PackageError {
+                    message: format!(
+                        "dynamic-urls: document of {} should be an ftd file, found: {}",
+                        page.url, page.document
+                    ),
+                })
+            }
+        };
+        main.id = id.to_string();
+        config.current_document = Some(id.to_string());
+        config.named_parameters = page.path_params;
+        config.pagination = None;
+        let resp = fastn_core::package::package_doc::process_ftd(
+            config,
+            &main,
+            base_url,
+            file.is_some(),
+            test,
+        )
+        .await;
+        let resp = match resp {
+            Ok(_) => process_pages(config, &main, base_url, file.is_some(), test).await,
+            Err(e) => Err(e),
+        };
+        config.named_parameters = vec![];
+        match (resp, ignore_failed) {
+            (Ok(_), _) => (),
+            (_, true) => {
+                println!("Failed");
+                continue;
+            }
+            (Err(e), _) => {
+                return Err(e);
+            }
+        }
+        fastn_core::utils::print_end(
+            format!("Processed {}/{}", config.package.name.as_str(), id).as_str(),
+            start,
+        );
+    }
+    Ok(())
+}
+
+/// Renders `<url>/page/<n>/` for the pages after the first of the paginated list in `main`, see
+/// `fastn_core::Pagination`
+async fn process_pages(
+    config: &mut fastn_core::Config,
+    main: &fastn_core::Document,
+    base_url: &str,
+    no_static: bool,
+    test: bool,
+) -> fastn_core::Result<()> {
+    let pages = match config.pagination.take() {
+        Some(pagination) => pagination.pages,
+        None => return Ok(()),
+    };
+    let url = fastn_core::utils::id_to_path(main.id.as_str())
+        .replace(std::path::MAIN_SEPARATOR, "/")
+        .trim_matches('/')
+        .to_string();
+    let named_parameters = config.named_parameters.clone();
+    for page in 2..=pages {
+        let mut doc = main.clone();
+        doc.id = if url.is_empty() {
+            format!("page/{}/index.ftd", page)
+        } else {
+            format!("{}/page/{}/index.ftd", url, page)
+        };
+        config.current_document = Some(doc.id.to_string());
+        config.named_parameters = named_parameters.clone();
+        config.named_parameters.push((
+            "page".to_string(),
+            ftd::Value::Integer { value: page as i64 },
+        ));
+        let resp =
+            fastn_core::package::package_doc::process_ftd(config, &doc, base_url, no_static, test)
+                .await;
+        config.named_parameters = named_parameters.clone();
+        resp?;
+    }
+    config.pagination = None;
     Ok(())
 }
 
@@ -0,0 +1,153 @@
+pub const COMMAND: &str = "design-tokens";
+
+pub fn command() -> clap::Command {
+    clap::Command::new(COMMAND)
+        .about("Convert colour schemes and typography to and from design tokens")
+        .subcommand_required(true)
+        .subcommand(
+            clap::Command::new("export")
+                .about("Write an `ftd.color-scheme` or `ftd.type-data` variable as design tokens")
+                .arg(clap::arg!(file: <FILE> "The document declaring the variable"))
+                .arg(clap::arg!(variable: <VARIABLE> "The `ftd.color-scheme` or `ftd.type-data` variable to export"))
+                .arg(clap::arg!(--format <FORMAT> "Format of the written tokens")
+                    .value_parser(["w3c", "style-dictionary", "css"])
+                    .default_value("w3c"))
+                .arg(clap::arg!(--out <PATH> "File the tokens are written to (leave empty to print them)").required(false)),
+        )
+        .subcommand(
+            clap::Command::new("import")
+                .about("Write design tokens as an `ftd.color-scheme` or `ftd.type-data` variable")
+                .arg(clap::arg!(file: <FILE> "The design tokens file, json or css"))
+                .arg(clap::arg!(--name <NAME> "Name of the variable to declare"))
+                .arg(clap::arg!(--format <FORMAT> "Format of the tokens (leave empty to guess from the file extension)")
+                    .value_parser(["w3c", "style-dictionary", "css"])
+                    .required(false))
+                .arg(clap::arg!(--out <PATH> "Document the variable is written to (leave empty to print it)").required(false)),
+        )
+}
+
+pub async fn handle_command(matches: &clap::ArgMatches) -> fastn_core::Result<()> {
+    use fastn_core::utils::ValueOf;
+
+    let config = fastn_core::Config::read(None, true, None).await?;
+    match matches.subcommand() {
+        Some(("export", matches)) => {
+            export(
+                &config,
+                matches.value_of_("file").unwrap(),
+                matches.value_of_("variable").unwrap(),
+                matches.value_of_("format").unwrap().parse()?,
+                matches.value_of_("out"),
+            )
+            .await
+        }
+        Some(("import", matches)) => {
+            let file = camino::Utf8PathBuf::from(matches.value_of_("file").unwrap());
+            let format = match matches.value_of_("format") {
+                Some(format) => format.parse()?,
+                None => fastn_core::DesignTokensFormat::from_path(&file),
+            };
+            import(
+                &config,
+                &file,
+                matches.value_of_("name").unwrap(),
+                format,
+                matches.value_of_("out"),
+            )
+            .await
+        }
+        _ => unreachable!("design-tokens: no subcommand matched"),
+    }
+}
+
+async fn export(
+    config: &fastn_core::Config,
+    file: &str,
+    variable: &str,
+    format: fastn_core::DesignTokensFormat,
+    out: Option<&str>,
+) -> fastn_core::Result<()> {
+    let id = file
+        .trim_start_matches(config.root.as_str())
+        .trim_start_matches('/');
+    let main = fastn_core::Document {
+        id: id.to_string(),
+        content: tokio::fs::read_to_string(config.root.join(id)).await?,
+        parent_path: config.root.to_string(),
+        package_name: config.package.name.clone(),
+    };
+
+    let mut lib = fastn_core::Library2022 {
+        config: config.clone(),
+        markdown: None,
+        document_id: main.id.clone(),
+        translated_data: Default::default(),
+        base_url: "/".to_string(),
+        module_package_map: Default::default(),
+    };
+    let mut doc_content =
+        config
+            .package
+            .get_prefixed_body(main.content.as_str(), main.id.as_str(), true);
+    doc_content = config
+        .package
+        .fix_imports_in_body(doc_content.as_str(), main.id.as_str())?;
+    let line_number = doc_content.split('\n').count() - main.content.split('\n').count();
+    let document = fastn_core::doc::interpret_helper(
+        main.id_with_package().as_str(),
+        doc_content.as_str(),
+        &mut lib,
+        "/",
+        false,
+        line_number,
+    )
+    .await?;
+
+    let doc = ftd::interpreter::TDoc::new(&document.name, &document.aliases, &document.data);
+    let tokens = fastn_core::design_tokens::Tokens::from_value(doc.get_value(0, variable)?, &doc)?;
+    check(&tokens, variable)?;
+
+    let rendered = tokens.render(format)?;
+    match out {
+        Some(out) => {
+            fastn_core::utils::update(config.root.join(out), rendered.as_bytes()).await?;
+            println!("{}: exported `{}`", out, variable);
+        }
+        None => println!("{}", rendered),
+    }
+    Ok(())
+}
+
+async fn import(
+    config: &fastn_core::Config,
+    file: &camino::Utf8Path,
+    name: &str,
+    format: fastn_core::DesignTokensFormat,
+    out: Option<&str>,
+) -> fastn_core::Result<()> {
+    let tokens = fastn_core::design_tokens::Tokens::parse(
+        tokio::fs::read_to_string(file).await?.as_str(),
+        format,
+    )?;
+    check(&tokens, file.as_str())?;
+
+    let ftd = tokens.to_ftd(name);
+    match out {
+        Some(out) => {
+            fastn_core::utils::update(config.root.join(out), ftd.as_bytes()).await?;
+            println!("{}: imported `{}` as `{}`", out, file, name);
+        }
+        None => print!("{}", ftd),
+    }
+    Ok(())
+}
+
+fn check(tokens: &fastn_core::design_tokens::Tokens, source: &str) -> fastn_core::Result<()> {
+    let problems = tokens.validate();
+    if problems.is_empty() {
+        return Ok(());
+    }
+    Err(fastn_core::Error::UsageError {
+        message: format!("{}:\n  {}", source, problems.join("\n  ")),
+    })
+}
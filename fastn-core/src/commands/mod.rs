@@ -5,6 +5,7 @@ pub mod clone;
 pub mod close_cr;
 pub mod create_cr;
 pub mod create_package;
+pub mod design_tokens;
 pub mod diff;
 pub mod edit;
 pub mod mark_resolved;
@@ -21,5 +22,6 @@ pub mod stop_tracking;
 pub mod sync;
 pub mod sync2;
 pub mod sync_status;
+pub mod translate;
 pub mod translation_status;
 pub mod update;
@@ -44,6 +44,23 @@ async fn serve_file(
     }
 
     let f = match config.get_file_and_package_by_id(path.as_str()).await {
+        Ok(f) => Ok(f),
+        // `/blog/page/2/` is the second page of `/blog/`, see `fastn_core::pagination`
+        Err(e) => match fastn_core::pagination::split_page_url(path.as_str()) {
+            Some((base, page)) => match config.get_file_and_package_by_id(base.as_str()).await {
+                Ok(f) => {
+                    config.named_parameters.push((
+                        "page".to_string(),
+                        ftd::Value::Integer { value: page as i64 },
+                    ));
+                    Ok(f)
+                }
+                Err(_) => Err(e),
+            },
+            None => Err(e),
+        },
+    };
+    let f = match f {
         Ok(f) => f,
         Err(e) => {
             tracing::error!(
@@ -114,6 +131,26 @@ async fn serve_file(
                     fastn_core::http::ok(main_document.content.as_bytes().to_vec())
                 };
             }
+            let cache_key = match config.request.as_ref() {
+                Some(req) if req.method() == "GET" && !has_redirect_url => {
+                    match fastn_core::render_cache::key(config, main_document.id.as_str()).await {
+                        Ok(key) => Some(key),
+                        Err(e) => {
+                            tracing::error!(msg = "render-cache-key-error", error = %e);
+                            None
+                        }
+                    }
+                }
+                _ => None,
+            };
+            if let (Some(key), Some(req)) = (cache_key.as_ref(), config.request.as_ref()) {
+                let entry = fastn_core::render_cache::get(key);
+                fastn_core::metrics::record_cache_lookup("render", entry.is_some());
+                if let Some(entry) = entry {
+                    return fastn_core::render_cache::response(req, entry);
+                }
+            }
+            config.request_dependent = false;
             match fastn_core::package::package_doc::read_ftd(
                 config,
                 &main_document,
@@ -123,15 +160,25 @@ async fn serve_file(
             )
             .await
             {
-                Ok(r) => match has_redirect_url {
-                    true => fastn_core::http::redirect_with_content_type(
-                        r,
-                        mime_guess::mime::TEXT_HTML_UTF_8,
-                        current_path.as_str(),
-                    ),
-                    false => {
-                        fastn_core::http::ok_with_content_type(r, mime_guess::mime::TEXT_HTML_UTF_8)
+                Ok(r) => match (cache_key, config.request.as_ref()) {
+                    // Documents without request dependent processors are rendered once
+                    (Some(key), Some(req)) if !config.request_dependent => {
+                        fastn_core::render_cache::response(
+                            req,
+                            fastn_core::render_cache::insert(key, r),
+                        )
                     }
+                    _ => match has_redirect_url {
+                        true => fastn_core::http::redirect_with_content_type(
+                            r,
+                            mime_guess::mime::TEXT_HTML_UTF_8,
+                            current_path.as_str(),
+                        ),
+                        false => fastn_core::http::ok_with_content_type(
+                            r,
+                            mime_guess::mime::TEXT_HTML_UTF_8,
+                        ),
+                    },
                 },
                 Err(e) => {
                     tracing::error!(
@@ -143,14 +190,23 @@ async fn serve_file(
                 }
             }
         }
-        fastn_core::File::Image(image) => fastn_core::http::ok_with_content_type(
-            image.content,
-            guess_mime_type(image.id.as_str()),
-        ),
-        fastn_core::File::Static(s) => fastn_core::http::ok(s.content),
-        fastn_core::File::Code(s) => fastn_core::http::ok_with_content_type(
+        fastn_core::File::Image(s) | fastn_core::File::Static(s) => {
+            fastn_core::static_asset::response(
+                &asset_request(config),
+                s.id.as_str(),
+                s.content,
+                Some(s.base_path.join(s.id.as_str()).as_path()),
+                false,
+                config.static_file_hash(&s.base_path, s.id.as_str()),
+            )
+        }
+        fastn_core::File::Code(s) => fastn_core::static_asset::response(
+            &asset_request(config),
+            s.id.as_str(),
             s.content.into_bytes(),
-            guess_mime_type(s.id.as_str()),
+            None,
+            false,
+            config.static_file_hash(camino::Utf8Path::new(s.parent_path.as_str()), s.id.as_str()),
         ),
         _ => {
             tracing::error!(msg = "unknown handler", path = path.as_str());
@@ -219,6 +275,14 @@ async fn serve_cr_file(
     }
 }
 
+fn asset_request(config: &fastn_core::Config) -> fastn_core::static_asset::AssetRequest {
+    config
+        .request
+        .as_ref()
+        .map(fastn_core::static_asset::AssetRequest::from_request)
+        .unwrap_or_default()
+}
+
 fn guess_mime_type(path: &str) -> mime_guess::Mime {
     mime_guess::from_path(path).first_or_octet_stream()
 }
@@ -241,14 +305,24 @@ async fn serve_fastn_file(config: &fastn_core::Config) -> fastn_core::http::Resp
 }
 
 #[tracing::instrument(skip_all)]
-async fn static_file(file_path: camino::Utf8PathBuf) -> fastn_core::http::Response {
+async fn static_file(
+    req: &fastn_core::http::Request,
+    file_path: camino::Utf8PathBuf,
+) -> fastn_core::http::Response {
     if !file_path.exists() {
         tracing::error!(msg = "no such static file ({})", path = file_path.as_str());
         return fastn_core::not_found!("no such static file ({})", file_path);
     }
 
     match tokio::fs::read(file_path.as_path()).await {
-        Ok(r) => fastn_core::http::ok_with_content_type(r, guess_mime_type(file_path.as_str())),
+        Ok(r) => fastn_core::static_asset::response(
+            &fastn_core::static_asset::AssetRequest::from_request(req),
+            file_path.as_str(),
+            r,
+            Some(file_path.as_path()),
+            false,
+            None,
+        ),
         Err(e) => {
             tracing::error!(
                 msg = "file-system-error ({})",
@@ -272,9 +346,11 @@ pub async fn serve(
     let _lock = LOCK.read().await;
     // TODO: remove unwrap
     let path: camino::Utf8PathBuf = req.path().replacen('/', "", 1).parse().unwrap();
+    let has_csrf_cookie = req.cookie(fastn_core::form::CSRF_COOKIE).is_some();
+    let req_is_secure = req.scheme() == "https";
     let favicon = camino::Utf8PathBuf::new().join("favicon.ico");
-    let response = if path.eq(&favicon) {
-        static_file(favicon).await
+    let mut response = if path.eq(&favicon) {
+        static_file(&req, favicon).await
     } else if path.eq(&camino::Utf8PathBuf::new().join("FASTN.ftd")) {
         let config = fastn_core::Config::read(None, false, Some(&req))
             .await
@@ -296,6 +372,15 @@ pub async fn serve(
             .add_external_css(external_css)
             .add_inline_css(inline_css);
 
+        // send readers to the translation in the language they prefer
+        if let Some(req) = config.request.as_ref().filter(|r| r.method() == "GET") {
+            if let Some(response) =
+                fastn_core::multilingual::negotiate(&config, req, path.as_str()).await
+            {
+                return Ok(response);
+            }
+        }
+
         serve_file(&mut config, &path.join("/")).await
     } else if let Some(cr_number) = fastn_core::cr::get_cr_path_from_url(path.as_str()) {
         let mut config = fastn_core::Config::read(None, false, Some(&req))
@@ -365,11 +450,103 @@ pub async fn serve(
                     return Ok(resp);
                 }
             }
+
+            // send readers to the translation in the language they prefer
+            if let Some(req) = config.request.as_ref() {
+                if let Some(response) =
+                    fastn_core::multilingual::negotiate(&config, req, path.as_str()).await
+                {
+                    return Ok(response);
+                }
+            }
+        }
+
+        // Routes declared with `fastn.form` in FASTN.ftd
+        if let Some(form) = config.package.form(path.as_str()).cloned() {
+            let req = if let Some(r) = config.request.clone() {
+                r
+            } else {
+                return Ok(fastn_core::server_error!("request not set"));
+            };
+            if !config.can_read(&req, path.as_str(), true).await? {
+                return Ok(fastn_core::unauthorised!(
+                    "You are unauthorized to access: {}",
+                    path
+                ));
+            }
+            return fastn_core::form::handle(&config, req, &form).await;
+        }
+
+        // Routes with a `backend` in the sitemap are handled by that WASM module
+        if let Some(wasm_module) = fastn_core::wasm::sitemap_backend(&config, path.as_str()) {
+            let req = if let Some(r) = config.request.clone() {
+                r
+            } else {
+                return Ok(fastn_core::server_error!("request not set"));
+            };
+            if !config.can_read(&req, path.as_str(), true).await? {
+                return Ok(fastn_core::unauthorised!(
+                    "You are unauthorized to access: {}",
+                    path
+                ));
+            }
+            return Ok(fastn_core::wasm::handle_wasm(
+                req,
+                wasm_module,
+                &config.package,
+                config.root.clone(),
+            )
+            .await);
         }
 
         // if request goes with mount-point /todos/api/add-todo/
         // so it should say not found and pass it to proxy
         let file_response = serve_file(&mut config, path.as_path()).await;
+
+        // Fallback to the package's `backend.wasm` in case of no successful response, for
+        // packages with `backend: true` in FASTN.ftd
+        if file_response.status() == actix_web::http::StatusCode::NOT_FOUND {
+            let package = config.find_package_by_id(path.as_str()).await?.1;
+            if package.backend {
+                let wasm_module = config.get_root_for_package(&package).join("backend.wasm");
+                // Set the temp path to and do `get_file_and_package_by_id` to retrieve the backend.wasm
+                // File for the particular dependency package
+                let wasm_module_path = format!("-/{}/backend.wasm", package.name,);
+                match config
+                    .get_file_and_package_by_id(wasm_module_path.as_str())
+                    .await
+                {
+                    Ok(_) => {
+                        let package_root = config.get_root_for_package(&package);
+                        let req = if let Some(r) = config.request.clone() {
+                            r
+                        } else {
+                            return Ok(fastn_core::server_error!("request not set"));
+                        };
+                        if !config.can_read(&req, path.as_str(), true).await? {
+                            return Ok(fastn_core::unauthorised!(
+                                "You are unauthorized to access: {}",
+                                path
+                            ));
+                        }
+                        return Ok(fastn_core::wasm::handle_wasm(
+                            req,
+                            wasm_module,
+                            &package,
+                            package_root,
+                        )
+                        .await);
+                    }
+                    // the not found response is sent as is
+                    Err(e) => tracing::warn!(
+                        msg = "backend.wasm not found",
+                        package = package.name.as_str(),
+                        error = %e
+                    ),
+                }
+            }
+        }
+
         // If path is not present in sitemap then pass it to proxy
         // TODO: Need to handle other package URL as well, and that will start from `-`
         // and all the static files starts with `-`
@@ -386,23 +563,45 @@ pub async fn serve(
             // TODO: Check if path exists in dynamic urls also, otherwise pass to endpoint
             // Already checked in the above method serve_file
             tracing::info!("executing proxy: path: {}", &path);
-            let (package_name, url, mut conf) =
-                fastn_core::config::utils::get_clean_url(&config, path.as_str())?;
+            let rule = config.package.proxy_rule(path.as_str()).cloned();
+            let (package_name, url, mut conf) = match rule
+                .as_ref()
+                .and_then(|rule| rule.endpoint.as_ref().map(|ep| (rule, ep)))
+            {
+                // Rules with their own endpoint forward the request path as is
+                Some((rule, endpoint)) => (
+                    None,
+                    url::Url::parse(
+                        format!(
+                            "{}{}",
+                            endpoint.trim_end_matches('/'),
+                            rule.rewrite_path(format!("/{}", path).as_str())
+                        )
+                        .as_str(),
+                    )?,
+                    std::collections::HashMap::new(),
+                ),
+                None => fastn_core::config::utils::get_clean_url(&config, path.as_str())?,
+            };
             let package_name = package_name.unwrap_or_else(|| config.package.name.to_string());
+            let upstream_path = match rule.as_ref() {
+                Some(rule) if rule.endpoint.is_none() => rule.rewrite_path(url.path()),
+                _ => url.path().to_string(),
+            };
 
             let host = if let Some(port) = url.port() {
                 format!("{}://{}:{}", url.scheme(), url.host_str().unwrap(), port)
             } else {
                 format!("{}://{}", url.scheme(), url.host_str().unwrap())
             };
-            let req = if let Some(r) = config.request {
+            let req = if let Some(r) = config.request.clone() {
                 r
             } else {
                 tracing::error!(msg = "request not set");
                 return Ok(fastn_core::server_error!("request not set"));
             };
 
-            // TODO: read app config and send them to service as header
+            // `conf` holds the config of the app, which is sent to the service as headers
             // Adjust x-fastn header from based on the platform and the requested field
             if let Some(user_id) = conf.get("user-id") {
                 match user_id.split_once('-') {
@@ -421,37 +620,33 @@ pub async fn serve(
                 }
             }
 
+            if let Some(rule) = rule.as_ref() {
+                fastn_core::proxy::add_identity_headers(
+                    &config,
+                    &req,
+                    rule,
+                    upstream_path.as_str(),
+                    &mut conf,
+                )
+                .await?;
+            }
+
             return fastn_core::proxy::get_out(
                 host.as_str(),
                 req,
-                url.path(),
+                upstream_path.as_str(),
                 package_name.as_str(),
                 &conf,
+                rule.as_ref(),
             )
             .await;
         }
 
-        // Fallback to WASM execution in case of no successful response
-        // TODO: This is hacky. Use the sitemap eventually.
-        // if file_response.status() == actix_web::http::StatusCode::NOT_FOUND {
-        //     let package = config.find_package_by_id(path.as_str()).await.unwrap().1;
-        //     let wasm_module = config.get_root_for_package(&package).join("backend.wasm");
-        //     // Set the temp path to and do `get_file_and_package_by_id` to retrieve the backend.wasm
-        //     // File for the particular dependency package
-        //     let wasm_module_path = format!("-/{}/backend.wasm", package.name,);
-        //     config
-        //         .get_file_and_package_by_id(wasm_module_path.as_str())
-        //         .await?;
-        //     let req = if let Some(r) = config.request {
-        //         r
-        //     } else {
-        //         return Ok(fastn_core::server_error!("request not set"));
-        //     };
-        //     fastn_core::wasm::handle_wasm(req, wasm_module, config.package.backend_headers).await
-        // }
-
         file_response
     };
+    if !has_csrf_cookie {
+        fastn_core::form::set_csrf_cookie(&mut response, req_is_secure);
+    }
     Ok(response)
 }
 
@@ -588,40 +783,52 @@ pub async fn create_cr_page(
     fastn_core::apis::cr::create_cr_page(req).await
 }
 
+pub async fn translation_status(
+    req: fastn_core::http::Request,
+) -> fastn_core::Result<fastn_core::http::Response> {
+    let _lock = LOCK.read().await;
+    fastn_core::apis::translation_status(req).await
+}
+
 struct AppData {
     edition: Option<String>,
     external_js: Vec<String>,
     inline_js: Vec<String>,
     external_css: Vec<String>,
     inline_css: Vec<String>,
+    limiter: std::sync::Arc<fastn_core::rate_limit::Limiter>,
 }
 
 fn handle_default_route(req: &actix_web::HttpRequest) -> Option<fastn_core::http::Response> {
-    if req
+    // The names have the hash of the content, so these are cached forever
+    let (name, content) = if req
         .path()
         .ends_with(fastn_core::utils::hashed_default_css_name().as_str())
     {
-        return Some(
-            actix_web::HttpResponse::Ok()
-                .content_type(mime_guess::mime::TEXT_CSS)
-                .body(ftd::css()),
-        );
+        (
+            fastn_core::utils::hashed_default_css_name(),
+            ftd::css().to_string(),
+        )
     } else if req
         .path()
         .ends_with(fastn_core::utils::hashed_default_js_name().as_str())
     {
-        return Some(
-            actix_web::HttpResponse::Ok()
-                .content_type(mime_guess::mime::TEXT_JAVASCRIPT)
-                .body(format!(
-                    "{}\n\n{}",
-                    ftd::build_js(),
-                    fastn_core::fastn_2022_js()
-                )),
-        );
-    }
+        (
+            fastn_core::utils::hashed_default_js_name(),
+            format!("{}\n\n{}", ftd::build_js(), fastn_core::fastn_2022_js()),
+        )
+    } else {
+        return None;
+    };
 
-    None
+    Some(fastn_core::static_asset::response(
+        &fastn_core::static_asset::AssetRequest::from_actix(req),
+        name.as_str(),
+        content.into_bytes(),
+        None,
+        true,
+        None,
+    ))
 }
 
 #[tracing::instrument(skip_all)]
@@ -632,6 +839,12 @@ async fn route(
 ) -> fastn_core::Result<fastn_core::http::Response> {
     tracing::info!(method = req.method().as_str(), uri = req.path());
 
+    // bodies without a content length are only checked once read
+    let max_body_size = app_data.limiter.max_body_size(req.path());
+    if body.len() > max_body_size {
+        return Ok(fastn_core::rate_limit::payload_too_large(max_body_size));
+    }
+
     if let Some(default_response) = handle_default_route(&req) {
         return Ok(default_response);
     }
@@ -660,7 +873,9 @@ async fn route(
         ("post", "/-/create-cr/") => create_cr(req).await,
         ("get", "/-/create-cr-page/") => create_cr_page(req).await,
         ("get", "/-/clear-cache/") => clear_cache(req).await,
+        ("get", "/-/translation-status/") => translation_status(req).await,
         ("get", "/-/poll/") => fastn_core::watcher::poll().await,
+        ("get", "/-/metrics/") => Ok(fastn_core::metrics::metrics()),
         (_, _) => {
             serve(
                 req,
@@ -675,6 +890,109 @@ async fn route(
     }
 }
 
+/// What is known of a request before it is handled, for the access log and the metrics
+struct RequestLog {
+    start: std::time::Instant,
+    time: chrono::DateTime<chrono::Local>,
+    ip: Option<String>,
+    method: String,
+    path: String,
+    query: String,
+    version: String,
+    cookies: std::collections::HashMap<String, String>,
+    referer: Option<String>,
+    user_agent: Option<String>,
+}
+
+impl RequestLog {
+    fn new(req: &actix_web::dev::ServiceRequest) -> RequestLog {
+        let header = |name: actix_web::http::header::HeaderName| {
+            req.headers()
+                .get(name)
+                .and_then(|v| v.to_str().ok())
+                .map(ToString::to_string)
+        };
+        RequestLog {
+            start: std::time::Instant::now(),
+            time: chrono::Local::now(),
+            ip: req.peer_addr().map(|x| x.ip().to_string()),
+            method: req.method().to_string(),
+            path: req.path().to_string(),
+            query: req.query_string().to_string(),
+            version: format!("{:?}", req.version()),
+            cookies: req
+                .cookies()
+                .map(|cookies| {
+                    cookies
+                        .iter()
+                        .map(|c| (c.name().to_string(), c.value().to_string()))
+                        .collect()
+                })
+                .unwrap_or_default(),
+            referer: header(actix_web::http::header::REFERER),
+            user_agent: header(actix_web::http::header::USER_AGENT),
+        }
+    }
+
+    async fn finish(
+        self,
+        response: &Result<actix_web::dev::ServiceResponse, actix_web::Error>,
+        format: Option<fastn_core::AccessLogFormat>,
+        packages: &[(String, String)],
+    ) {
+        use actix_web::body::MessageBody;
+
+        let elapsed = self.start.elapsed();
+        let (status, bytes) = match response {
+            Ok(response) => (
+                response.status().as_u16(),
+                match response.response().body().size() {
+                    actix_web::body::BodySize::Sized(size) => Some(size),
+                    _ => None,
+                },
+            ),
+            Err(e) => (e.as_response_error().status_code().as_u16(), None),
+        };
+        fastn_core::metrics::record_request(
+            self.path.as_str(),
+            self.method.as_str(),
+            status,
+            elapsed,
+        );
+
+        let format = match format {
+            Some(format) => format,
+            None => return,
+        };
+        let identity = fastn_core::auth::get_logged_in_identities(&self.cookies)
+            .await
+            .into_iter()
+            .map(|(provider, username)| format!("{}:{}", provider, username))
+            .collect::<Vec<_>>()
+            .join(",");
+        fastn_core::access_log::Entry {
+            time: self.time,
+            ip: self.ip,
+            route: fastn_core::metrics::route(self.path.as_str()),
+            package: packages
+                .iter()
+                .find(|(mount_point, _)| self.path.starts_with(mount_point.as_str()))
+                .map(|(_, package)| package.to_string()),
+            method: self.method,
+            path: self.path,
+            query: self.query,
+            version: self.version,
+            status,
+            bytes,
+            latency_ms: elapsed.as_secs_f64() * 1000.0,
+            identity: Some(identity).filter(|i| !i.is_empty()),
+            referer: self.referer,
+            user_agent: self.user_agent,
+        }
+        .write(format);
+    }
+}
+
 #[allow(clippy::too_many_arguments)]
 pub async fn listen(
     bind_address: &str,
@@ -685,7 +1003,12 @@ pub async fn listen(
     inline_js: Vec<String>,
     external_css: Vec<String>,
     inline_css: Vec<String>,
+    tls: Option<fastn_core::Tls>,
+    access_log: Option<fastn_core::AccessLogFormat>,
+    rate_limit: Option<u32>,
+    max_body_size: usize,
 ) -> fastn_core::Result<()> {
+    use actix_web::dev::Service;
     use colored::Colorize;
 
     if package_download_base_url.is_some() {
@@ -706,6 +1029,15 @@ pub async fn listen(
         }
     }
 
+    // The package db is loaded once, and then kept up to date by the watcher
+    match fastn_core::Config::read(None, false, None).await {
+        Ok(config) => fastn_core::package::initializer::initialize(&config.root).await,
+        Err(e) => tracing::warn!(msg = "failed to read the package", error = %e),
+    }
+
+    // Rendered documents are cached till a file changes
+    fastn_core::watcher::start();
+
     let tcp_listener = match fastn_core::http::get_available_port(port, bind_address) {
         Some(listener) => listener,
         None => {
@@ -728,7 +1060,32 @@ You can try without providing port, it will automatically pick unused port."#,
 
     env_logger::init_from_env(env_logger::Env::new().default_filter_or("info"));
 
+    let config = fastn_core::Config::read(None, false, None).await.ok();
+
+    // (mount point, package name), longest mount point first, for the access log
+    let packages = std::sync::Arc::new(match config.as_ref() {
+        Some(config) => {
+            let mut packages = config
+                .package
+                .apps
+                .iter()
+                .map(|a| (a.mount_point.to_string(), a.package.name.to_string()))
+                .collect::<Vec<_>>();
+            packages.sort_by_key(|(mount_point, _)| std::cmp::Reverse(mount_point.len()));
+            packages.push(("/".to_string(), config.package.name.to_string()));
+            packages
+        }
+        None => vec![],
+    });
+    let limiter = std::sync::Arc::new(fastn_core::rate_limit::Limiter::new(
+        config.as_ref().map(|c| &c.package),
+        rate_limit,
+        max_body_size,
+    ));
+
     let app = move || {
+        let packages = packages.clone();
+        let limiter = limiter.clone();
         actix_web::App::new()
             .app_data(actix_web::web::Data::new(AppData {
                 edition: edition.clone(),
@@ -736,26 +1093,53 @@ You can try without providing port, it will automatically pick unused port."#,
                 inline_js: inline_js.clone(),
                 external_css: external_css.clone(),
                 inline_css: inline_css.clone(),
+                limiter: limiter.clone(),
             }))
-            .wrap(
-                actix_web::middleware::Logger::new(
-                    r#""%r" %Ts %s %b %a "%{Referer}i" "%{User-Agent}i""#,
-                )
-                .log_target(""),
-            )
+            .app_data(actix_web::web::PayloadConfig::new(
+                limiter.largest_body_size(),
+            ))
+            .wrap_fn(move |req, srv| match limiter.check(&req) {
+                Some(response) => {
+                    futures::future::Either::Left(futures::future::ok(req.into_response(response)))
+                }
+                None => futures::future::Either::Right(srv.call(req)),
+            })
+            .wrap_fn(move |req, srv| {
+                let log = RequestLog::new(&req);
+                let packages = packages.clone();
+                let response = srv.call(req);
+                async move {
+                    let response = response.await;
+                    log.finish(&response, access_log, packages.as_slice()).await;
+                    response
+                }
+            })
             .route("/{path:.*}", actix_web::web::route().to(route))
     };
 
+    let acceptor = match tls.as_ref() {
+        Some(tls) => Some(tls.acceptor(bind_address)?),
+        None => None,
+    };
+
     println!("### Server Started ###");
     println!(
-        "Go to: http://{}:{}",
+        "Go to: {}://{}:{}",
+        if acceptor.is_some() { "https" } else { "http" },
         bind_address,
         tcp_listener.local_addr()?.port()
     );
-    actix_web::HttpServer::new(app)
-        .listen(tcp_listener)?
-        .run()
-        .await?;
+    if matches!(tls, Some(fastn_core::Tls::SelfSigned)) {
+        fastn_core::warning!("using a self-signed certificate, only use it for development");
+    }
+    let server = actix_web::HttpServer::new(app);
+    match acceptor {
+        // http/2 is negotiated over tls (alpn)
+        Some(acceptor) => server.listen_openssl(tcp_listener, acceptor)?,
+        None => server.listen(tcp_listener)?,
+    }
+    .run()
+    .await?;
     Ok(())
 }
 
@@ -0,0 +1,171 @@
+pub const COMMAND: &str = "translate";
+
+pub fn command() -> clap::Command {
+    clap::Command::new(COMMAND)
+        .about("Extract the strings of the original package to translate, and merge them back")
+        .subcommand_required(true)
+        .subcommand(
+            clap::Command::new("extract")
+                .about("Write the translatable strings of the original documents to XLIFF or PO files")
+                .arg(clap::arg!(file: [FILE]... "The original document(s) to extract (leave empty to extract all)"))
+                .arg(clap::arg!(--format <FORMAT> "Format of the written files")
+                    .value_parser(["xliff", "po"])
+                    .default_value("xliff"))
+                .arg(clap::arg!(--out <DIR> "Directory the files are written to").default_value("translations")),
+        )
+        .subcommand(
+            clap::Command::new("merge")
+                .about("Write the translated documents from translated XLIFF or PO files")
+                .arg(clap::arg!(file: [FILE]... "The XLIFF or PO file(s) to merge (leave empty to merge all files in --out)"))
+                .arg(clap::arg!(--out <DIR> "Directory the files were written to").default_value("translations")),
+        )
+}
+
+pub async fn handle_command(matches: &clap::ArgMatches) -> fastn_core::Result<()> {
+    use fastn_core::utils::ValueOf;
+
+    let config = fastn_core::Config::read(None, true, None).await?;
+    if !config.is_translation_package() {
+        return Err(fastn_core::Error::UsageError {
+            message: "`translate` works only when `translation-of` is set.".to_string(),
+        });
+    }
+    match matches.subcommand() {
+        Some(("extract", matches)) => {
+            extract(
+                &config,
+                matches.values_of_("file"),
+                matches.value_of_("format").unwrap().parse()?,
+                matches.value_of_("out").unwrap(),
+            )
+            .await
+        }
+        Some(("merge", matches)) => {
+            merge(
+                &config,
+                matches.values_of_("file"),
+                matches.value_of_("out").unwrap(),
+            )
+            .await
+        }
+        _ => unreachable!("translate: no subcommand matched"),
+    }
+}
+
+async fn extract(
+    config: &fastn_core::Config,
+    files: Vec<String>,
+    format: fastn_core::TranslationFormat,
+    out: &str,
+) -> fastn_core::Result<()> {
+    let original_path = config.original_path()?;
+    let snapshots = fastn_core::snapshot::get_latest_snapshots(&original_path).await?;
+    let memory = fastn_core::translate::TranslationMemory::read(config).await?;
+    let source_language = config
+        .package
+        .translation_of
+        .as_ref()
+        .as_ref()
+        .and_then(|o| o.language.clone());
+
+    for id in snapshots.keys() {
+        if !id.ends_with(".ftd") || (!files.is_empty() && !files.contains(id)) {
+            continue;
+        }
+        let content = tokio::fs::read_to_string(original_path.join(id)).await?;
+        let units = fastn_core::translate::extract_units(content.as_str(), id.as_str())?;
+        let translations = units
+            .into_iter()
+            .map(|unit| fastn_core::translate::Translation {
+                target: memory.get(unit.source.as_str()).cloned(),
+                id: unit.id,
+                source: unit.source,
+            })
+            .collect::<Vec<_>>();
+        let pending = translations.iter().filter(|t| t.target.is_none()).count();
+        let file = fastn_core::translate::TranslationFile {
+            document: id.to_string(),
+            source_language: source_language.clone(),
+            target_language: config.package.language.clone(),
+            translations,
+        };
+
+        let path = config.root.join(out).join(format!(
+            "{}.{}",
+            id.trim_end_matches(".ftd"),
+            format.extension()
+        ));
+        fastn_core::utils::update(&path, file.render(format).as_bytes()).await?;
+        println!(
+            "{}: {} strings, {} to translate",
+            path,
+            file.translations.len(),
+            pending
+        );
+    }
+    Ok(())
+}
+
+async fn merge(
+    config: &fastn_core::Config,
+    files: Vec<String>,
+    out: &str,
+) -> fastn_core::Result<()> {
+    let original_path = config.original_path()?;
+    let mut memory = fastn_core::translate::TranslationMemory::read(config).await?;
+
+    let files: Vec<camino::Utf8PathBuf> = if files.is_empty() {
+        ignore::WalkBuilder::new(config.root.join(out))
+            .build()
+            .flatten()
+            .filter_map(|e| camino::Utf8PathBuf::from_path_buf(e.into_path()).ok())
+            .filter(|p| p.is_file())
+            .collect()
+    } else {
+        files.iter().map(camino::Utf8PathBuf::from).collect()
+    };
+
+    for path in files {
+        let format = match fastn_core::TranslationFormat::from_path(&path) {
+            Some(format) => format,
+            None => continue,
+        };
+        let file = fastn_core::translate::TranslationFile::parse(
+            tokio::fs::read_to_string(&path).await?.as_str(),
+            format,
+        )?;
+        let content = tokio::fs::read_to_string(original_path.join(&file.document)).await?;
+        let units = fastn_core::translate::extract_units(content.as_str(), &file.document)?;
+
+        // a unit whose source changed since the file was extracted is only translated if the
+        // translation memory knows its new source
+        let mut targets = std::collections::BTreeMap::new();
+        for unit in units.iter() {
+            let target = file
+                .translations
+                .iter()
+                .find(|t| t.id.eq(&unit.id) && t.source.trim().eq(unit.source.as_str()))
+                .and_then(|t| t.target.clone())
+                .or_else(|| memory.get(unit.source.as_str()).cloned());
+            if let Some(target) = target {
+                memory.insert(unit.source.as_str(), target.as_str());
+                targets.insert(unit.id.to_string(), target);
+            }
+        }
+
+        let (translated, missed) =
+            fastn_core::translate::merge_units(content.as_str(), &units, &targets);
+        for id in missed.iter() {
+            fastn_core::warning!("{}: could not find the source of unit `{}`", path, id);
+        }
+        fastn_core::utils::update(config.root.join(&file.document), translated.as_bytes()).await?;
+        println!(
+            "{}: {} of {} strings translated",
+            file.document,
+            targets.len() - missed.len(),
+            units.len()
+        );
+    }
+
+    memory.write(config).await
+}
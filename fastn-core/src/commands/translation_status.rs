@@ -1,9 +1,13 @@
-pub async fn translation_status(config: &fastn_core::Config) -> fastn_core::Result<()> {
+pub async fn translation_status(
+    config: &fastn_core::Config,
+    json: bool,
+    fail_above: Option<usize>,
+) -> fastn_core::Result<()> {
     // it can be original package or translation
-    if config.is_translation_package() {
-        translation_package_status(config).await?;
+    let pending = if config.is_translation_package() {
+        translation_package_status(config, json).await?
     } else if !config.package.translations.is_empty() {
-        original_package_status(config).await?;
+        original_package_status(config, json).await?
     } else {
         return Err(fastn_core::Error::UsageError {
             message:
@@ -11,25 +15,61 @@ pub async fn translation_status(config: &fastn_core::Config) -> fastn_core::Resu
                     .to_string(),
         });
     };
-    Ok(())
+    match fail_above {
+        Some(allowed) if pending > allowed => Err(fastn_core::Error::GenericError(format!(
+            "{} documents are missing or out-dated, more than the allowed {}",
+            pending, allowed
+        ))),
+        _ => Ok(()),
+    }
 }
 
-async fn translation_package_status(config: &fastn_core::Config) -> fastn_core::Result<()> {
-    let original_snapshots =
-        fastn_core::snapshot::get_latest_snapshots(&config.original_path()?).await?;
-    let translation_status = get_translation_status(&original_snapshots, &config.root)?;
-    print_translation_status(&translation_status);
-    Ok(())
+/// Returns the number of missing and out-dated documents
+async fn translation_package_status(
+    config: &fastn_core::Config,
+    json: bool,
+) -> fastn_core::Result<usize> {
+    let report = get_translation_status_report(config).await?;
+    if json {
+        println!("{}", serde_json::to_string_pretty(&report)?);
+    } else {
+        print_translation_status(&report);
+    }
+    Ok((report.summary.missing + report.summary.out_dated) as usize)
 }
 
-async fn original_package_status(config: &fastn_core::Config) -> fastn_core::Result<()> {
+/// Returns the number of missing and out-dated documents, across all translations
+async fn original_package_status(
+    config: &fastn_core::Config,
+    json: bool,
+) -> fastn_core::Result<usize> {
+    #[derive(serde::Serialize)]
+    struct Translation<'a> {
+        package: &'a str,
+        language: Option<&'a str>,
+        summary: &'a fastn_core::translation::TranslationStatusSummary,
+    }
+
+    let mut translations = vec![];
+    let mut pending = 0;
     for translation in config.package.translations.iter() {
         if let Some(ref status) = translation.translation_status_summary {
-            println!("Status for `{}` package:", translation.name);
-            println!("{}", status.to_string());
+            pending += (status.missing + status.out_dated) as usize;
+            if !json {
+                println!("Status for `{}` package:", translation.name);
+                println!("{}", status.to_string());
+            }
+            translations.push(Translation {
+                package: translation.name.as_str(),
+                language: translation.language.as_deref(),
+                summary: status,
+            });
         }
     }
-    Ok(())
+    if json {
+        println!("{}", serde_json::to_string_pretty(&translations)?);
+    }
+    Ok(pending)
 }
 
 pub(crate) fn get_translation_status(
@@ -65,17 +105,131 @@ pub(crate) fn get_translation_status(
     Ok(translation_status)
 }
 
-fn print_translation_status(
-    translation_status: &std::collections::BTreeMap<String, TranslationStatus>,
-) {
+/// Status of every document of the original package in the current translation package, with
+/// the dates it was last marked up to date and, for out-dated documents, the changes in the
+/// original since then
+pub(crate) async fn get_translation_status_report(
+    config: &fastn_core::Config,
+) -> fastn_core::Result<TranslationStatusReport> {
+    let original_snapshots =
+        fastn_core::snapshot::get_latest_snapshots(&config.original_path()?).await?;
+    let translation_status = get_translation_status(&original_snapshots, &config.root)?;
+
+    let mut summary = fastn_core::translation::TranslationStatusSummary {
+        last_modified_on: fastn_core::utils::get_last_modified_on(&config.root).await,
+        ..Default::default()
+    };
+    let mut documents = vec![];
     for (file, status) in translation_status {
-        println!("{}: {}", status.as_str(), file);
+        let original_latest = original_snapshots[&file];
+        let mut document = DocumentStatus {
+            file,
+            status,
+            last_marked_on: None,
+            original_latest: fastn_core::utils::nanos_to_rfc3339(&original_latest),
+            translated_latest: None,
+            diff: None,
+        };
+        match document.status {
+            TranslationStatus::Missing => summary.missing += 1,
+            TranslationStatus::NeverMarked => summary.never_marked += 1,
+            TranslationStatus::Outdated => summary.out_dated += 1,
+            TranslationStatus::UptoDate => summary.upto_date += 1,
+        }
+
+        let track_path =
+            fastn_core::utils::track_path(document.file.as_str(), config.root.as_str());
+        if track_path.exists() {
+            let tracks = fastn_core::tracker::get_tracks(config.root.as_str(), &track_path)?;
+            if let Some(fastn_core::Track {
+                last_merged_version: Some(last_merged_version),
+                self_timestamp,
+                ..
+            }) = tracks.get(&document.file)
+            {
+                document.last_marked_on =
+                    Some(fastn_core::utils::nanos_to_rfc3339(last_merged_version));
+                document.translated_latest =
+                    Some(fastn_core::utils::nanos_to_rfc3339(self_timestamp));
+                if matches!(document.status, TranslationStatus::Outdated) {
+                    // the history of the original may not have the last marked version
+                    document.diff = fastn_core::translation::get_diff(
+                        config,
+                        document.file.as_str(),
+                        last_merged_version,
+                        &original_latest,
+                    )
+                    .await
+                    .ok();
+                }
+            }
+        }
+        documents.push(document);
+    }
+
+    Ok(TranslationStatusReport {
+        package: config.package.name.to_string(),
+        language: config.package.language.clone(),
+        translation_of: config
+            .package
+            .translation_of
+            .as_ref()
+            .as_ref()
+            .map(|o| o.name.to_string())
+            .unwrap_or_default(),
+        summary,
+        documents,
+    })
+}
+
+fn print_translation_status(report: &TranslationStatusReport) {
+    for document in report.documents.iter() {
+        match document.last_marked_on {
+            Some(ref last_marked_on) if matches!(document.status, TranslationStatus::Outdated) => {
+                println!(
+                    "{}: {} (last marked on {})",
+                    document.status.as_str(),
+                    document.file,
+                    last_marked_on
+                )
+            }
+            _ => println!("{}: {}", document.status.as_str(), document.file),
+        }
     }
+    println!();
+    print!("{}", report.summary.to_string());
+}
+
+#[derive(Debug, serde::Serialize)]
+pub(crate) struct TranslationStatusReport {
+    pub package: String,
+    pub language: Option<String>,
+    #[serde(rename = "translation-of")]
+    pub translation_of: String,
+    pub summary: fastn_core::translation::TranslationStatusSummary,
+    pub documents: Vec<DocumentStatus>,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub(crate) struct DocumentStatus {
+    pub file: String,
+    pub status: TranslationStatus,
+    #[serde(rename = "last-marked-on")]
+    pub last_marked_on: Option<String>,
+    #[serde(rename = "original-latest")]
+    pub original_latest: String,
+    #[serde(rename = "translated-latest")]
+    pub translated_latest: Option<String>,
+    /// changes in the original document since it was last marked, for out-dated documents
+    pub diff: Option<String>,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "kebab-case")]
 pub(crate) enum TranslationStatus {
     Missing,
     NeverMarked,
+    #[serde(rename = "out-dated")]
     Outdated,
     UptoDate,
 }
@@ -36,6 +36,14 @@ pub struct Config {
     pub named_parameters: Vec<(String, ftd::Value)>,
     pub current_document: Option<String>,
     pub request: Option<fastn_core::http::Request>, // TODO: It should only contain reference
+    /// Set when the current document used a processor whose output depends on the request, such
+    /// documents are not stored in `fastn_core::render_cache`
+    pub request_dependent: bool,
+    /// Pages of the last list with `page-size` in the current document
+    pub pagination: Option<fastn_core::Pagination>,
+    /// Base url of the combined output of `fastn build --multilingual`, translations of the
+    /// package are linked at `<base-url><language>/` instead of their own domain
+    pub multilingual_base_url: Option<String>,
     pub ftd_edition: FTDEdition,
     pub ftd_external_js: Vec<String>,
     pub ftd_inline_js: Vec<String>,
@@ -481,6 +489,27 @@ impl Config {
         }
     }
 
+    /// Hash of a static file of this package, from the package db kept up to date by the watcher,
+    /// `None` if the file is of a dependency or the db is not loaded
+    pub(crate) fn static_file_hash(
+        &self,
+        base_path: &camino::Utf8Path,
+        id: &str,
+    ) -> Option<String> {
+        if base_path != self.root.as_path() {
+            return None;
+        }
+        let package = fastn_core::package::initializer::package(self.root.as_path())?;
+        match fastn_package::queries::name(package) {
+            Ok(name) if name.eq(&self.package.name) => {}
+            _ => return None,
+        }
+        fastn_package::queries::static_file(package, id)
+            .ok()
+            .flatten()
+            .and_then(|f| f.content_hash)
+    }
+
     pub(crate) fn get_root_for_package(
         &self,
         package: &fastn_core::Package,
@@ -711,11 +740,15 @@ impl Config {
         // Handling of `-- fastn.dynamic-urls:`
         package.dynamic_urls = {
             match &package.dynamic_urls_temp {
-                Some(urls_temp) => Some(fastn_core::sitemap::DynamicUrls::parse(
-                    &self.global_ids,
-                    &package.name,
-                    urls_temp.body.as_str(),
-                )?),
+                Some(urls_temp) => {
+                    let dynamic_urls = fastn_core::sitemap::DynamicUrls::parse(
+                        &self.global_ids,
+                        &package.name,
+                        urls_temp.body.as_str(),
+                    )?;
+                    dynamic_urls.check_conflicts(package.sitemap.as_ref())?;
+                    Some(dynamic_urls)
+                }
                 None => None,
             }
         };
@@ -757,8 +790,11 @@ impl Config {
             // Getting `document` with dynamic parameters, if exists
             // It will first resolve in sitemap
             // Then it will resolve in the dynamic urls
-            let (document, path_params) =
-                fastn_core::sitemap::resolve(sanitized_package, &sanitized_path)?;
+            let (document, path_params) = fastn_core::sitemap::resolve(
+                sanitized_package,
+                &sanitized_path,
+                self.request.as_ref().map(|r| r.query_string()),
+            )?;
 
             // document with package-name prefix
             let document = document.map(|doc| {
@@ -1297,6 +1333,9 @@ impl Config {
             downloaded_assets: Default::default(),
             global_ids: Default::default(),
             request: req.map(ToOwned::to_owned),
+            request_dependent: false,
+            pagination: None,
+            multilingual_base_url: None,
             named_parameters: vec![],
             ftd_edition: FTDEdition::default(),
             ftd_external_js: Default::default(),
@@ -1337,11 +1376,15 @@ impl Config {
         // Handling of `-- fastn.dynamic-urls:`
         config.package.dynamic_urls = {
             match &package.dynamic_urls_temp {
-                Some(urls_temp) => Some(fastn_core::sitemap::DynamicUrls::parse(
-                    &config.global_ids,
-                    &package.name,
-                    urls_temp.body.as_str(),
-                )?),
+                Some(urls_temp) => {
+                    let dynamic_urls = fastn_core::sitemap::DynamicUrls::parse(
+                        &config.global_ids,
+                        &package.name,
+                        urls_temp.body.as_str(),
+                    )?;
+                    dynamic_urls.check_conflicts(config.package.sitemap.as_ref())?;
+                    Some(dynamic_urls)
+                }
                 None => None,
             }
         };
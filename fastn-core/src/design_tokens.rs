@@ -0,0 +1,1026 @@
+//! Conversion of `ftd.color-scheme` and `ftd.type-data` values to and from design token files:
+//! the [W3C Design Tokens](https://design-tokens.github.io/community-group/format/) format,
+//! [Style Dictionary](https://amzn.github.io/style-dictionary/) and CSS custom properties.
+//!
+//! Design tokens have no notion of light and dark mode, or of devices, so colours are grouped
+//! under `light` and `dark` and typography under `desktop` and `mobile`. In CSS the dark colours
+//! and the mobile typography are declared in `prefers-color-scheme` and `max-width` media queries.
+
+/// The fields of `ftd.color-scheme`, with the record each of them is and the colours in that
+/// record (no colours if the field is an `ftd.color` itself)
+const COLOR_SCHEME: &[(&str, &str, &[&str])] = &[
+    (
+        "background",
+        "ftd.background-colors",
+        &["base", "step-1", "step-2", "overlay", "code"],
+    ),
+    ("border", "ftd.color", &[]),
+    ("border-strong", "ftd.color", &[]),
+    ("text", "ftd.color", &[]),
+    ("text-strong", "ftd.color", &[]),
+    ("shadow", "ftd.color", &[]),
+    ("scrim", "ftd.color", &[]),
+    ("cta-primary", "ftd.cta-colors", CTA_COLORS),
+    ("cta-secondary", "ftd.cta-colors", CTA_COLORS),
+    ("cta-tertiary", "ftd.cta-colors", CTA_COLORS),
+    ("cta-danger", "ftd.cta-colors", CTA_COLORS),
+    ("accent", "ftd.pst", &["primary", "secondary", "tertiary"]),
+    ("error", "ftd.btb", BTB_COLORS),
+    ("success", "ftd.btb", BTB_COLORS),
+    ("info", "ftd.btb", BTB_COLORS),
+    ("warning", "ftd.btb", BTB_COLORS),
+    (
+        "custom",
+        "ftd.custom-colors",
+        &[
+            "one", "two", "three", "four", "five", "six", "seven", "eight", "nine", "ten",
+        ],
+    ),
+];
+
+const CTA_COLORS: &[&str] = &[
+    "base",
+    "hover",
+    "pressed",
+    "disabled",
+    "focused",
+    "border",
+    "border-disabled",
+    "text",
+    "text-disabled",
+];
+
+const BTB_COLORS: &[&str] = &["base", "text", "border"];
+
+/// The fields of `ftd.type-data`, each an `ftd.responsive-type`
+const TYPE_DATA: &[&str] = &[
+    "heading-large",
+    "heading-medium",
+    "heading-small",
+    "heading-hero",
+    "heading-tiny",
+    "copy-small",
+    "copy-regular",
+    "copy-large",
+    "fine-print",
+    "blockquote",
+    "source-code",
+    "button-small",
+    "button-medium",
+    "button-large",
+    "link",
+    "label-large",
+    "label-small",
+];
+
+/// The CSS properties of a type, in the order they are written
+const TYPE_PROPERTIES: &[&str] = &[
+    "font-family",
+    "font-size",
+    "line-height",
+    "letter-spacing",
+    "font-weight",
+];
+
+const DARK_MEDIA_QUERY: &str = "@media (prefers-color-scheme: dark)";
+/// ftd switches to the mobile types below the 768px breakpoint
+const MOBILE_MEDIA_QUERY: &str = "@media (max-width: 767px)";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DesignTokensFormat {
+    W3c,
+    StyleDictionary,
+    Css,
+}
+
+impl std::str::FromStr for DesignTokensFormat {
+    type Err = fastn_core::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "w3c" => Ok(DesignTokensFormat::W3c),
+            "style-dictionary" => Ok(DesignTokensFormat::StyleDictionary),
+            "css" => Ok(DesignTokensFormat::Css),
+            t => Err(fastn_core::Error::UsageError {
+                message: format!(
+                    "unknown design tokens format `{}`, expected `w3c`, `style-dictionary` or `css`",
+                    t
+                ),
+            }),
+        }
+    }
+}
+
+impl DesignTokensFormat {
+    /// The format of a token file from its extension, json files are read as W3C tokens, which
+    /// also reads Style Dictionary tokens
+    pub(crate) fn from_path(path: &camino::Utf8Path) -> DesignTokensFormat {
+        match path.extension() {
+            Some("css") => DesignTokensFormat::Css,
+            _ => DesignTokensFormat::W3c,
+        }
+    }
+
+    /// A token of type `kind`, W3C tokens have `$type` and `$value`, Style Dictionary ones
+    /// `type` and `value`
+    fn token(&self, kind: &str, value: serde_json::Value) -> serde_json::Value {
+        let (type_key, value_key) = match self {
+            DesignTokensFormat::StyleDictionary => ("type", "value"),
+            _ => ("$type", "$value"),
+        };
+        let mut token = serde_json::Map::new();
+        token.insert(type_key.to_string(), kind.into());
+        token.insert(value_key.to_string(), value);
+        serde_json::Value::Object(token)
+    }
+}
+
+#[derive(Debug, Default, PartialEq)]
+pub(crate) struct ColorScheme {
+    /// Colours by their path in the scheme, like `background.step-1`
+    pub light: std::collections::BTreeMap<String, String>,
+    pub dark: std::collections::BTreeMap<String, String>,
+}
+
+#[derive(Debug, Default, PartialEq)]
+pub(crate) struct TypeData {
+    /// Types by their role, like `heading-large`
+    pub desktop: std::collections::BTreeMap<String, Type>,
+    pub mobile: std::collections::BTreeMap<String, Type>,
+}
+
+/// An `ftd.type`, lengths are kept as css lengths like `16px` or `1.5rem`
+#[derive(Debug, Default, Clone, PartialEq)]
+pub(crate) struct Type {
+    pub font_family: Option<String>,
+    pub size: Option<String>,
+    pub line_height: Option<String>,
+    pub letter_spacing: Option<String>,
+    pub weight: Option<i64>,
+}
+
+#[derive(Debug, PartialEq)]
+pub(crate) enum Tokens {
+    ColorScheme(ColorScheme),
+    TypeData(TypeData),
+}
+
+impl Tokens {
+    /// Reads the tokens of a resolved `ftd.color-scheme` or `ftd.type-data` value
+    pub(crate) fn from_value(
+        value: ftd::interpreter::Value,
+        doc: &ftd::interpreter::TDoc,
+    ) -> fastn_core::Result<Tokens> {
+        match value {
+            ftd::interpreter::Value::Record { name, fields }
+                if name.eq(ftd::interpreter::FTD_COLOR_SCHEME) =>
+            {
+                let mut scheme = ColorScheme::default();
+                collect_colors("", &fields, doc, &mut scheme)?;
+                Ok(Tokens::ColorScheme(scheme))
+            }
+            ftd::interpreter::Value::Record { name, fields }
+                if name.eq(ftd::interpreter::FTD_TYPE_DATA) =>
+            {
+                let mut type_data = TypeData::default();
+                for (role, value) in fields.iter() {
+                    let fields = match resolve(value, doc)? {
+                        Some(ftd::interpreter::Value::Record { fields, .. }) => fields,
+                        _ => continue,
+                    };
+                    if let Some(desktop) = fields.get("desktop") {
+                        type_data
+                            .desktop
+                            .insert(role.to_string(), Type::from_value(desktop, doc)?);
+                    }
+                    if let Some(mobile) = fields.get("mobile") {
+                        type_data
+                            .mobile
+                            .insert(role.to_string(), Type::from_value(mobile, doc)?);
+                    }
+                }
+                Ok(Tokens::TypeData(type_data))
+            }
+            t => Err(fastn_core::Error::UsageError {
+                message: format!(
+                    "expected `ftd.color-scheme` or `ftd.type-data`, found: {:?}",
+                    t
+                ),
+            }),
+        }
+    }
+
+    /// Returns the missing and invalid tokens, an empty list if the tokens make a complete
+    /// `ftd.color-scheme` or `ftd.type-data`
+    pub(crate) fn validate(&self) -> Vec<String> {
+        match self {
+            Tokens::ColorScheme(scheme) => scheme.validate(),
+            Tokens::TypeData(type_data) => type_data.validate(),
+        }
+    }
+
+    pub(crate) fn render(&self, format: DesignTokensFormat) -> fastn_core::Result<String> {
+        Ok(match (self, format) {
+            (Tokens::ColorScheme(scheme), DesignTokensFormat::Css) => scheme.to_css(),
+            (Tokens::TypeData(type_data), DesignTokensFormat::Css) => type_data.to_css(),
+            (Tokens::ColorScheme(scheme), format) => {
+                serde_json::to_string_pretty(&scheme.to_json(format))?
+            }
+            (Tokens::TypeData(type_data), format) => {
+                serde_json::to_string_pretty(&type_data.to_json(format))?
+            }
+        })
+    }
+
+    /// Reads the tokens of a design tokens file, whether it has colours or typography is known
+    /// from the top level groups of json files and from the property names of css files
+    pub(crate) fn parse(content: &str, format: DesignTokensFormat) -> fastn_core::Result<Tokens> {
+        match format {
+            DesignTokensFormat::Css => Tokens::from_css(content),
+            _ => Tokens::from_json(&serde_json::from_str(content)?),
+        }
+    }
+
+    fn from_json(json: &serde_json::Value) -> fastn_core::Result<Tokens> {
+        let mut tokens = std::collections::BTreeMap::new();
+        flatten_tokens("", json, &mut tokens);
+        let tokens = tokens
+            .iter()
+            .map(|(path, value)| (path.to_string(), resolve_alias(value, &tokens)))
+            .collect::<std::collections::BTreeMap<_, _>>();
+
+        let group = |prefix: &str| {
+            tokens
+                .iter()
+                .filter_map(move |(path, value)| {
+                    path.strip_prefix(prefix)
+                        .and_then(|p| p.strip_prefix('.'))
+                        .map(|p| (p.to_string(), value))
+                })
+                .collect::<Vec<_>>()
+        };
+
+        if json.get("light").is_some() || json.get("dark").is_some() {
+            let mut scheme = ColorScheme::default();
+            for (mode, colors) in [("light", &mut scheme.light), ("dark", &mut scheme.dark)] {
+                for (path, value) in group(mode) {
+                    match value.as_str() {
+                        Some(color) => colors.insert(path, color.to_string()),
+                        None => colors.insert(path, value.to_string()),
+                    };
+                }
+            }
+            return Ok(Tokens::ColorScheme(scheme));
+        }
+
+        if json.get("desktop").is_some() || json.get("mobile").is_some() {
+            let mut type_data = TypeData::default();
+            for (device, types) in [
+                ("desktop", &mut type_data.desktop),
+                ("mobile", &mut type_data.mobile),
+            ] {
+                // a composite typography token is flattened into its properties
+                let mut roles: std::collections::BTreeMap<String, Type> = Default::default();
+                for (path, value) in group(device) {
+                    let (role, property) = match path.split_once('.') {
+                        Some(v) => v,
+                        None => continue,
+                    };
+                    roles
+                        .entry(role.to_string())
+                        .or_default()
+                        .set_from_json(property, value)?;
+                }
+                types.extend(roles);
+            }
+            return Ok(Tokens::TypeData(type_data));
+        }
+
+        Err(fastn_core::Error::UsageError {
+            message: "expected the tokens to be grouped under `light` and `dark`, or `desktop` and `mobile`".to_string(),
+        })
+    }
+
+    fn from_css(content: &str) -> fastn_core::Result<Tokens> {
+        static CSS: once_cell::sync::Lazy<regex::Regex> = once_cell::sync::Lazy::new(|| {
+            regex::Regex::new(r"(?s)/\*.*?\*/|([^{};]*)\{|\}|--([A-Za-z0-9_-]+)\s*:\s*([^;}]+)")
+                .unwrap()
+        });
+
+        // the selectors and media queries the current declaration is nested in
+        let mut blocks: Vec<String> = vec![];
+        let mut declarations = vec![];
+        for captures in CSS.captures_iter(content) {
+            let matched = captures.get(0).unwrap().as_str();
+            if let Some(prelude) = captures.get(1) {
+                blocks.push(
+                    prelude
+                        .as_str()
+                        .split_whitespace()
+                        .collect::<Vec<_>>()
+                        .join(" "),
+                );
+            } else if let (Some(name), Some(value)) = (captures.get(2), captures.get(3)) {
+                let dark = blocks
+                    .iter()
+                    .any(|b| b.contains("prefers-color-scheme: dark"));
+                let mobile = blocks.iter().any(|b| b.contains("max-width"));
+                declarations.push((
+                    name.as_str().to_string(),
+                    value.as_str().trim().to_string(),
+                    dark || mobile,
+                ));
+            } else if matched.eq("}") {
+                blocks.pop();
+            }
+        }
+
+        let color_names = color_paths()
+            .into_iter()
+            .map(|path| (path.replace('.', "-"), path))
+            .collect::<std::collections::BTreeMap<_, _>>();
+        if declarations
+            .iter()
+            .any(|(name, _, _)| color_names.contains_key(name))
+        {
+            let mut scheme = ColorScheme::default();
+            for (name, value, dark) in declarations {
+                let path = color_names.get(&name).cloned().unwrap_or(name);
+                if dark {
+                    scheme.dark.insert(path, value);
+                } else {
+                    scheme.light.insert(path, value);
+                }
+            }
+            return Ok(Tokens::ColorScheme(scheme));
+        }
+
+        let mut type_data = TypeData::default();
+        for (name, value, mobile) in declarations {
+            let (role, property) = match TYPE_PROPERTIES
+                .iter()
+                .find_map(|p| name.strip_suffix(&format!("-{}", p)).map(|r| (r, *p)))
+            {
+                Some(v) => v,
+                None => {
+                    fastn_core::warning!("ignoring unknown custom property `--{}`", name);
+                    continue;
+                }
+            };
+            let types = if mobile {
+                &mut type_data.mobile
+            } else {
+                &mut type_data.desktop
+            };
+            types
+                .entry(role.to_string())
+                .or_default()
+                .set(property, value.as_str())?;
+        }
+        Ok(Tokens::TypeData(type_data))
+    }
+
+    /// FTD declaring the tokens as an `ftd.color-scheme` or `ftd.type-data` variable `name`, with
+    /// a variable for each of its colours or types
+    pub(crate) fn to_ftd(&self, name: &str) -> String {
+        match self {
+            Tokens::ColorScheme(scheme) => scheme.to_ftd(name),
+            Tokens::TypeData(type_data) => type_data.to_ftd(name),
+        }
+    }
+}
+
+impl ColorScheme {
+    fn validate(&self) -> Vec<String> {
+        let mut problems = vec![];
+        let paths = color_paths();
+        for path in paths.iter() {
+            if !self.light.contains_key(path) {
+                problems.push(format!("missing colour `{}`", path));
+            }
+        }
+        for (mode, colors) in [("light", &self.light), ("dark", &self.dark)] {
+            for (path, color) in colors.iter() {
+                if !paths.contains(path) {
+                    problems.push(format!("unknown colour `{}.{}`", mode, path));
+                } else if !is_valid_color(color) {
+                    problems.push(format!(
+                        "invalid colour `{}` for `{}.{}`",
+                        color, mode, path
+                    ));
+                }
+            }
+        }
+        problems
+    }
+
+    /// The dark colour of `path`, ftd uses the light one if a colour has no dark variant
+    fn dark(&self, path: &str) -> Option<&String> {
+        self.dark.get(path).or_else(|| self.light.get(path))
+    }
+
+    fn to_json(&self, format: DesignTokensFormat) -> serde_json::Value {
+        let mut root = serde_json::Map::new();
+        for (mode, colors) in [("light", &self.light), ("dark", &self.dark)] {
+            let mut group = serde_json::Map::new();
+            for (path, color) in colors.iter() {
+                insert_token(
+                    &mut group,
+                    path,
+                    format.token("color", color.as_str().into()),
+                );
+            }
+            root.insert(mode.to_string(), serde_json::Value::Object(group));
+        }
+        serde_json::Value::Object(root)
+    }
+
+    fn to_css(&self) -> String {
+        let declarations = |colors: &std::collections::BTreeMap<String, String>, indent: &str| {
+            colors
+                .iter()
+                .map(|(path, color)| {
+                    format!("{}--{}: {};\n", indent, path.replace('.', "-"), color)
+                })
+                .collect::<String>()
+        };
+        format!(
+            ":root {{\n{}}}\n\n{} {{\n  :root {{\n{}  }}\n}}\n",
+            declarations(&self.light, "  "),
+            DARK_MEDIA_QUERY,
+            declarations(&self.dark, "    "),
+        )
+    }
+
+    fn to_ftd(&self, name: &str) -> String {
+        let mut ftd = String::new();
+        let mut scheme = format!("-- ftd.color-scheme {}:\n", name);
+        for (field, record, colors) in COLOR_SCHEME {
+            let variable = format!("{}-{}", name, field);
+            if colors.is_empty() {
+                ftd.push_str(self.color_to_ftd(variable.as_str(), field).as_str());
+            } else {
+                let mut fields = String::new();
+                for color in colors.iter() {
+                    let path = format!("{}.{}", field, color);
+                    let color_variable = format!("{}-{}", variable, color);
+                    ftd.push_str(self.color_to_ftd(color_variable.as_str(), &path).as_str());
+                    fields.push_str(format!("{}: ${}\n", color, color_variable).as_str());
+                }
+                ftd.push_str(format!("-- {} {}:\n{}\n", record, variable, fields).as_str());
+            }
+            scheme.push_str(format!("{}: ${}\n", field, variable).as_str());
+        }
+        ftd.push_str(scheme.as_str());
+        ftd
+    }
+
+    fn color_to_ftd(&self, variable: &str, path: &str) -> String {
+        format!(
+            "-- ftd.color {}:\nlight: {}\ndark: {}\n\n",
+            variable,
+            self.light.get(path).map(String::as_str).unwrap_or_default(),
+            self.dark(path).map(String::as_str).unwrap_or_default(),
+        )
+    }
+}
+
+impl TypeData {
+    fn validate(&self) -> Vec<String> {
+        let mut problems = vec![];
+        for role in TYPE_DATA.iter() {
+            if !self.desktop.contains_key(*role) {
+                problems.push(format!("missing type `{}`", role));
+            }
+        }
+        for (device, types) in [("desktop", &self.desktop), ("mobile", &self.mobile)] {
+            for (role, t) in types.iter() {
+                if !TYPE_DATA.contains(&role.as_str()) {
+                    problems.push(format!("unknown type `{}.{}`", device, role));
+                    continue;
+                }
+                for (property, length) in [
+                    ("font-size", &t.size),
+                    ("line-height", &t.line_height),
+                    ("letter-spacing", &t.letter_spacing),
+                ] {
+                    if let Some(length) = length {
+                        if parse_length(length).is_none() {
+                            problems.push(format!(
+                                "invalid length `{}` for `{}.{}.{}`, expected px, em or rem",
+                                length, device, role, property
+                            ));
+                        }
+                    }
+                }
+            }
+        }
+        problems
+    }
+
+    fn to_json(&self, format: DesignTokensFormat) -> serde_json::Value {
+        let mut root = serde_json::Map::new();
+        for (device, types) in [("desktop", &self.desktop), ("mobile", &self.mobile)] {
+            let mut group = serde_json::Map::new();
+            for (role, t) in types.iter() {
+                let mut value = serde_json::Map::new();
+                if let Some(ref font_family) = t.font_family {
+                    value.insert("fontFamily".to_string(), font_family.as_str().into());
+                }
+                if let Some(ref size) = t.size {
+                    value.insert("fontSize".to_string(), size.as_str().into());
+                }
+                if let Some(weight) = t.weight {
+                    value.insert("fontWeight".to_string(), weight.into());
+                }
+                if let Some(ref letter_spacing) = t.letter_spacing {
+                    value.insert("letterSpacing".to_string(), letter_spacing.as_str().into());
+                }
+                if let Some(ref line_height) = t.line_height {
+                    value.insert("lineHeight".to_string(), line_height.as_str().into());
+                }
+                group.insert(
+                    role.to_string(),
+                    format.token("typography", serde_json::Value::Object(value)),
+                );
+            }
+            root.insert(device.to_string(), serde_json::Value::Object(group));
+        }
+        serde_json::Value::Object(root)
+    }
+
+    fn to_css(&self) -> String {
+        let declarations = |types: &std::collections::BTreeMap<String, Type>, indent: &str| {
+            let mut css = String::new();
+            for (role, t) in types.iter() {
+                for property in TYPE_PROPERTIES.iter() {
+                    if let Some(value) = t.get(property) {
+                        css.push_str(
+                            format!("{}--{}-{}: {};\n", indent, role, property, value).as_str(),
+                        );
+                    }
+                }
+            }
+            css
+        };
+        format!(
+            ":root {{\n{}}}\n\n{} {{\n  :root {{\n{}  }}\n}}\n",
+            declarations(&self.desktop, "  "),
+            MOBILE_MEDIA_QUERY,
+            declarations(&self.mobile, "    "),
+        )
+    }
+
+    fn to_ftd(&self, name: &str) -> String {
+        let mut ftd = String::new();
+        let mut type_data = format!("-- ftd.type-data {}:\n", name);
+        for role in TYPE_DATA.iter() {
+            let desktop = match self.desktop.get(*role) {
+                Some(desktop) => desktop,
+                None => continue,
+            };
+            // the mobile tokens only have to override the desktop ones
+            let mobile = match self.mobile.get(*role) {
+                Some(mobile) => desktop.overridden_by(mobile),
+                None => desktop.clone(),
+            };
+            let variable = format!("{}-{}", name, role);
+            ftd.push_str(
+                desktop
+                    .to_ftd(format!("{}-desktop", variable).as_str())
+                    .as_str(),
+            );
+            ftd.push_str(
+                mobile
+                    .to_ftd(format!("{}-mobile", variable).as_str())
+                    .as_str(),
+            );
+            ftd.push_str(
+                format!(
+                    "-- ftd.responsive-type {variable}:\ndesktop: ${variable}-desktop\nmobile: ${variable}-mobile\n\n",
+                    variable = variable
+                )
+                .as_str(),
+            );
+            type_data.push_str(format!("{}: ${}\n", role, variable).as_str());
+        }
+        ftd.push_str(type_data.as_str());
+        ftd
+    }
+}
+
+impl Type {
+    fn from_value(
+        value: &ftd::interpreter::PropertyValue,
+        doc: &ftd::interpreter::TDoc,
+    ) -> fastn_core::Result<Type> {
+        let fields = match resolve(value, doc)? {
+            Some(ftd::interpreter::Value::Record { fields, .. }) => fields,
+            _ => return Ok(Type::default()),
+        };
+        let length = |name: &str| -> fastn_core::Result<Option<String>> {
+            let (variant, value) = match fields.get(name).map(|v| resolve(v, doc)).transpose()? {
+                Some(Some(ftd::interpreter::Value::OrType {
+                    full_variant,
+                    value,
+                    ..
+                })) => (full_variant, resolve(&value, doc)?),
+                _ => return Ok(None),
+            };
+            let unit = variant.rsplit('.').next().unwrap_or_default();
+            Ok(match value {
+                Some(ftd::interpreter::Value::Integer { value }) => {
+                    Some(format!("{}{}", value, unit))
+                }
+                Some(ftd::interpreter::Value::Decimal { value }) => {
+                    Some(format!("{}{}", value, unit))
+                }
+                _ => None,
+            })
+        };
+        Ok(Type {
+            font_family: match fields
+                .get("font-family")
+                .map(|v| resolve(v, doc))
+                .transpose()?
+            {
+                Some(Some(ftd::interpreter::Value::String { text })) => Some(text),
+                _ => None,
+            },
+            size: length("size")?,
+            line_height: length("line-height")?,
+            letter_spacing: length("letter-spacing")?,
+            weight: match fields.get("weight").map(|v| resolve(v, doc)).transpose()? {
+                Some(Some(ftd::interpreter::Value::Integer { value })) => Some(value),
+                _ => None,
+            },
+        })
+    }
+
+    fn overridden_by(&self, other: &Type) -> Type {
+        Type {
+            font_family: other
+                .font_family
+                .clone()
+                .or_else(|| self.font_family.clone()),
+            size: other.size.clone().or_else(|| self.size.clone()),
+            line_height: other
+                .line_height
+                .clone()
+                .or_else(|| self.line_height.clone()),
+            letter_spacing: other
+                .letter_spacing
+                .clone()
+                .or_else(|| self.letter_spacing.clone()),
+            weight: other.weight.or(self.weight),
+        }
+    }
+
+    fn get(&self, property: &str) -> Option<String> {
+        match property {
+            "font-family" => self.font_family.clone(),
+            "font-size" => self.size.clone(),
+            "line-height" => self.line_height.clone(),
+            "letter-spacing" => self.letter_spacing.clone(),
+            "font-weight" => self.weight.map(|w| w.to_string()),
+            _ => None,
+        }
+    }
+
+    /// Sets a css property of the type
+    fn set(&mut self, property: &str, value: &str) -> fastn_core::Result<()> {
+        let value = value.trim().to_string();
+        match property {
+            "font-family" => self.font_family = Some(value),
+            "font-size" => self.size = Some(value),
+            // a unitless line height is a multiple of the font size
+            "line-height" if value.parse::<f64>().is_ok() => {
+                self.line_height = Some(format!("{}em", value))
+            }
+            "line-height" => self.line_height = Some(value),
+            "letter-spacing" => self.letter_spacing = Some(value),
+            "font-weight" => {
+                self.weight = Some(match value.as_str() {
+                    "normal" => 400,
+                    "bold" => 700,
+                    _ => value.parse()?,
+                })
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// Sets a property of a W3C or Style Dictionary typography token, numbers are pixels except
+    /// for the weight and the line height
+    fn set_from_json(
+        &mut self,
+        property: &str,
+        value: &serde_json::Value,
+    ) -> fastn_core::Result<()> {
+        let property = match property {
+            "fontFamily" => "font-family",
+            "fontSize" => "font-size",
+            "lineHeight" => "line-height",
+            "letterSpacing" => "letter-spacing",
+            "fontWeight" => "font-weight",
+            _ => return Ok(()),
+        };
+        let value = match value {
+            serde_json::Value::String(s) => s.to_string(),
+            serde_json::Value::Number(n) if !matches!(property, "font-weight" | "line-height") => {
+                format!("{}px", n)
+            }
+            serde_json::Value::Array(families) => families
+                .iter()
+                .filter_map(|f| f.as_str())
+                .collect::<Vec<_>>()
+                .join(", "),
+            v => v.to_string(),
+        };
+        self.set(property, value.as_str())
+    }
+
+    fn to_ftd(&self, variable: &str) -> String {
+        let mut ftd = format!("-- ftd.type {}:\n", variable);
+        if let Some(ref font_family) = self.font_family {
+            ftd.push_str(format!("font-family: {}\n", font_family).as_str());
+        }
+        for (name, length) in [
+            ("size", &self.size),
+            ("line-height", &self.line_height),
+            ("letter-spacing", &self.letter_spacing),
+        ] {
+            if let Some((value, unit)) = length.as_deref().and_then(parse_length) {
+                ftd.push_str(format!("{}.{}: {}\n", name, unit, value).as_str());
+            }
+        }
+        if let Some(weight) = self.weight {
+            ftd.push_str(format!("weight: {}\n", weight).as_str());
+        }
+        ftd.push('\n');
+        ftd
+    }
+}
+
+/// The names of the colours of `ftd.color-scheme`, like `text` or `background.step-1`
+fn color_paths() -> Vec<String> {
+    let mut paths = vec![];
+    for (field, _, colors) in COLOR_SCHEME {
+        if colors.is_empty() {
+            paths.push(field.to_string());
+        }
+        for color in colors.iter() {
+            paths.push(format!("{}.{}", field, color));
+        }
+    }
+    paths
+}
+
+fn collect_colors(
+    path: &str,
+    fields: &ftd::Map<ftd::interpreter::PropertyValue>,
+    doc: &ftd::interpreter::TDoc,
+    scheme: &mut ColorScheme,
+) -> fastn_core::Result<()> {
+    for (name, value) in fields.iter() {
+        let path = match path {
+            "" => name.to_string(),
+            path => format!("{}.{}", path, name),
+        };
+        match resolve(value, doc)? {
+            Some(ftd::interpreter::Value::Record { name, fields })
+                if name.eq(ftd::interpreter::FTD_COLOR) =>
+            {
+                let color = |mode: &str| -> fastn_core::Result<Option<String>> {
+                    Ok(
+                        match fields.get(mode).map(|v| resolve(v, doc)).transpose()? {
+                            Some(Some(ftd::interpreter::Value::String { text })) => Some(text),
+                            _ => None,
+                        },
+                    )
+                };
+                if let Some(light) = color("light")? {
+                    scheme.light.insert(path.to_string(), light);
+                }
+                if let Some(dark) = color("dark")? {
+                    scheme.dark.insert(path, dark);
+                }
+            }
+            Some(ftd::interpreter::Value::Record { fields, .. }) => {
+                collect_colors(path.as_str(), &fields, doc, scheme)?
+            }
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+/// Resolves a property value, `None` for an optional without a value
+fn resolve(
+    value: &ftd::interpreter::PropertyValue,
+    doc: &ftd::interpreter::TDoc,
+) -> fastn_core::Result<Option<ftd::interpreter::Value>> {
+    let mut value = value.clone().resolve(doc, value.line_number())?;
+    while let ftd::interpreter::Value::Optional { data, .. } = value {
+        value = match *data {
+            Some(v) => v,
+            None => return Ok(None),
+        };
+    }
+    Ok(Some(value))
+}
+
+fn is_valid_color(color: &str) -> bool {
+    use std::str::FromStr;
+
+    css_color_parser::Color::from_str(color).is_ok()
+}
+
+/// Splits a css length in its value and unit, if it is a length ftd supports: whole pixels, em
+/// or rem
+fn parse_length(length: &str) -> Option<(&str, &str)> {
+    let length = length.trim();
+    if let Some(value) = length.strip_suffix("rem") {
+        return value.parse::<f64>().ok().map(|_| (value, "rem"));
+    }
+    if let Some(value) = length.strip_suffix("em") {
+        return value.parse::<f64>().ok().map(|_| (value, "em"));
+    }
+    if let Some(value) = length.strip_suffix("px") {
+        return value.parse::<i64>().ok().map(|_| (value, "px"));
+    }
+    None
+}
+
+/// Collects the tokens of a W3C or Style Dictionary group by their dotted path, the properties of
+/// composite tokens get their own path
+fn flatten_tokens(
+    path: &str,
+    json: &serde_json::Value,
+    tokens: &mut std::collections::BTreeMap<String, serde_json::Value>,
+) {
+    let object = match json.as_object() {
+        Some(object) => object,
+        None => return,
+    };
+    if let Some(value) = object.get("$value").or_else(|| object.get("value")) {
+        match value.as_object() {
+            Some(composite) => {
+                for (property, value) in composite.iter() {
+                    tokens.insert(format!("{}.{}", path, property), value.clone());
+                }
+            }
+            None => {
+                tokens.insert(path.to_string(), value.clone());
+            }
+        }
+        return;
+    }
+    for (name, value) in object.iter() {
+        // `$type`, `$description` and the like describe the group
+        if name.starts_with('$') {
+            continue;
+        }
+        let path = match path {
+            "" => name.to_string(),
+            path => format!("{}.{}", path, name),
+        };
+        flatten_tokens(path.as_str(), value, tokens);
+    }
+}
+
+/// Follows a token that is an alias of another token, written `{group.token}`
+fn resolve_alias(
+    value: &serde_json::Value,
+    tokens: &std::collections::BTreeMap<String, serde_json::Value>,
+) -> serde_json::Value {
+    let mut value = value;
+    // aliases can refer to aliases, but not forever
+    for _ in 0..10 {
+        match value
+            .as_str()
+            .and_then(|v| v.strip_prefix('{'))
+            .and_then(|v| v.strip_suffix('}'))
+            .and_then(|path| tokens.get(path.trim_end_matches(".value")))
+        {
+            Some(aliased) => value = aliased,
+            None => break,
+        }
+    }
+    value.clone()
+}
+
+fn insert_token(
+    group: &mut serde_json::Map<String, serde_json::Value>,
+    path: &str,
+    token: serde_json::Value,
+) {
+    match path.split_once('.') {
+        Some((name, rest)) => {
+            let entry = group
+                .entry(name.to_string())
+                .or_insert_with(|| serde_json::Value::Object(Default::default()));
+            if let serde_json::Value::Object(group) = entry {
+                insert_token(group, rest, token);
+            }
+        }
+        None => {
+            group.insert(path.to_string(), token);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    fn scheme() -> super::ColorScheme {
+        let mut scheme = super::ColorScheme::default();
+        for path in super::color_paths() {
+            scheme.light.insert(path.to_string(), "#FFFFFF".to_string());
+            scheme.dark.insert(path, "#000000".to_string());
+        }
+        scheme
+    }
+
+    #[test]
+    fn color_scheme_round_trip() {
+        let tokens = super::Tokens::ColorScheme(scheme());
+        assert!(tokens.validate().is_empty());
+        for format in [
+            super::DesignTokensFormat::W3c,
+            super::DesignTokensFormat::StyleDictionary,
+            super::DesignTokensFormat::Css,
+        ] {
+            let rendered = tokens.render(format).unwrap();
+            assert_eq!(
+                super::Tokens::parse(rendered.as_str(), format).unwrap(),
+                tokens
+            );
+        }
+    }
+
+    #[test]
+    fn type_data_round_trip() {
+        let mut type_data = super::TypeData::default();
+        let t = super::Type {
+            font_family: Some("Inter, sans-serif".to_string()),
+            size: Some("16px".to_string()),
+            line_height: Some("1.5em".to_string()),
+            letter_spacing: None,
+            weight: Some(400),
+        };
+        for role in super::TYPE_DATA.iter() {
+            type_data.desktop.insert(role.to_string(), t.clone());
+            type_data.mobile.insert(role.to_string(), t.clone());
+        }
+        let tokens = super::Tokens::TypeData(type_data);
+        assert!(tokens.validate().is_empty());
+        for format in [
+            super::DesignTokensFormat::W3c,
+            super::DesignTokensFormat::StyleDictionary,
+            super::DesignTokensFormat::Css,
+        ] {
+            let rendered = tokens.render(format).unwrap();
+            assert_eq!(
+                super::Tokens::parse(rendered.as_str(), format).unwrap(),
+                tokens
+            );
+        }
+    }
+
+    #[test]
+    fn missing_and_invalid_colors() {
+        let mut scheme = scheme();
+        scheme.light.remove("accent.primary");
+        scheme
+            .dark
+            .insert("background.base".to_string(), "not-a-colour".to_string());
+        assert_eq!(
+            super::Tokens::ColorScheme(scheme).validate(),
+            vec![
+                "missing colour `accent.primary`".to_string(),
+                "invalid colour `not-a-colour` for `dark.background.base`".to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn w3c_aliases() {
+        let tokens = super::Tokens::parse(
+            r##"{
+                "light": {
+                    "text": {"$type": "color", "$value": "{light.border}"},
+                    "border": {"$type": "color", "$value": "#333333"}
+                }
+            }"##,
+            super::DesignTokensFormat::W3c,
+        )
+        .unwrap();
+        match tokens {
+            super::Tokens::ColorScheme(scheme) => {
+                assert_eq!(scheme.light.get("text"), Some(&"#333333".to_string()))
+            }
+            t => panic!("expected a colour scheme, found {:?}", t),
+        }
+    }
+}
@@ -206,6 +206,11 @@ pub async fn resolve_import_2022<'a>(
                 "is-reader".to_string(),
                 "package-query".to_string(),
                 "package-tree".to_string(),
+                "pagination".to_string(),
+                "message".to_string(),
+                "format-number".to_string(),
+                "format-date".to_string(),
+                "language-switcher".to_string(),
                 "fetch-file".to_string(),
                 "query".to_string(),
             ],
@@ -267,6 +272,11 @@ pub async fn resolve_import_2022<'a>(
                 "document-suffix".to_string(),
                 "package-id".to_string(),
                 "package-tree".to_string(),
+                "pagination".to_string(),
+                "message".to_string(),
+                "format-number".to_string(),
+                "format-date".to_string(),
+                "language-switcher".to_string(),
                 "fetch-file".to_string(),
                 "get-version-data".to_string(),
                 "cr-meta".to_string(),
@@ -0,0 +1,452 @@
+//! Form handlers declared with `-- fastn.form: <route>` in `FASTN.ftd`.
+//!
+//! A submission is validated against the `required`, `pattern` and `max-length` rules of the
+//! form, and then inserted in `table` or passed to the `wasm` handler. Responses use the format
+//! the `ftd.http` action understands: `{"errors": {"<field>-error": "..."}}` when validation
+//! fails, and `{"redirect": "<url>"}`, `{"reload": true}` or `{"data": {}}` on success.
+//!
+//! Submissions must carry the value of the `fastn-csrf` cookie, which is set on every page served
+//! by `fastn serve`, in the `X-FASTN-CSRF` header. `ftd.http` does this on its own.
+
+pub(crate) const CSRF_COOKIE: &str = "fastn-csrf";
+pub(crate) const CSRF_HEADER: &str = "X-FASTN-CSRF";
+
+/// A new random token for the [`CSRF_COOKIE`]
+pub(crate) fn csrf_token() -> String {
+    use rand::Rng;
+
+    rand::thread_rng()
+        .gen::<[u8; 32]>()
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+/// Adds a new [`CSRF_COOKIE`] to `response` if it is an html page, `secure` when served over
+/// https
+pub(crate) fn set_csrf_cookie(response: &mut fastn_core::http::Response, secure: bool) {
+    let is_html = response
+        .headers()
+        .get(actix_web::http::header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .map(|v| v.starts_with("text/html"))
+        .unwrap_or(false);
+    if !is_html {
+        return;
+    }
+    let cookie = actix_web::cookie::Cookie::build(CSRF_COOKIE, csrf_token())
+        .path("/")
+        .same_site(actix_web::cookie::SameSite::Strict)
+        .secure(secure)
+        .finish();
+    if let Err(e) = response.add_cookie(&cookie) {
+        tracing::error!(msg = "failed to set csrf cookie", error = %e);
+    }
+}
+
+fn check_csrf(req: &fastn_core::http::Request) -> bool {
+    let cookie = match req.cookie(CSRF_COOKIE) {
+        Some(cookie) if !cookie.is_empty() => cookie,
+        _ => return false,
+    };
+    req.headers()
+        .get(CSRF_HEADER)
+        .and_then(|v| v.to_str().ok())
+        .map(|v| v.eq(cookie.as_str()))
+        .unwrap_or(false)
+}
+
+fn json_response(value: serde_json::Value) -> fastn_core::Result<fastn_core::http::Response> {
+    Ok(fastn_core::http::ok_with_content_type(
+        serde_json::to_vec(&value)?,
+        mime_guess::mime::APPLICATION_JSON,
+    ))
+}
+
+/// Values of the declared fields of the form in the body of `req`, which is either json or url
+/// encoded
+fn submitted_values(
+    req: &fastn_core::http::Request,
+    form: &fastn_package::old_fastn::Form,
+) -> fastn_core::Result<std::collections::BTreeMap<String, String>> {
+    let submitted: std::collections::HashMap<String, String> =
+        if req.content_type() == Some(mime_guess::mime::APPLICATION_WWW_FORM_URLENCODED) {
+            url::form_urlencoded::parse(req.body())
+                .into_owned()
+                .collect()
+        } else {
+            req.body_as_json()?
+                .unwrap_or_default()
+                .into_iter()
+                .map(|(k, v)| match v {
+                    serde_json::Value::String(s) => (k, s),
+                    serde_json::Value::Null => (k, "".to_string()),
+                    v => (k, v.to_string()),
+                })
+                .collect()
+        };
+    Ok(form
+        .fields
+        .iter()
+        .map(|field| {
+            (
+                field.to_string(),
+                submitted
+                    .get(field)
+                    .map(|v| v.trim().to_string())
+                    .unwrap_or_default(),
+            )
+        })
+        .collect())
+}
+
+/// `<field>=<value>` rules of the form
+fn rules(rules: &[String]) -> fastn_core::Result<Vec<(&str, &str)>> {
+    rules
+        .iter()
+        .map(|rule| {
+            rule.split_once('=')
+                .map(|(field, value)| (field.trim(), value.trim()))
+                .ok_or_else(|| fastn_core::Error::PackageError {
+                    message: format!(
+                        "form-config-error, format is <field>=<value>, rule: {}",
+                        rule
+                    ),
+                })
+        })
+        .collect()
+}
+
+fn validate(
+    form: &fastn_package::old_fastn::Form,
+    values: &std::collections::BTreeMap<String, String>,
+) -> fastn_core::Result<std::collections::BTreeMap<String, String>> {
+    let mut errors = std::collections::BTreeMap::new();
+    let value = |field: &str| values.get(field).map(|v| v.as_str()).unwrap_or_default();
+
+    for field in form.required.iter() {
+        if value(field).is_empty() {
+            errors.insert(format!("{}-error", field), format!("{} is required", field));
+        }
+    }
+    for (field, pattern) in rules(form.patterns.as_slice())? {
+        let regex = regex::Regex::new(format!("^(?:{})$", pattern).as_str()).map_err(|e| {
+            fastn_core::Error::PackageError {
+                message: format!("form-config-error, invalid pattern for {}: {}", field, e),
+            }
+        })?;
+        if !value(field).is_empty() && !regex.is_match(value(field)) {
+            errors
+                .entry(format!("{}-error", field))
+                .or_insert_with(|| format!("{} is not valid", field));
+        }
+    }
+    for (field, max_length) in rules(form.max_lengths.as_slice())? {
+        let max_length: usize =
+            max_length
+                .parse()
+                .map_err(|e| fastn_core::Error::PackageError {
+                    message: format!("form-config-error, invalid max-length for {}: {}", field, e),
+                })?;
+        if value(field).chars().count() > max_length {
+            errors
+                .entry(format!("{}-error", field))
+                .or_insert_with(|| format!("{} must be at most {} characters", field, max_length));
+        }
+    }
+    Ok(errors)
+}
+
+fn is_identifier(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+}
+
+async fn insert(
+    database: camino::Utf8PathBuf,
+    table: &str,
+    values: &std::collections::BTreeMap<String, String>,
+) -> fastn_core::Result<()> {
+    if let Some(name) = std::iter::once(table)
+        .chain(values.keys().map(|k| k.as_str()))
+        .find(|name| !is_identifier(name))
+    {
+        return Err(fastn_core::Error::PackageError {
+            message: format!("form-config-error, invalid table or field name: {}", name),
+        });
+    }
+    let columns = values
+        .keys()
+        .map(|k| format!("\"{}\"", k))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let placeholders = (1..=values.len())
+        .map(|i| format!("?{}", i))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let query = format!(
+        "INSERT INTO \"{}\" ({}) VALUES ({})",
+        table, columns, placeholders
+    );
+    let params = values.values().cloned().collect::<Vec<_>>();
+    tokio::task::spawn_blocking(move || -> fastn_core::Result<()> {
+        let conn = rusqlite::Connection::open_with_flags(
+            database.as_path(),
+            rusqlite::OpenFlags::SQLITE_OPEN_READ_WRITE,
+        )
+        .map_err(|e| {
+            fastn_core::Error::GenericError(format!("Failed to open `{}`: {:?}", database, e))
+        })?;
+        conn.execute(query.as_str(), rusqlite::params_from_iter(params))
+            .map_err(|e| fastn_core::Error::GenericError(format!("Failed to insert: {:?}", e)))?;
+        Ok(())
+    })
+    .await
+    .map_err(|e| fastn_core::Error::GenericError(format!("Failed to insert: {}", e)))?
+}
+
+pub(crate) async fn handle(
+    config: &fastn_core::Config,
+    req: fastn_core::http::Request,
+    form: &fastn_package::old_fastn::Form,
+) -> fastn_core::Result<fastn_core::http::Response> {
+    if req.method() != "POST" {
+        return Ok(actix_web::HttpResponse::MethodNotAllowed()
+            .insert_header((actix_web::http::header::ALLOW, "POST"))
+            .finish());
+    }
+    if !check_csrf(&req) {
+        return Ok(fastn_core::unauthorised!(
+            "missing or invalid {} header",
+            CSRF_HEADER
+        ));
+    }
+
+    let values = match submitted_values(&req, form) {
+        Ok(values) => values,
+        Err(e) => return Ok(actix_web::HttpResponse::BadRequest().body(e.to_string())),
+    };
+    let errors = validate(form, &values)?;
+    if !errors.is_empty() {
+        return json_response(serde_json::json!({ "errors": errors }));
+    }
+
+    if let Some(wasm) = form.wasm.as_ref() {
+        return Ok(fastn_core::wasm::handle_wasm(
+            req,
+            config.root.join(wasm.trim_start_matches('/')),
+            &config.package,
+            config.root.clone(),
+        )
+        .await);
+    }
+
+    if let Some(table) = form.table.as_ref() {
+        let database = form
+            .database
+            .as_ref()
+            .ok_or_else(|| fastn_core::Error::PackageError {
+                message: format!(
+                    "form-config-error, `database` is not set for {}",
+                    form.route
+                ),
+            })?;
+        insert(
+            config.root.join(database.trim_start_matches('/')),
+            table.as_str(),
+            &values,
+        )
+        .await?;
+    }
+
+    if let Some(redirect) = form.redirect.as_ref() {
+        return json_response(serde_json::json!({ "redirect": redirect }));
+    }
+    if form.reload {
+        return json_response(serde_json::json!({ "reload": true }));
+    }
+    json_response(serde_json::json!({ "data": {} }))
+}
+
+#[cfg(test)]
+mod tests {
+    fn form() -> fastn_package::old_fastn::Form {
+        fastn_package::old_fastn::Form {
+            route: "/subscribe/".to_string(),
+            database: None,
+            table: None,
+            wasm: None,
+            fields: vec!["email".to_string(), "name".to_string()],
+            required: vec!["email".to_string()],
+            patterns: vec!["email=[^@]+@[^@]+".to_string()],
+            max_lengths: vec!["name=5".to_string(), "email=20".to_string()],
+            redirect: None,
+            reload: false,
+        }
+    }
+
+    fn values(values: &[(&str, &str)]) -> std::collections::BTreeMap<String, String> {
+        values
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    fn request(headers: &[(&str, &str)]) -> fastn_core::http::Request {
+        let mut req = actix_web::test::TestRequest::post();
+        for header in headers {
+            req = req.insert_header(*header);
+        }
+        fastn_core::http::Request::from_actix(req.to_http_request(), Default::default())
+    }
+
+    #[test]
+    fn csrf_token() {
+        let token = super::csrf_token();
+        assert_eq!(token.len(), 64);
+        assert!(token.chars().all(|c| c.is_ascii_hexdigit()));
+        assert_ne!(token, super::csrf_token());
+    }
+
+    #[test]
+    fn check_csrf() {
+        assert!(super::check_csrf(&request(&[
+            ("cookie", "fastn-csrf=abc"),
+            ("x-fastn-csrf", "abc")
+        ])));
+        assert!(!super::check_csrf(&request(&[])));
+        assert!(!super::check_csrf(&request(&[("x-fastn-csrf", "abc")])));
+        assert!(!super::check_csrf(&request(&[(
+            "cookie",
+            "fastn-csrf=abc"
+        )])));
+        assert!(!super::check_csrf(&request(&[
+            ("cookie", "fastn-csrf=abc"),
+            ("x-fastn-csrf", "xyz")
+        ])));
+        assert!(!super::check_csrf(&request(&[
+            ("cookie", "fastn-csrf="),
+            ("x-fastn-csrf", "")
+        ])));
+    }
+
+    #[tokio::test]
+    async fn handle_rejects_without_csrf() {
+        let config = fastn_core::Config::read(
+            Some(format!(
+                "{}/tests/27-wasm-backend/input/amitu",
+                env!("CARGO_MANIFEST_DIR")
+            )),
+            false,
+            None,
+        )
+        .await
+        .unwrap();
+        let response = super::handle(&config, request(&[]), &form()).await.unwrap();
+        assert_eq!(response.status(), actix_web::http::StatusCode::UNAUTHORIZED);
+
+        let req = fastn_core::http::Request::from_actix(
+            actix_web::test::TestRequest::get().to_http_request(),
+            Default::default(),
+        );
+        let response = super::handle(&config, req, &form()).await.unwrap();
+        assert_eq!(
+            response.status(),
+            actix_web::http::StatusCode::METHOD_NOT_ALLOWED
+        );
+    }
+
+    #[test]
+    fn validate() {
+        let form = form();
+        assert!(
+            super::validate(&form, &values(&[("email", "a@b.c"), ("name", "amit")]))
+                .unwrap()
+                .is_empty()
+        );
+        assert_eq!(
+            super::validate(&form, &values(&[("email", ""), ("name", "")])).unwrap(),
+            values(&[("email-error", "email is required")])
+        );
+        assert_eq!(
+            super::validate(&form, &values(&[("email", "ab.c"), ("name", "amitabh")])).unwrap(),
+            values(&[
+                ("email-error", "email is not valid"),
+                ("name-error", "name must be at most 5 characters")
+            ])
+        );
+        // the pattern has to match the whole value, and the first error of a field is kept
+        assert_eq!(
+            super::validate(&form, &values(&[("email", "a@b@c.and-a-very-long-domain")])).unwrap(),
+            values(&[("email-error", "email is not valid")])
+        );
+        // max-length counts characters, not bytes
+        assert!(
+            super::validate(&form, &values(&[("email", "a@b.c"), ("name", "ऋषभ")]))
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn validate_config_errors() {
+        let mut form = form();
+        form.patterns = vec!["email=(".to_string()];
+        assert!(super::validate(&form, &values(&[("email", "a@b.c")])).is_err());
+
+        let mut form = self::form();
+        form.max_lengths = vec!["name".to_string()];
+        assert!(super::validate(&form, &values(&[("email", "a@b.c")])).is_err());
+
+        let mut form = self::form();
+        form.max_lengths = vec!["name=five".to_string()];
+        assert!(super::validate(&form, &values(&[("email", "a@b.c")])).is_err());
+    }
+
+    #[tokio::test]
+    async fn insert() {
+        let database = camino::Utf8PathBuf::from_path_buf(std::env::temp_dir())
+            .unwrap()
+            .join(format!("fastn-form-{}.sqlite", std::process::id()));
+        std::fs::remove_file(&database).ok();
+        rusqlite::Connection::open(&database)
+            .unwrap()
+            .execute("CREATE TABLE subscribers (email TEXT, name TEXT)", [])
+            .unwrap();
+
+        let valid = values(&[("email", "a@b.c"), ("name", "amit")]);
+        super::insert(database.clone(), "subscribers", &valid)
+            .await
+            .unwrap();
+        let (email, name): (String, String) = rusqlite::Connection::open(&database)
+            .unwrap()
+            .query_row("SELECT email, name FROM subscribers", [], |row| {
+                Ok((row.get(0)?, row.get(1)?))
+            })
+            .unwrap();
+        assert_eq!((email.as_str(), name.as_str()), ("a@b.c", "amit"));
+
+        // names are quoted into the query, so only identifiers are accepted
+        for (table, values) in [
+            ("subscribers\"; DROP TABLE subscribers; --", valid.clone()),
+            ("", valid.clone()),
+            ("subscribers", values(&[("email\" TEXT", "a@b.c")])),
+            ("subscribers", values(&[("name) VALUES ('x'); --", "a")])),
+        ] {
+            match super::insert(database.clone(), table, &values).await {
+                Err(fastn_core::Error::PackageError { message }) => {
+                    assert!(
+                        message.contains("invalid table or field name"),
+                        "{}",
+                        message
+                    )
+                }
+                r => panic!("{:?} {:?} is not rejected: {:?}", table, values, r),
+            }
+        }
+
+        std::fs::remove_file(&database).ok();
+    }
+}
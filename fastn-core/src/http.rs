@@ -249,7 +249,6 @@ pub(crate) struct ResponseBuilder {
     // remaining
 }
 
-// We will no do stream, data is going to less from services
 impl ResponseBuilder {
     // chain implementation
     // .build
@@ -300,6 +299,29 @@ impl ResponseBuilder {
         };
         response_builder.body(content)
     }
+
+    /// Forwards the body of `response` as it arrives instead of buffering it
+    pub fn stream_reqwest(response: reqwest::Response) -> fastn_core::http::Response {
+        let mut response_builder = actix_web::HttpResponse::build(response.status());
+        for header in response
+            .headers()
+            .iter()
+            .filter(|(h, _)| *h != "connection" && *h != "content-length")
+        {
+            response_builder.insert_header(header);
+        }
+        response_builder.streaming(futures::stream::unfold(
+            Some(response),
+            |response| async move {
+                let mut response = response?;
+                match response.chunk().await {
+                    Ok(Some(chunk)) => Some((Ok(chunk), Some(response))),
+                    Ok(None) => None,
+                    Err(e) => Some((Err(e), None)),
+                }
+            },
+        ))
+    }
 }
 
 pub(crate) fn url_regex() -> regex::Regex {
@@ -0,0 +1,254 @@
+//! Locale aware formatting of numbers, currencies and dates for the `format-number` and
+//! `format-date` processors.
+//!
+//! Fluent leaves number formatting to the application, and fastn has no CLDR data, so this knows
+//! the conventions of the common languages and falls back to the English ones for the others.
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum NumberStyle {
+    Decimal,
+    Percent,
+    /// An ISO 4217 currency code, like `INR`
+    Currency(String),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct NumberFormat {
+    pub style: NumberStyle,
+    pub minimum_fraction_digits: Option<usize>,
+    pub maximum_fraction_digits: Option<usize>,
+}
+
+impl NumberFormat {
+    pub(crate) fn new(style: NumberStyle) -> NumberFormat {
+        NumberFormat {
+            style,
+            minimum_fraction_digits: None,
+            maximum_fraction_digits: None,
+        }
+    }
+
+    fn fraction_digits(&self) -> (usize, usize) {
+        let (minimum, maximum) = match self.style {
+            NumberStyle::Decimal => (0, 3),
+            NumberStyle::Percent => (0, 0),
+            NumberStyle::Currency(ref code) if ZERO_DECIMAL_CURRENCIES.contains(&code.as_str()) => {
+                (0, 0)
+            }
+            NumberStyle::Currency(_) => (2, 2),
+        };
+        let minimum = self.minimum_fraction_digits.unwrap_or(minimum);
+        let maximum = self.maximum_fraction_digits.unwrap_or(maximum).max(minimum);
+        (minimum, maximum)
+    }
+}
+
+const ZERO_DECIMAL_CURRENCIES: &[&str] = &["JPY", "KRW", "VND", "CLP", "ISK"];
+
+/// The separators of a locale
+struct Symbols {
+    decimal: &'static str,
+    group: &'static str,
+    /// Groups of two digits after the first thousand, `12,34,567`
+    indian_grouping: bool,
+    /// Currency symbols and the percent sign come after the number, separated by a space
+    symbol_after: bool,
+}
+
+fn symbols(locale: &str) -> Symbols {
+    let (language, region) = split_locale(locale);
+    let (decimal, group, symbol_after) = match language.as_str() {
+        "de" | "es" | "it" | "nl" | "pt" | "id" | "tr" | "da" | "el" | "ro" | "hr" | "sl"
+        | "sr" | "vi" => (",", ".", true),
+        "fr" | "ru" | "pl" | "cs" | "sk" | "sv" | "fi" | "nb" | "no" | "uk" | "hu" | "bg"
+        | "lt" | "lv" | "et" => (",", "\u{a0}", true),
+        _ => (".", ",", false),
+    };
+    Symbols {
+        decimal,
+        group,
+        indian_grouping: matches!(
+            language.as_str(),
+            "hi" | "bn" | "mr" | "gu" | "ta" | "te" | "kn" | "ml" | "pa" | "or" | "as"
+        ) || region.as_deref().eq(&Some("IN")),
+        symbol_after,
+    }
+}
+
+/// The language and the region of a locale like `en-IN` or `pt_BR`
+fn split_locale(locale: &str) -> (String, Option<String>) {
+    let mut parts = locale.split(|c| c == '-' || c == '_');
+    let language = parts.next().unwrap_or_default().to_lowercase();
+    let region = parts.find(|p| p.len() == 2).map(|p| p.to_uppercase());
+    (language, region)
+}
+
+fn currency_symbol(code: &str) -> Option<&'static str> {
+    Some(match code {
+        "USD" => "$",
+        "EUR" => "€",
+        "GBP" => "£",
+        "INR" => "₹",
+        "JPY" | "CNY" => "¥",
+        "KRW" => "₩",
+        "RUB" => "₽",
+        "BRL" => "R$",
+        _ => return None,
+    })
+}
+
+pub(crate) fn format_number(value: f64, locale: &str, format: &NumberFormat) -> String {
+    let symbols = symbols(locale);
+    let (minimum, maximum) = format.fraction_digits();
+    let value = match format.style {
+        NumberStyle::Percent => value * 100.0,
+        _ => value,
+    };
+
+    let rounded = format!("{:.*}", maximum, value.abs());
+    let (integer, fraction) = rounded.split_once('.').unwrap_or((rounded.as_str(), ""));
+    let mut fraction = fraction.trim_end_matches('0').to_string();
+    while fraction.len() < minimum {
+        fraction.push('0');
+    }
+
+    let mut number = group(integer, symbols.group, symbols.indian_grouping);
+    if !fraction.is_empty() {
+        number.push_str(symbols.decimal);
+        number.push_str(fraction.as_str());
+    }
+    let sign = if value < 0.0 && number.chars().any(|c| c.is_ascii_digit() && c != '0') {
+        "-"
+    } else {
+        ""
+    };
+
+    match format.style {
+        NumberStyle::Decimal => format!("{}{}", sign, number),
+        NumberStyle::Percent if symbols.symbol_after => format!("{}{}\u{a0}%", sign, number),
+        NumberStyle::Percent => format!("{}{}%", sign, number),
+        NumberStyle::Currency(ref code) => {
+            let code = code.to_uppercase();
+            match currency_symbol(code.as_str()) {
+                Some(symbol) if symbols.symbol_after => {
+                    format!("{}{}\u{a0}{}", sign, number, symbol)
+                }
+                Some(symbol) => format!("{}{}{}", sign, symbol, number),
+                None if symbols.symbol_after => format!("{}{}\u{a0}{}", sign, number, code),
+                None => format!("{}{}\u{a0}{}", sign, code, number),
+            }
+        }
+    }
+}
+
+fn group(integer: &str, separator: &str, indian_grouping: bool) -> String {
+    let digits = integer.chars().collect::<Vec<_>>();
+    let mut groups = vec![];
+    let mut end = digits.len();
+    let mut size = 3;
+    while end > size {
+        groups.push(digits[end - size..end].iter().collect::<String>());
+        end -= size;
+        if indian_grouping {
+            size = 2;
+        }
+    }
+    groups.push(digits[..end].iter().collect::<String>());
+    groups.reverse();
+    groups.join(separator)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum DateOrder {
+    DayMonthYear,
+    MonthDayYear,
+    YearMonthDay,
+}
+
+/// Formats a date the way the locale writes dates in numbers, `5/1/2023` in `en`, `01.05.2023`
+/// in `de` and `2023-05-01` in `sv`
+pub(crate) fn format_date(date: &chrono::NaiveDate, locale: &str) -> String {
+    use chrono::Datelike;
+
+    let (language, region) = split_locale(locale);
+    let (order, separator) = match language.as_str() {
+        "en" if region.is_none() || region.as_deref().eq(&Some("US")) => {
+            (DateOrder::MonthDayYear, "/")
+        }
+        "zh" | "ja" => (DateOrder::YearMonthDay, "/"),
+        "ko" | "hu" => (DateOrder::YearMonthDay, ". "),
+        "sv" | "lt" => (DateOrder::YearMonthDay, "-"),
+        "de" | "ru" | "pl" | "tr" | "fi" | "nb" | "no" | "cs" | "sk" | "uk" | "ro" | "da"
+        | "bg" | "hr" | "lv" | "et" => (DateOrder::DayMonthYear, "."),
+        "nl" => (DateOrder::DayMonthYear, "-"),
+        _ => (DateOrder::DayMonthYear, "/"),
+    };
+    // american dates are not zero padded
+    let (day, month) = match order {
+        DateOrder::MonthDayYear => (date.day().to_string(), date.month().to_string()),
+        _ => (format!("{:02}", date.day()), format!("{:02}", date.month())),
+    };
+    let year = date.year().to_string();
+    let parts = match order {
+        DateOrder::DayMonthYear => [day, month, year],
+        DateOrder::MonthDayYear => [month, day, year],
+        DateOrder::YearMonthDay => [year, month, day],
+    };
+    parts.join(separator)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{NumberFormat, NumberStyle};
+
+    #[test]
+    fn numbers() {
+        let decimal = NumberFormat::new(NumberStyle::Decimal);
+        assert_eq!(
+            super::format_number(1234567.891, "en", &decimal),
+            "1,234,567.891"
+        );
+        assert_eq!(
+            super::format_number(1234567.891, "hi", &decimal),
+            "12,34,567.891"
+        );
+        assert_eq!(
+            super::format_number(1234567.891, "de", &decimal),
+            "1.234.567,891"
+        );
+        assert_eq!(
+            super::format_number(1234567.891, "fr", &decimal),
+            "1\u{a0}234\u{a0}567,891"
+        );
+        assert_eq!(super::format_number(-0.0001, "en", &decimal), "0");
+        assert_eq!(
+            super::format_number(0.256, "en", &NumberFormat::new(NumberStyle::Percent)),
+            "26%"
+        );
+    }
+
+    #[test]
+    fn currencies() {
+        let inr = NumberFormat::new(NumberStyle::Currency("INR".to_string()));
+        assert_eq!(
+            super::format_number(123456.5, "en-IN", &inr),
+            "₹1,23,456.50"
+        );
+        let eur = NumberFormat::new(NumberStyle::Currency("EUR".to_string()));
+        assert_eq!(
+            super::format_number(-1234.5, "de", &eur),
+            "-1.234,50\u{a0}€"
+        );
+        let jpy = NumberFormat::new(NumberStyle::Currency("JPY".to_string()));
+        assert_eq!(super::format_number(1234.5, "ja", &jpy), "¥1,234");
+    }
+
+    #[test]
+    fn dates() {
+        let date = chrono::NaiveDate::from_ymd_opt(2023, 5, 1).unwrap();
+        assert_eq!(super::format_date(&date, "en"), "5/1/2023");
+        assert_eq!(super::format_date(&date, "en-GB"), "01/05/2023");
+        assert_eq!(super::format_date(&date, "de"), "01.05.2023");
+        assert_eq!(super::format_date(&date, "sv"), "2023-05-01");
+    }
+}
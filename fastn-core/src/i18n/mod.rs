@@ -1,5 +1,10 @@
+mod format;
+mod package;
 pub mod translation;
 
+pub(crate) use format::{format_date, format_number, NumberFormat, NumberStyle};
+pub(crate) use package::{default_locale, locale, message, negotiate};
+
 type Bundle = fluent::bundle::FluentBundle<
     fluent::FluentResource,
     intl_memoizer::concurrent::IntlLangMemoizer,
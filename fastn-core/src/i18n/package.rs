@@ -0,0 +1,223 @@
+//! Fluent messages shipped by packages.
+//!
+//! A package keeps its messages in `i18n/<language>/*.ftl`, the same layout fastn uses for its
+//! own strings, and documents look them up with the `message` processor:
+//!
+//! ```ftd
+//! -- string cart-summary:
+//! $processor$: pr.message
+//! id: cart-summary
+//! count: $cart-size
+//! ```
+//!
+//! Number arguments are passed to Fluent as numbers, so messages can select on their plural
+//! category. The locale is the language prefix of the document (`hi/index.ftd`), else the most
+//! preferred language of the `Accept-Language` header the package has messages for, else the
+//! language of the package, and messages missing in that locale fall back to the language of the
+//! package.
+
+pub(crate) const I18N_DIRECTORY: &str = "i18n";
+
+/// The locales a package has messages for, the names of the directories in `i18n`
+pub(crate) fn available_locales(root: &camino::Utf8Path) -> Vec<String> {
+    let mut locales = std::fs::read_dir(root.join(I18N_DIRECTORY))
+        .map(|entries| {
+            entries
+                .flatten()
+                .filter(|e| e.path().is_dir())
+                .filter_map(|e| e.file_name().into_string().ok())
+                .collect::<Vec<_>>()
+        })
+        .unwrap_or_default();
+    locales.sort();
+    locales
+}
+
+/// The locale `document_id` is rendered in, see the module documentation
+pub(crate) fn locale(
+    config: &fastn_core::Config,
+    root: &camino::Utf8Path,
+    document_id: &str,
+) -> String {
+    let available = available_locales(root);
+    if let Some(prefix) = document_id.trim_start_matches('/').split('/').next() {
+        if available.iter().any(|l| l.eq(prefix)) {
+            return prefix.to_string();
+        }
+    }
+    if let Some(locale) = config
+        .request
+        .as_ref()
+        .and_then(|r| r.headers().get("accept-language"))
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| negotiate(v, &available))
+    {
+        return locale;
+    }
+    default_locale(&config.package)
+}
+
+/// The language of the package, English if it has none
+pub(crate) fn default_locale(package: &fastn_core::Package) -> String {
+    package.language.clone().unwrap_or_else(|| "en".to_string())
+}
+
+/// The languages of an `Accept-Language` header, most preferred first
+pub(crate) fn parse_accept_language(header: &str) -> Vec<String> {
+    let mut languages = header
+        .split(',')
+        .filter_map(|part| {
+            let mut parts = part.split(';');
+            let tag = parts.next()?.trim();
+            let quality = parts
+                .find_map(|p| p.trim().strip_prefix("q="))
+                .and_then(|q| q.trim().parse::<f32>().ok())
+                .unwrap_or(1.0);
+            if tag.is_empty() || tag.eq("*") || quality <= 0.0 {
+                return None;
+            }
+            Some((tag.to_string(), quality))
+        })
+        .collect::<Vec<_>>();
+    // a stable sort keeps the order of the header for languages of the same quality
+    languages.sort_by(|a, b| b.1.total_cmp(&a.1));
+    languages.into_iter().map(|(tag, _)| tag).collect()
+}
+
+/// The most preferred language of an `Accept-Language` header out of `available`, a language
+/// matches its region specific variants in the header, `hi` matches `hi-IN`
+pub(crate) fn negotiate(header: &str, available: &[String]) -> Option<String> {
+    parse_accept_language(header).into_iter().find_map(|tag| {
+        let primary = tag.split('-').next().unwrap_or_default().to_string();
+        available
+            .iter()
+            .find(|l| l.eq_ignore_ascii_case(tag.as_str()))
+            .or_else(|| {
+                available
+                    .iter()
+                    .find(|l| l.eq_ignore_ascii_case(primary.as_str()))
+            })
+            .cloned()
+    })
+}
+
+fn bundle(
+    root: &camino::Utf8Path,
+    locale: &str,
+) -> fastn_core::Result<Option<fastn_core::i18n::Bundle>> {
+    let directory = root.join(I18N_DIRECTORY).join(locale);
+    if !directory.is_dir() {
+        return Ok(None);
+    }
+    let language = locale
+        .parse()
+        .map_err(|_| fastn_core::Error::PackageError {
+            message: format!("`{}` is not a valid language", locale),
+        })?;
+    let mut bundle = fluent::bundle::FluentBundle::new_concurrent(vec![language]);
+    // the isolation marks around arguments end up in attributes and urls
+    bundle.set_use_isolating(false);
+
+    let mut files = std::fs::read_dir(&directory)?
+        .flatten()
+        .map(|e| e.path())
+        .filter(|p| p.extension().map(|e| e.eq("ftl")).unwrap_or(false))
+        .collect::<Vec<_>>();
+    files.sort();
+    for file in files {
+        let resource = fluent::FluentResource::try_new(std::fs::read_to_string(&file)?).map_err(
+            |(_, errors)| fastn_core::Error::PackageError {
+                message: format!("failed to parse {}: {:?}", file.display(), errors),
+            },
+        )?;
+        bundle
+            .add_resource(resource)
+            .map_err(|errors| fastn_core::Error::PackageError {
+                message: format!("failed to load {}: {:?}", file.display(), errors),
+            })?;
+    }
+    Ok(Some(bundle))
+}
+
+/// Formats the message `id` of the package at `root` in `locale`, or its `attribute`, falling
+/// back to the `fallback` locale
+pub(crate) fn message(
+    root: &camino::Utf8Path,
+    locale: &str,
+    fallback: &str,
+    id: &str,
+    attribute: Option<&str>,
+    args: &fluent::FluentArgs,
+) -> fastn_core::Result<String> {
+    let mut locales = vec![locale];
+    if !fallback.eq(locale) {
+        locales.push(fallback);
+    }
+    for locale in locales {
+        let bundle = match bundle(root, locale)? {
+            Some(bundle) => bundle,
+            None => continue,
+        };
+        let message = match bundle.get_message(id) {
+            Some(message) => message,
+            None => continue,
+        };
+        let pattern = match attribute {
+            Some(attribute) => message.get_attribute(attribute).map(|a| a.value()),
+            None => message.value(),
+        };
+        let pattern = match pattern {
+            Some(pattern) => pattern,
+            None => continue,
+        };
+        let mut errors = vec![];
+        let text = bundle.format_pattern(pattern, Some(args), &mut errors);
+        if !errors.is_empty() {
+            return Err(fastn_core::Error::PackageError {
+                message: format!("errors in message `{}` of {}: {:?}", id, locale, errors),
+            });
+        }
+        return Ok(text.to_string());
+    }
+    Err(fastn_core::Error::PackageError {
+        message: match attribute {
+            Some(attribute) => format!(
+                "message `{}.{}` not found in {}/{}",
+                id, attribute, I18N_DIRECTORY, locale
+            ),
+            None => format!(
+                "message `{}` not found in {}/{}",
+                id, I18N_DIRECTORY, locale
+            ),
+        },
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn accept_language() {
+        assert_eq!(
+            super::parse_accept_language("fr-CH, fr;q=0.9, en;q=0.8, de;q=0.7, *;q=0.5"),
+            vec!["fr-CH", "fr", "en", "de"]
+        );
+        assert_eq!(
+            super::parse_accept_language("en;q=0.5, hi-IN"),
+            vec!["hi-IN", "en"]
+        );
+    }
+
+    #[test]
+    fn negotiate() {
+        let available = vec!["en".to_string(), "hi".to_string()];
+        assert_eq!(
+            super::negotiate("hi-IN,en;q=0.8", &available),
+            Some("hi".to_string())
+        );
+        assert_eq!(
+            super::negotiate("de, en-GB;q=0.5", &available),
+            Some("en".to_string())
+        );
+        assert_eq!(super::negotiate("de", &available), None);
+    }
+}
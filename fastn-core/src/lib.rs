@@ -2,6 +2,7 @@ extern crate self as fastn_core;
 
 #[macro_use]
 pub mod utils;
+mod access_log;
 mod apis;
 mod auto_import;
 mod cache;
@@ -9,11 +10,14 @@ pub mod commands;
 mod config;
 mod controller;
 mod cr;
+mod design_tokens;
 mod doc;
 mod file;
 mod font;
+mod form;
 mod history;
 mod package;
+mod pagination;
 pub(crate) mod watcher;
 #[macro_use]
 mod http;
@@ -22,34 +26,46 @@ mod ds;
 mod error;
 mod i18n;
 pub mod library;
+mod library2022;
+mod metrics;
+mod multilingual;
 mod proxy;
+mod rate_limit;
+mod render_cache;
 pub mod sitemap;
 mod snapshot;
+mod static_asset;
 mod sync_utils;
+mod tls;
 mod track;
 mod tracker;
+mod translate;
 mod translation;
 mod version;
-// mod wasm;
-mod library2022;
+mod wasm;
 mod workspace;
 
 pub(crate) use auto_import::AutoImport;
 pub use commands::{
-    abort_merge::abort_merge, add::add, build::build, clone::clone, close_cr::close_cr,
-    create_cr::create_cr, create_package::create_package, diff::diff, edit::edit,
-    mark_resolved::mark_resolved, mark_upto_date::mark_upto_date, merge::merge, query::query,
-    resolve_conflict::resolve_conflict, revert::revert, rm::rm, serve::listen,
+    abort_merge::abort_merge, add::add, build::build, build::build_translations, clone::clone,
+    close_cr::close_cr, create_cr::create_cr, create_package::create_package, diff::diff,
+    edit::edit, mark_resolved::mark_resolved, mark_upto_date::mark_upto_date, merge::merge,
+    query::query, resolve_conflict::resolve_conflict, revert::revert, rm::rm, serve::listen,
     start_tracking::start_tracking, status::status, sync2::sync2,
     translation_status::translation_status, update::update,
 };
+pub use access_log::AccessLogFormat;
 pub use config::{Config, FTDEdition};
+pub use design_tokens::DesignTokensFormat;
 pub use error::Error;
 pub use file::File;
+pub use tls::Tls;
+pub use translate::TranslationFormat;
 pub(crate) use file::{get_file, paths_to_files, Document, Static};
 pub(crate) use font::Font;
 pub use library::{FastnLibrary, Library, Library2};
 pub use library2022::Library2022;
+pub use pagination::Pagination;
 pub(crate) use package::dependency::Dependency;
 pub use package::user_group;
 pub(crate) use package::Package;
@@ -126,6 +126,7 @@ impl Library {
             fastn_core::Config::download_required_file(&lib.config.root, name, package)
                 .await
                 .ok()?;
+            fastn_core::metrics::record_dependency_download("file");
             // Explicit check for the current package.
             if name.starts_with(package.name.as_str()) {
                 let new_name = name.replacen(package.name.as_str(), "", 1);
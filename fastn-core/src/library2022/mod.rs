@@ -211,8 +211,27 @@ impl Library2022 {
             processor = processor
         );
         let line_number = ast.line_number();
-        let (_processor, value, kind) = get_processor_data(ast, doc)?;
-        match processor.as_str() {
+        let (_processor, mut value, kind) = get_processor_data(ast, doc)?;
+        let page_size = fastn_core::pagination::take_page_size(&mut value, &kind, doc.name)?;
+        if page_size.is_some() && self.config.request.is_some() {
+            self.config.request_dependent = true;
+        }
+        if matches!(
+            processor.as_str(),
+            "http" | "request-data" | "user-details" | "is-reader" | "package-query" | "query"
+        ) {
+            self.config.request_dependent = true;
+        }
+        // the locale, and the urls of the language switcher, depend on the request
+        if matches!(
+            processor.as_str(),
+            "message" | "format-number" | "format-date" | "language-switcher"
+        ) && self.config.request.is_some()
+        {
+            self.config.request_dependent = true;
+        }
+        let start = std::time::Instant::now();
+        let value = match processor.as_str() {
             "figma-typo-token" => {
                 processor::figma_typography_tokens::process_typography_tokens(value, kind, doc)
             }
@@ -266,11 +285,40 @@ impl Library2022 {
             }
             "user-details" => processor::user_details::process(value, kind, doc, &self.config),
             "fastn-apps" => processor::apps::process(value, kind, doc, &self.config),
+            "app-config" => processor::apps::process_config(value, kind, doc, &self.config),
             "is-reader" => processor::user_group::is_reader(value, kind, doc, &self.config).await,
             "package-query" => processor::sqlite::process(value, kind, doc, &self.config).await,
             "package-tree" => {
                 processor::package_tree::process(value, kind, doc, &self.config).await
             }
+            "pagination" => processor::pagination::process(value, kind, doc, &self.config),
+            "message" => {
+                processor::i18n::message(value, kind, doc, &self.config, self.document_id.as_str())
+            }
+            "format-number" => processor::i18n::format_number(
+                value,
+                kind,
+                doc,
+                &self.config,
+                self.document_id.as_str(),
+            ),
+            "format-date" => processor::i18n::format_date(
+                value,
+                kind,
+                doc,
+                &self.config,
+                self.document_id.as_str(),
+            ),
+            "language-switcher" => {
+                processor::language_switcher::process(
+                    value,
+                    kind,
+                    doc,
+                    &self.config,
+                    self.document_id.as_str(),
+                )
+                .await
+            }
             "query" => {
                 processor::query::process(
                     value,
@@ -286,6 +334,16 @@ impl Library2022 {
                 line_number,
                 message: format!("fastn-Error: No such processor: {}", t),
             }),
+        };
+        fastn_core::metrics::record_processor(processor.as_str(), start.elapsed());
+        match (value, page_size) {
+            (Ok(value), Some(page_size)) => Ok(fastn_core::pagination::paginate(
+                value,
+                page_size,
+                &mut self.config,
+                self.base_url.as_str(),
+            )),
+            (value, _) => value,
         }
     }
 }
@@ -29,3 +29,49 @@ pub fn process(
     let installed_apps = fastn_core::ds::LengthList::from_owned(apps);
     doc.from_json(&installed_apps, &kind, value.line_number())
 }
+
+/// Returns the `config` of the app, named by the `app` header or else the one the current
+/// document belongs to, or the value of its `key` header
+pub fn process_config(
+    value: ftd::ast::VariableValue,
+    kind: ftd::interpreter::Kind,
+    doc: &ftd::interpreter::TDoc,
+    config: &fastn_core::Config,
+) -> ftd::interpreter::Result<ftd::interpreter::Value> {
+    let headers = match value.get_record(doc.name) {
+        Ok(val) => val.2.to_owned(),
+        Err(_e) => ftd::ast::HeaderValues::new(vec![]),
+    };
+    let app_name = headers.get_optional_string_by_key("app", doc.name, value.line_number())?;
+    let key = headers.get_optional_string_by_key("key", doc.name, value.line_number())?;
+
+    let app = config
+        .package
+        .apps
+        .iter()
+        .find(|a| match app_name.as_ref() {
+            Some(name) => a.name.eq(name),
+            None => doc
+                .name
+                .trim_start_matches("-/")
+                .starts_with(a.package.name.trim_matches('/')),
+        });
+    let app = match app {
+        Some(app) => app,
+        None => {
+            return ftd::interpreter::utils::e2(
+                match app_name {
+                    Some(name) => format!("app `{}` is not installed", name),
+                    None => format!("document `{}` does not belong to an app", doc.name),
+                },
+                doc.name,
+                value.line_number(),
+            )
+        }
+    };
+
+    match key {
+        Some(key) => doc.from_json(&app.config.get(key.as_str()), &kind, value.line_number()),
+        None => doc.from_json(&app.config, &kind, value.line_number()),
+    }
+}
@@ -0,0 +1,268 @@
+/// `message`: formats a Fluent message of the package, every header other than `id`,
+/// `attribute` and `locale` is an argument of the message
+pub fn message(
+    value: ftd::ast::VariableValue,
+    kind: ftd::interpreter::Kind,
+    doc: &ftd::interpreter::TDoc,
+    config: &fastn_core::Config,
+    document_id: &str,
+) -> ftd::interpreter::Result<ftd::interpreter::Value> {
+    let (headers, line_number) = headers(&value, &kind, doc)?;
+    let id = headers
+        .get_optional_string_by_key("id", doc.name, line_number)?
+        .ok_or(ftd::interpreter::Error::ParseError {
+            message: "`id` not found".to_string(),
+            doc_id: doc.name.to_string(),
+            line_number,
+        })?;
+    let attribute = headers.get_optional_string_by_key("attribute", doc.name, line_number)?;
+    let (root, fallback) = package_root_and_language(doc.name, config);
+    let locale = match headers.get_optional_string_by_key("locale", doc.name, line_number)? {
+        Some(locale) => locale,
+        None => fastn_core::i18n::locale(config, &root, document_id),
+    };
+
+    let mut args = fluent::FluentArgs::new();
+    for header in headers.0.iter() {
+        if matches!(
+            header.key.as_str(),
+            ftd::PROCESSOR_MARKER | "id" | "attribute" | "locale"
+        ) {
+            continue;
+        }
+        let argument = match argument(header, doc)? {
+            Argument::Number(n) => fluent::FluentValue::from(n),
+            Argument::String(s) => fluent::FluentValue::from(s),
+        };
+        args.set(header.key.to_string(), argument);
+    }
+
+    match fastn_core::i18n::message(
+        &root,
+        locale.as_str(),
+        fallback.as_str(),
+        id.as_str(),
+        attribute.as_deref(),
+        &args,
+    ) {
+        Ok(text) => Ok(ftd::interpreter::Value::String { text }),
+        Err(e) => ftd::interpreter::utils::e2(e.to_string(), doc.name, line_number),
+    }
+}
+
+/// `format-number`: formats `value` as a number, a percentage (`style: percent`) or an amount of
+/// `currency` (`style: currency`) in the locale of the document
+pub fn format_number(
+    value: ftd::ast::VariableValue,
+    kind: ftd::interpreter::Kind,
+    doc: &ftd::interpreter::TDoc,
+    config: &fastn_core::Config,
+    document_id: &str,
+) -> ftd::interpreter::Result<ftd::interpreter::Value> {
+    let (headers, line_number) = headers(&value, &kind, doc)?;
+    let number = match headers.get_by_key_optional("value", doc.name, line_number)? {
+        Some(header) => match argument(header, doc)? {
+            Argument::Number(n) => n,
+            Argument::String(s) => {
+                return ftd::interpreter::utils::e2(
+                    format!("`value` is not a number: {}", s),
+                    doc.name,
+                    line_number,
+                )
+            }
+        },
+        None => return ftd::interpreter::utils::e2("`value` not found", doc.name, line_number),
+    };
+
+    let style = match headers
+        .get_optional_string_by_key("style", doc.name, line_number)?
+        .as_deref()
+    {
+        None | Some("decimal") => fastn_core::i18n::NumberStyle::Decimal,
+        Some("percent") => fastn_core::i18n::NumberStyle::Percent,
+        Some("currency") => fastn_core::i18n::NumberStyle::Currency(
+            headers
+                .get_optional_string_by_key("currency", doc.name, line_number)?
+                .ok_or(ftd::interpreter::Error::ParseError {
+                    message: "`currency` is needed with `style: currency`".to_string(),
+                    doc_id: doc.name.to_string(),
+                    line_number,
+                })?
+                .to_uppercase(),
+        ),
+        Some(t) => {
+            return ftd::interpreter::utils::e2(
+                format!(
+                    "unknown style `{}`, expected `decimal`, `percent` or `currency`",
+                    t
+                ),
+                doc.name,
+                line_number,
+            )
+        }
+    };
+    let mut format = fastn_core::i18n::NumberFormat::new(style);
+    format.minimum_fraction_digits =
+        fraction_digits(&headers, "minimum-fraction-digits", doc, line_number)?;
+    format.maximum_fraction_digits =
+        fraction_digits(&headers, "maximum-fraction-digits", doc, line_number)?;
+
+    let locale = locale(&headers, doc, config, document_id, line_number)?;
+    Ok(ftd::interpreter::Value::String {
+        text: fastn_core::i18n::format_number(number, locale.as_str(), &format),
+    })
+}
+
+/// `format-date`: formats `value`, a `yyyy-mm-dd` date or an RFC 3339 date time, in the locale
+/// of the document
+pub fn format_date(
+    value: ftd::ast::VariableValue,
+    kind: ftd::interpreter::Kind,
+    doc: &ftd::interpreter::TDoc,
+    config: &fastn_core::Config,
+    document_id: &str,
+) -> ftd::interpreter::Result<ftd::interpreter::Value> {
+    let (headers, line_number) = headers(&value, &kind, doc)?;
+    let date = match headers.get_optional_string_by_key("value", doc.name, line_number)? {
+        Some(date) if date.starts_with('$') => match doc.get_value(line_number, date.as_str())? {
+            ftd::interpreter::Value::Date { value } => Some(value),
+            ftd::interpreter::Value::DateTime { value } => Some(value.date_naive()),
+            ftd::interpreter::Value::String { text } => parse_date(text.as_str()),
+            _ => None,
+        },
+        Some(date) => parse_date(date.as_str()),
+        None => return ftd::interpreter::utils::e2("`value` not found", doc.name, line_number),
+    };
+    let date = match date {
+        Some(date) => date,
+        None => {
+            return ftd::interpreter::utils::e2(
+                "`value` is not a date, expected yyyy-mm-dd",
+                doc.name,
+                line_number,
+            )
+        }
+    };
+
+    let locale = locale(&headers, doc, config, document_id, line_number)?;
+    Ok(ftd::interpreter::Value::String {
+        text: fastn_core::i18n::format_date(&date, locale.as_str()),
+    })
+}
+
+enum Argument {
+    Number(f64),
+    String(String),
+}
+
+/// The value of a header, resolving `$` references, numbers are kept as numbers so Fluent can
+/// select on their plural category
+fn argument(
+    header: &ftd::ast::HeaderValue,
+    doc: &ftd::interpreter::TDoc,
+) -> ftd::interpreter::Result<Argument> {
+    let value = header.value.string(doc.name)?;
+    if !value.starts_with('$') {
+        return Ok(match value.trim().parse::<f64>() {
+            Ok(n) => Argument::Number(n),
+            Err(_) => Argument::String(value),
+        });
+    }
+    Ok(match doc.get_value(header.line_number, value.as_str())? {
+        ftd::interpreter::Value::Integer { value } => Argument::Number(value as f64),
+        ftd::interpreter::Value::Decimal { value } => Argument::Number(value),
+        ftd::interpreter::Value::String { text } => Argument::String(text),
+        ftd::interpreter::Value::Boolean { value } => Argument::String(value.to_string()),
+        t => {
+            return ftd::interpreter::utils::e2(
+                format!("`{}` can not be a message argument: {:?}", header.key, t),
+                doc.name,
+                header.line_number,
+            )
+        }
+    })
+}
+
+fn headers(
+    value: &ftd::ast::VariableValue,
+    kind: &ftd::interpreter::Kind,
+    doc: &ftd::interpreter::TDoc,
+) -> ftd::interpreter::Result<(ftd::ast::HeaderValues, usize)> {
+    if !kind.is_string() {
+        return ftd::interpreter::utils::e2(
+            format!("Expected kind is `string`, found: `{:?}`", kind),
+            doc.name,
+            value.line_number(),
+        );
+    }
+    Ok(match value.get_record(doc.name) {
+        Ok(val) => (val.2.to_owned(), val.5.to_owned()),
+        Err(_) => (ftd::ast::HeaderValues::new(vec![]), value.line_number()),
+    })
+}
+
+/// The `locale` header, else the locale the document is rendered in
+fn locale(
+    headers: &ftd::ast::HeaderValues,
+    doc: &ftd::interpreter::TDoc,
+    config: &fastn_core::Config,
+    document_id: &str,
+    line_number: usize,
+) -> ftd::interpreter::Result<String> {
+    Ok(
+        match headers.get_optional_string_by_key("locale", doc.name, line_number)? {
+            Some(locale) => locale,
+            None => {
+                let (root, _) = package_root_and_language(doc.name, config);
+                fastn_core::i18n::locale(config, &root, document_id)
+            }
+        },
+    )
+}
+
+fn fraction_digits(
+    headers: &ftd::ast::HeaderValues,
+    key: &str,
+    doc: &ftd::interpreter::TDoc,
+    line_number: usize,
+) -> ftd::interpreter::Result<Option<usize>> {
+    match headers.get_optional_string_by_key(key, doc.name, line_number)? {
+        Some(digits) => match digits.trim().parse() {
+            Ok(digits) => Ok(Some(digits)),
+            Err(_) => ftd::interpreter::utils::e2(
+                format!("`{}` is not a number: {}", key, digits),
+                doc.name,
+                line_number,
+            ),
+        },
+        None => Ok(None),
+    }
+}
+
+fn parse_date(date: &str) -> Option<chrono::NaiveDate> {
+    chrono::NaiveDate::parse_from_str(date.trim(), "%Y-%m-%d")
+        .ok()
+        .or_else(|| {
+            chrono::DateTime::parse_from_rfc3339(date.trim())
+                .ok()
+                .map(|d| d.date_naive())
+        })
+}
+
+/// The root and the language of the package `doc_name` belongs to, dependencies look up their
+/// messages in their own `i18n` directory
+fn package_root_and_language(
+    doc_name: &str,
+    config: &fastn_core::Config,
+) -> (camino::Utf8PathBuf, String) {
+    let packages = config.all_packages.borrow();
+    let package = packages
+        .values()
+        .filter(|p| doc_name.starts_with(format!("{}/", p.name).as_str()))
+        .max_by_key(|p| p.name.len())
+        .unwrap_or(&config.package);
+    (
+        config.get_root_for_package(package),
+        fastn_core::i18n::default_locale(package),
+    )
+}
@@ -0,0 +1,24 @@
+/// `language-switcher`: the current document in every language of the package, as a list of
+/// `pr.language-data`. When serving, the urls pick the language with `?lang=` so `fastn serve`
+/// remembers the choice of the reader.
+pub async fn process<'a>(
+    value: ftd::ast::VariableValue,
+    kind: ftd::interpreter::Kind,
+    doc: &ftd::interpreter::TDoc<'a>,
+    config: &fastn_core::Config,
+    document_id: &str,
+) -> ftd::interpreter::Result<ftd::interpreter::Value> {
+    let path = fastn_core::multilingual::document_path(config, document_id);
+    let mut alternates = fastn_core::multilingual::alternates(config, path.as_str()).await;
+    if config.request.is_some() {
+        for alternate in alternates.iter_mut() {
+            alternate.url = format!(
+                "{}?{}={}",
+                alternate.url,
+                fastn_core::multilingual::LANGUAGE_QUERY,
+                alternate.language
+            );
+        }
+    }
+    doc.from_json(&alternates, &kind, value.line_number())
+}
@@ -5,7 +5,10 @@ pub(crate) mod figma_tokens;
 pub(crate) mod figma_typography_tokens;
 pub(crate) mod get_data;
 pub(crate) mod http;
+pub(crate) mod i18n;
+pub(crate) mod language_switcher;
 pub(crate) mod package_tree;
+pub(crate) mod pagination;
 pub(crate) mod query;
 pub(crate) mod request_data;
 pub(crate) mod sitemap;
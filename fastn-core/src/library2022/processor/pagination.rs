@@ -0,0 +1,17 @@
+/// The details of the pages of the last list with `page-size` in the document, see
+/// `fastn_core::Pagination`
+pub fn process(
+    value: ftd::ast::VariableValue,
+    kind: ftd::interpreter::Kind,
+    doc: &ftd::interpreter::TDoc,
+    config: &fastn_core::Config,
+) -> ftd::interpreter::Result<ftd::interpreter::Value> {
+    match config.pagination.as_ref() {
+        Some(pagination) => doc.from_json(pagination, &kind, value.line_number()),
+        None => ftd::interpreter::utils::e2(
+            "`pagination` needs a list with `page-size` defined before it",
+            doc.name,
+            value.line_number(),
+        ),
+    }
+}
@@ -6,7 +6,9 @@ pub fn process(
 ) -> ftd::interpreter::Result<ftd::interpreter::Value> {
     // TODO: URL params not yet handled
     let req = match config.request.as_ref() {
-        Some(v) => v,
+        Some(v) => Some(v),
+        // `fastn build` pre-rendering a dynamic url, only the path parameters are known
+        None if !config.named_parameters.is_empty() => None,
         None => {
             return ftd::interpreter::utils::e2(
                 "config does not contain http-request object",
@@ -15,7 +17,7 @@ pub fn process(
             )
         }
     };
-    let mut data = req.query().clone();
+    let mut data = req.map(|req| req.query().clone()).unwrap_or_default();
 
     let mut named_parameters = std::collections::HashMap::new();
     for (name, param_value) in config.named_parameters.iter() {
@@ -32,7 +34,7 @@ pub fn process(
 
     data.extend(named_parameters);
 
-    match req.body_as_json() {
+    match req.map(|req| req.body_as_json()).unwrap_or(Ok(None)) {
         Ok(Some(b)) => {
             data.extend(b);
         }
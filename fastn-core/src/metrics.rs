@@ -0,0 +1,252 @@
+//! Metrics of `fastn serve`, exposed at `/-/metrics/` in the prometheus text format.
+
+/// Upper bounds, in seconds, of the buckets of the duration histograms
+const BUCKETS: [f64; 11] = [
+    0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0,
+];
+
+/// Routes handled by `fastn_core::commands::serve::route`, anything else is `serve`
+const ROUTES: [&str; 14] = [
+    "/-/sync/",
+    "/-/sync2/",
+    "/-/clone/",
+    "/-/view-src/",
+    "/-/edit-src/",
+    "/-/edit/",
+    "/-/revert/",
+    "/-/editor-sync/",
+    "/-/create-cr/",
+    "/-/create-cr-page/",
+    "/-/clear-cache/",
+    "/-/poll/",
+    "/-/metrics/",
+    "/auth/",
+];
+
+#[derive(Default)]
+struct Histogram {
+    buckets: [u64; BUCKETS.len()],
+    sum: f64,
+    count: u64,
+}
+
+impl Histogram {
+    fn observe(&mut self, duration: std::time::Duration) {
+        let seconds = duration.as_secs_f64();
+        for (bucket, le) in self.buckets.iter_mut().zip(BUCKETS) {
+            if seconds <= le {
+                *bucket += 1;
+            }
+        }
+        self.sum += seconds;
+        self.count += 1;
+    }
+
+    fn render(&self, out: &mut String, name: &str, labels: &str) {
+        use std::fmt::Write;
+
+        for (bucket, le) in self.buckets.iter().zip(BUCKETS) {
+            writeln!(
+                out,
+                "{}_bucket{{{},le=\"{}\"}} {}",
+                name, labels, le, bucket
+            )
+            .ok();
+        }
+        writeln!(
+            out,
+            "{}_bucket{{{},le=\"+Inf\"}} {}",
+            name, labels, self.count
+        )
+        .ok();
+        writeln!(out, "{}_sum{{{}}} {}", name, labels, self.sum).ok();
+        writeln!(out, "{}_count{{{}}} {}", name, labels, self.count).ok();
+    }
+}
+
+#[derive(Default)]
+struct Metrics {
+    /// (route, method, status)
+    requests: std::collections::BTreeMap<(String, String, u16), u64>,
+    request_durations: std::collections::BTreeMap<String, Histogram>,
+    processor_durations: std::collections::BTreeMap<String, Histogram>,
+    /// kind of download, `package` or `file`
+    dependency_downloads: std::collections::BTreeMap<String, u64>,
+    /// (cache, hit)
+    cache_lookups: std::collections::BTreeMap<(String, bool), u64>,
+}
+
+static METRICS: once_cell::sync::Lazy<antidote::Mutex<Metrics>> =
+    once_cell::sync::Lazy::new(|| antidote::Mutex::new(Default::default()));
+
+/// The route `path` is counted under, there is one per api and everything else is `serve`, to
+/// keep the number of series bounded
+pub(crate) fn route(path: &str) -> &'static str {
+    ROUTES
+        .iter()
+        .find(|route| path.starts_with(*route))
+        .copied()
+        .unwrap_or("serve")
+}
+
+pub(crate) fn record_request(path: &str, method: &str, status: u16, duration: std::time::Duration) {
+    let route = route(path);
+    let mut metrics = METRICS.lock();
+    *metrics
+        .requests
+        .entry((route.to_string(), method.to_string(), status))
+        .or_default() += 1;
+    metrics
+        .request_durations
+        .entry(route.to_string())
+        .or_default()
+        .observe(duration);
+}
+
+pub(crate) fn record_processor(processor: &str, duration: std::time::Duration) {
+    METRICS
+        .lock()
+        .processor_durations
+        .entry(processor.to_string())
+        .or_default()
+        .observe(duration);
+}
+
+pub(crate) fn record_dependency_download(kind: &str) {
+    *METRICS
+        .lock()
+        .dependency_downloads
+        .entry(kind.to_string())
+        .or_default() += 1;
+}
+
+pub(crate) fn record_cache_lookup(cache: &str, hit: bool) {
+    *METRICS
+        .lock()
+        .cache_lookups
+        .entry((cache.to_string(), hit))
+        .or_default() += 1;
+}
+
+fn escape(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+fn render() -> String {
+    use std::fmt::Write;
+
+    let metrics = METRICS.lock();
+    let mut out = String::new();
+
+    out.push_str(
+        "# HELP fastn_http_requests_total Requests served, by route, method and status.\n",
+    );
+    out.push_str("# TYPE fastn_http_requests_total counter\n");
+    for ((route, method, status), count) in metrics.requests.iter() {
+        writeln!(
+            out,
+            "fastn_http_requests_total{{route=\"{}\",method=\"{}\",status=\"{}\"}} {}",
+            escape(route),
+            escape(method),
+            status,
+            count
+        )
+        .ok();
+    }
+
+    out.push_str(
+        "# HELP fastn_http_request_duration_seconds Time taken to serve requests, by route.\n",
+    );
+    out.push_str("# TYPE fastn_http_request_duration_seconds histogram\n");
+    for (route, histogram) in metrics.request_durations.iter() {
+        histogram.render(
+            &mut out,
+            "fastn_http_request_duration_seconds",
+            format!("route=\"{}\"", escape(route)).as_str(),
+        );
+    }
+
+    out.push_str(
+        "# HELP fastn_processor_duration_seconds Time taken by processors, by processor.\n",
+    );
+    out.push_str("# TYPE fastn_processor_duration_seconds histogram\n");
+    for (processor, histogram) in metrics.processor_durations.iter() {
+        histogram.render(
+            &mut out,
+            "fastn_processor_duration_seconds",
+            format!("processor=\"{}\"", escape(processor)).as_str(),
+        );
+    }
+
+    out.push_str("# HELP fastn_dependency_downloads_total Dependencies downloaded, by kind.\n");
+    out.push_str("# TYPE fastn_dependency_downloads_total counter\n");
+    for (kind, count) in metrics.dependency_downloads.iter() {
+        writeln!(
+            out,
+            "fastn_dependency_downloads_total{{kind=\"{}\"}} {}",
+            escape(kind),
+            count
+        )
+        .ok();
+    }
+
+    out.push_str("# HELP fastn_cache_lookups_total Cache lookups, by cache and result.\n");
+    out.push_str("# TYPE fastn_cache_lookups_total counter\n");
+    for ((cache, hit), count) in metrics.cache_lookups.iter() {
+        writeln!(
+            out,
+            "fastn_cache_lookups_total{{cache=\"{}\",result=\"{}\"}} {}",
+            escape(cache),
+            if *hit { "hit" } else { "miss" },
+            count
+        )
+        .ok();
+    }
+
+    out
+}
+
+/// route: /-/metrics/
+pub(crate) fn metrics() -> fastn_core::http::Response {
+    actix_web::HttpResponse::Ok()
+        .content_type("text/plain; version=0.0.4; charset=utf-8")
+        .body(render())
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn route() {
+        assert_eq!(super::route("/-/metrics/"), "/-/metrics/");
+        assert_eq!(super::route("/-/view-src/index/"), "/-/view-src/");
+        assert_eq!(super::route("/auth/login/"), "/auth/");
+        assert_eq!(super::route("/blog/"), "serve");
+        assert_eq!(super::route("/"), "serve");
+    }
+
+    #[test]
+    fn histogram() {
+        let mut histogram = super::Histogram::default();
+        histogram.observe(std::time::Duration::from_millis(20));
+        histogram.observe(std::time::Duration::from_secs(20));
+
+        let mut out = String::new();
+        histogram.render(&mut out, "d", "route=\"serve\"");
+        let lines = out.lines().collect::<Vec<_>>();
+        assert_eq!(lines.len(), super::BUCKETS.len() + 3);
+        assert_eq!(lines[0], "d_bucket{route=\"serve\",le=\"0.005\"} 0");
+        assert_eq!(lines[2], "d_bucket{route=\"serve\",le=\"0.025\"} 1");
+        assert_eq!(lines[10], "d_bucket{route=\"serve\",le=\"10\"} 1");
+        assert_eq!(lines[11], "d_bucket{route=\"serve\",le=\"+Inf\"} 2");
+        assert_eq!(lines[12], "d_sum{route=\"serve\"} 20.02");
+        assert_eq!(lines[13], "d_count{route=\"serve\"} 2");
+    }
+
+    #[test]
+    fn escape() {
+        assert_eq!(super::escape("a\"b\\c\nd"), "a\\\"b\\\\c\\nd");
+    }
+}
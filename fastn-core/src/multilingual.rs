@@ -0,0 +1,292 @@
+//! Links between a package and its translations.
+//!
+//! `translations` in FASTN.ftd, and `translation-of` in each translation, group packages holding
+//! the same documents in different languages. Every document links to its equivalents with
+//! `<link rel="alternate" hreflang>`, the `language-switcher` processor lists them, and
+//! `fastn serve` sends readers to the language they prefer: the one they picked with `?lang=`,
+//! remembered in the `fastn-language` cookie, else the most preferred language of their
+//! `Accept-Language` header.
+//!
+//! Each package is served from its own domain, `https://<package-name>/`, except in the output
+//! of `fastn build --multilingual` where the original package is at the base url and every
+//! translation at `<base-url><language>/`.
+
+pub(crate) const LANGUAGE_COOKIE: &str = "fastn-language";
+pub(crate) const LANGUAGE_QUERY: &str = "lang";
+
+/// A document in one of the languages of the package
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub(crate) struct Alternate {
+    pub language: String,
+    pub language_name: String,
+    pub package: String,
+    pub url: String,
+    pub is_current: bool,
+    pub is_original: bool,
+}
+
+/// The path of the document in the url, without the leading `/`: the path of the request when
+/// serving, else `` for `index.ftd` and `blog/` for `blog.ftd`
+pub(crate) fn document_path(config: &fastn_core::Config, document_id: &str) -> String {
+    match config.request.as_ref() {
+        Some(req) => req.path().trim_start_matches('/').to_string(),
+        None => fastn_core::utils::id_to_path(document_id)
+            .replace(std::path::MAIN_SEPARATOR, "/")
+            .trim_start_matches('/')
+            .to_string(),
+    }
+}
+
+/// The original package and its translations, in that order. The translations listed in
+/// FASTN.ftd only have a name, they are resolved to know their language.
+async fn packages(config: &fastn_core::Config) -> Vec<fastn_core::Package> {
+    let original = match config.package.translation_of.as_ref() {
+        Some(original) => match config.resolve_package(original).await {
+            Ok(original) => original,
+            Err(e) => {
+                tracing::warn!(msg = "failed to resolve the original package", package = original.name, error = %e);
+                return vec![];
+            }
+        },
+        None if config.package.translations.is_empty() => return vec![],
+        None => config.package.clone(),
+    };
+
+    let mut packages = vec![];
+    for package in std::iter::once(&original).chain(original.translations.iter()) {
+        match config.resolve_package(package).await {
+            Ok(package) => packages.push(package),
+            Err(e) => {
+                tracing::warn!(msg = "failed to resolve the translation", package = package.name, error = %e);
+            }
+        }
+    }
+    packages
+}
+
+/// The document at `path` in every language of the package, packages without a `language` are
+/// left out as they can not be told apart
+pub(crate) async fn alternates(config: &fastn_core::Config, path: &str) -> Vec<Alternate> {
+    // documents of dependencies are not translated
+    if path.starts_with("-/") {
+        return vec![];
+    }
+    let base_url = config.multilingual_base_url.as_deref();
+    packages(config)
+        .await
+        .into_iter()
+        .enumerate()
+        .filter_map(|(index, package)| {
+            let language = package.language.clone()?;
+            let is_original = index == 0;
+            Some(Alternate {
+                url: url(&package, language.as_str(), is_original, base_url, path),
+                language_name: fastn_core::utils::language_to_human(language.as_str()),
+                is_current: package.name.eq(&config.package.name),
+                is_original,
+                package: package.name,
+                language,
+            })
+        })
+        .collect()
+}
+
+fn url(
+    package: &fastn_core::Package,
+    language: &str,
+    is_original: bool,
+    base_url: Option<&str>,
+    path: &str,
+) -> String {
+    match base_url {
+        Some(base_url) if is_original => {
+            format!("{}/{}", base_url.trim_end_matches('/'), path)
+        }
+        Some(base_url) => format!("{}/{}/{}", base_url.trim_end_matches('/'), language, path),
+        None => format!("https://{}/{}", package.name.trim_end_matches('/'), path),
+    }
+}
+
+/// The `<link rel="alternate" hreflang>` tags of a document, `x-default` is the original
+pub(crate) fn hreflang_links(alternates: &[Alternate]) -> String {
+    let mut links = alternates
+        .iter()
+        .map(|a| {
+            format!(
+                "\n<link rel=\"alternate\" hreflang=\"{}\" href=\"{}\" />",
+                a.language, a.url
+            )
+        })
+        .collect::<String>();
+    if let Some(original) = alternates.iter().find(|a| a.is_original) {
+        links.push_str(
+            format!(
+                "\n<link rel=\"alternate\" hreflang=\"x-default\" href=\"{}\" />",
+                original.url
+            )
+            .as_str(),
+        );
+    }
+    links
+}
+
+/// The response sending a `GET` request for the page at `path` to the language the reader
+/// prefers, if it is not the language of the package serving it
+pub(crate) async fn negotiate(
+    config: &fastn_core::Config,
+    req: &fastn_core::http::Request,
+    path: &str,
+) -> Option<fastn_core::http::Response> {
+    // only pages opened in the browser are redirected, not the requests of scripts and apis
+    let accepts_html = req
+        .headers()
+        .get("accept")
+        .and_then(|v| v.to_str().ok())
+        .map(|v| v.contains("text/html"))
+        .unwrap_or(false);
+    if !accepts_html {
+        return None;
+    }
+
+    let alternates = alternates(config, path).await;
+    let current = alternates.iter().find(|a| a.is_current)?;
+
+    if let Some(chosen) = req.query().get(LANGUAGE_QUERY).and_then(|v| v.as_str()) {
+        let chosen = alternates
+            .iter()
+            .find(|a| a.language.eq_ignore_ascii_case(chosen))?;
+        // the choice is remembered in the cookie, the url is cleaned up
+        let location = if chosen.is_current {
+            with_query(
+                format!("/{}", path).as_str(),
+                without_language(req.query_string()).as_str(),
+            )
+        } else {
+            location(chosen)
+        };
+        return Some(redirect(location.as_str(), Some(chosen.language.as_str())));
+    }
+
+    let languages = alternates
+        .iter()
+        .map(|a| a.language.clone())
+        .collect::<Vec<_>>();
+    let preferred = match req.cookie(LANGUAGE_COOKIE).filter(|c| !c.is_empty()) {
+        Some(language) => language,
+        None => req
+            .headers()
+            .get("accept-language")
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| fastn_core::i18n::negotiate(v, &languages))?,
+    };
+    let preferred = alternates
+        .iter()
+        .find(|a| a.language.eq_ignore_ascii_case(preferred.as_str()))?;
+    if preferred.language.eq(&current.language) {
+        return None;
+    }
+    Some(redirect(location(preferred).as_str(), None))
+}
+
+/// Other packages are served from their own domain, which does not get our cookie, so the
+/// language is passed along for that domain to remember it too
+fn location(alternate: &Alternate) -> String {
+    with_query(
+        alternate.url.as_str(),
+        format!("{}={}", LANGUAGE_QUERY, alternate.language).as_str(),
+    )
+}
+
+fn with_query(url: &str, query: &str) -> String {
+    if query.is_empty() {
+        url.to_string()
+    } else {
+        format!("{}?{}", url, query)
+    }
+}
+
+/// The query string without the `lang` parameter
+fn without_language(query_string: &str) -> String {
+    query_string
+        .split('&')
+        .filter(|p| {
+            !p.is_empty()
+                && p.split('=')
+                    .next()
+                    .map(|k| k.ne(LANGUAGE_QUERY))
+                    .unwrap_or(true)
+        })
+        .collect::<Vec<_>>()
+        .join("&")
+}
+
+fn redirect(location: &str, language: Option<&str>) -> fastn_core::http::Response {
+    let mut response = actix_web::HttpResponse::Found();
+    response
+        .insert_header((actix_web::http::header::LOCATION, location))
+        // the response depends on the language of the reader, caches must not share it
+        .insert_header((actix_web::http::header::VARY, "Accept-Language, Cookie"));
+    if let Some(language) = language {
+        response.cookie(
+            actix_web::cookie::Cookie::build(LANGUAGE_COOKIE, language.to_string())
+                .path("/")
+                .same_site(actix_web::cookie::SameSite::Lax)
+                .permanent()
+                .finish(),
+        );
+    }
+    response.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    fn alternate(language: &str, url: &str, is_original: bool) -> super::Alternate {
+        super::Alternate {
+            language: language.to_string(),
+            language_name: language.to_string(),
+            package: format!("{}.example.com", language),
+            url: url.to_string(),
+            is_current: is_original,
+            is_original,
+        }
+    }
+
+    #[test]
+    fn urls() {
+        let package = fastn_core::Package::new("hi.example.com");
+        assert_eq!(
+            super::url(&package, "hi", false, None, "blog/"),
+            "https://hi.example.com/blog/"
+        );
+        assert_eq!(
+            super::url(&package, "hi", false, Some("/"), "blog/"),
+            "/hi/blog/"
+        );
+        assert_eq!(
+            super::url(&package, "hi", true, Some("https://example.com/"), ""),
+            "https://example.com/"
+        );
+    }
+
+    #[test]
+    fn hreflang_links() {
+        let alternates = vec![
+            alternate("en", "/blog/", true),
+            alternate("hi", "/hi/blog/", false),
+        ];
+        assert_eq!(
+            super::hreflang_links(&alternates),
+            "\n<link rel=\"alternate\" hreflang=\"en\" href=\"/blog/\" />\
+             \n<link rel=\"alternate\" hreflang=\"hi\" href=\"/hi/blog/\" />\
+             \n<link rel=\"alternate\" hreflang=\"x-default\" href=\"/blog/\" />"
+        );
+    }
+
+    #[test]
+    fn without_language() {
+        assert_eq!(super::without_language("lang=hi"), "");
+        assert_eq!(super::without_language("page=2&lang=hi&q=a"), "page=2&q=a");
+        assert_eq!(super::without_language("language=hi"), "language=hi");
+    }
+}
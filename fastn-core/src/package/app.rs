@@ -80,6 +80,12 @@ impl AppTemp {
             ))
             .await?;
 
+        // Dependencies of the app come from its own FASTN.ftd, the host package does not need to
+        // declare them
+        for dependency in package.dependencies.iter() {
+            config.resolve_package(&dependency.package).await?;
+        }
+
         Ok(App {
             name: self.name,
             package,
@@ -93,55 +99,122 @@ impl AppTemp {
     }
 }
 
-// Takes the path /-/<package-name>/<remaining>/ or /mount-point/<remaining>/
-pub async fn can_read(config: &fastn_core::Config, path: &str) -> fastn_core::Result<bool> {
+impl App {
+    /// The document path inside the app for `sanitized_path`, in the form used by the sitemap of
+    /// the app package
+    fn document_name(sanitized_path: &str) -> String {
+        let name = sanitized_path.trim_matches('/');
+        if name.is_empty() {
+            "/".to_string()
+        } else {
+            format!("/{}/", name)
+        }
+    }
+}
+
+/// Returns `true` if the user making the current request belongs to any of `groups`
+async fn belongs_to(
+    config: &fastn_core::Config,
+    groups: &[&fastn_core::user_group::UserGroup],
+) -> fastn_core::Result<bool> {
     use itertools::Itertools;
-    // first get the app
-    let readers_groups = if let Some((_, _, _, Some(app))) =
-        config.get_mountpoint_sanitized_path(&config.package, path)
-    {
-        app.readers.clone()
-    } else {
-        vec![]
-    };
 
-    if readers_groups.is_empty() {
-        return Ok(true);
+    let mut app_identities = vec![];
+    for ug in groups.iter() {
+        app_identities.extend(ug.get_identities(config)?)
     }
 
-    let user_groups = config
+    let req = match config.request.as_ref() {
+        Some(req) => req,
+        None => return Ok(false),
+    };
+    let auth_identities =
+        match fastn_core::auth::get_auth_identities(req.cookies(), app_identities.as_slice()).await
+        {
+            Ok(ids) => ids,
+            Err(fastn_core::Error::GenericError(_err)) => vec![],
+            Err(e) => return Err(e),
+        };
+
+    fastn_core::user_group::belongs_to(
+        config,
+        groups,
+        auth_identities.iter().collect_vec().as_slice(),
+    )
+}
+
+/// Groups of the host package with the given ids
+fn host_groups<'a>(
+    config: &'a fastn_core::Config,
+    ids: &[String],
+) -> Vec<&'a fastn_core::user_group::UserGroup> {
+    config
         .package
         .groups
         .iter()
-        .filter_map(|(id, g)| {
-            if readers_groups.contains(id) {
-                Some(g)
-            } else {
-                None
-            }
-        })
-        .collect_vec();
+        .filter_map(|(id, g)| if ids.contains(id) { Some(g) } else { None })
+        .collect()
+}
 
-    let mut app_identities = vec![];
-    for ug in user_groups.iter() {
-        app_identities.extend(ug.get_identities(config)?)
+// Takes the path /-/<package-name>/<remaining>/ or /mount-point/<remaining>/
+// The user must be one of the `readers` of the app in the host package, and one of the readers
+// of the document in the sitemap of the app package
+pub async fn can_read(config: &fastn_core::Config, path: &str) -> fastn_core::Result<bool> {
+    let (app, sanitized_path) = match config.get_mountpoint_sanitized_path(&config.package, path) {
+        Some((_, _, sanitized_path, Some(app))) => (app.clone(), sanitized_path),
+        _ => return Ok(true),
+    };
+
+    let readers = host_groups(config, app.readers.as_slice());
+    if !readers.is_empty() && !belongs_to(config, readers.as_slice()).await? {
+        return Ok(false);
     }
 
-    let auth_identities = fastn_core::auth::get_auth_identities(
-        config.request.as_ref().unwrap().cookies(),
-        app_identities.as_slice(),
-    )
-    .await?;
+    let package = config.update_sitemap(&app.package).await?;
+    if let Some(sitemap) = package.sitemap.as_ref() {
+        let (readers, confidential) = sitemap.readers(
+            App::document_name(sanitized_path.as_str()).as_str(),
+            &package.groups,
+        );
+        if !readers.is_empty() && !belongs_to(config, readers.as_slice()).await? {
+            return Ok(!confidential);
+        }
+    }
+    Ok(true)
+}
 
-    return fastn_core::user_group::belongs_to(
-        config,
-        user_groups.as_slice(),
-        auth_identities.iter().collect_vec().as_slice(),
-    );
+// Same as `can_read`, for the `writers` of the app and of the document
+pub async fn can_write(config: &fastn_core::Config, path: &str) -> fastn_core::Result<bool> {
+    let (app, sanitized_path) = match config.get_mountpoint_sanitized_path(&config.package, path) {
+        Some((_, _, sanitized_path, Some(app))) => (app.clone(), sanitized_path),
+        _ => return Ok(true),
+    };
+
+    let writers = host_groups(config, app.writers.as_slice());
+    if !writers.is_empty() && !belongs_to(config, writers.as_slice()).await? {
+        return Ok(false);
+    }
 
-    // get the app readers
-    // get the groups
-    // the the access identities
-    // check belongs _to
-    //return Ok(true);
+    let package = config.update_sitemap(&app.package).await?;
+    if let Some(sitemap) = package.sitemap.as_ref() {
+        let writers = sitemap.writers(
+            App::document_name(sanitized_path.as_str()).as_str(),
+            &package.groups,
+        );
+        if !writers.is_empty() {
+            return belongs_to(config, writers.as_slice()).await;
+        }
+    }
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn document_name() {
+        assert_eq!(super::App::document_name(""), "/");
+        assert_eq!(super::App::document_name("/"), "/");
+        assert_eq!(super::App::document_name("index"), "/index/");
+        assert_eq!(super::App::document_name("/blog/post/"), "/blog/post/");
+    }
 }
@@ -207,6 +207,7 @@ impl fastn_core::Package {
                 // TODO: instead of reading the whole thing in memory use tokio::io::copy() somehow?
                 file.write_all(&response)?;
                 // file.write_all(response.text().await?.as_bytes())?;
+                fastn_core::metrics::record_dependency_download("package");
             }
 
             let file = std::fs::File::open(&path)?;
@@ -452,6 +453,7 @@ impl fastn_core::Package {
             // TODO: instead of reading the whole thing in memory use tokio::io::copy() somehow?
             file.write_all(&response)?;
             // file.write_all(response.text().await?.as_bytes())?;
+            fastn_core::metrics::record_dependency_download("package");
         }
 
         let file = std::fs::File::open(&path)?;
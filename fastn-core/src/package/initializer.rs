@@ -0,0 +1,157 @@
+/// Reads the package from the disk, for `fastn_package::initialize()` and
+/// `fastn_package::file_changed()`
+pub(crate) struct FileSystemInitializer {
+    root: camino::Utf8PathBuf,
+}
+
+impl FileSystemInitializer {
+    pub(crate) fn new(root: &camino::Utf8Path) -> FileSystemInitializer {
+        FileSystemInitializer {
+            root: root.to_path_buf(),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl fastn_package::initializer::Initializer for FileSystemInitializer {
+    async fn file_as_string(
+        &self,
+        path: &str,
+    ) -> Result<String, fastn_issues::initialization::FileAsStringError> {
+        let content = tokio::fs::read(self.root.join(path))
+            .await
+            .map_err(|source| match source.kind() {
+                std::io::ErrorKind::NotFound => {
+                    fastn_issues::initialization::FileAsStringError::FileDoesNotExist {
+                        name: path.to_string(),
+                        source,
+                    }
+                }
+                _ => fastn_issues::initialization::FileAsStringError::CantReadFile {
+                    name: path.to_string(),
+                    source,
+                },
+            })?;
+        String::from_utf8(content).map_err(|e| {
+            fastn_issues::initialization::FileAsStringError::ContentIsNotUTF8 {
+                name: path.to_string(),
+                source: std::io::Error::new(std::io::ErrorKind::InvalidData, e),
+            }
+        })
+    }
+
+    async fn file_as_bytes(
+        &self,
+        path: &str,
+    ) -> Result<Vec<u8>, fastn_issues::initialization::FileAsBytesError> {
+        tokio::fs::read(self.root.join(path))
+            .await
+            .map_err(|source| match source.kind() {
+                std::io::ErrorKind::NotFound => {
+                    fastn_issues::initialization::FileAsBytesError::FileDoesNotExist {
+                        name: path.to_string(),
+                        source,
+                    }
+                }
+                _ => fastn_issues::initialization::FileAsBytesError::CantReadFile {
+                    name: path.to_string(),
+                    source,
+                },
+            })
+    }
+
+    async fn files(&self) -> Result<Vec<String>, fastn_issues::initialization::ListFilesError> {
+        // hidden files, and so `.build`, `.packages` etc, are skipped by the walker
+        let mut files = vec![];
+        for entry in ignore::WalkBuilder::new(&self.root).build() {
+            let entry = entry.map_err(|e| {
+                fastn_issues::initialization::ListFilesError::CantReadDirectory {
+                    name: self.root.to_string(),
+                    source: e
+                        .into_io_error()
+                        .unwrap_or_else(|| std::io::ErrorKind::Other.into()),
+                }
+            })?;
+            if !entry.file_type().map(|t| t.is_file()).unwrap_or(false) {
+                continue;
+            }
+            if let Some(name) = relative_path(&self.root, entry.path()) {
+                files.push(name);
+            }
+        }
+        files.sort();
+        Ok(files)
+    }
+}
+
+/// `path` relative to `root`, `None` if it is not inside `root` or is inside a hidden directory
+pub(crate) fn relative_path(root: &camino::Utf8Path, path: &std::path::Path) -> Option<String> {
+    // notify reports canonical paths, the root may not be one
+    let relative = match path.strip_prefix(root) {
+        Ok(relative) => relative,
+        Err(_) => path.strip_prefix(root.canonicalize().ok()?).ok()?,
+    };
+    let mut name = vec![];
+    for component in relative.components() {
+        let component = component.as_os_str().to_str()?;
+        if component.starts_with('.') {
+            return None;
+        }
+        name.push(component);
+    }
+    Some(name.join("/"))
+}
+
+/// The package served by `fastn serve`, with its db
+struct Served {
+    root: camino::Utf8PathBuf,
+    package: fastn_package::Package,
+}
+
+static SERVED: once_cell::sync::OnceCell<Served> = once_cell::sync::OnceCell::new();
+
+/// Loads the package at `root` into a `fastn_package` db, a failure is logged and static file
+/// hashes are computed from the files instead
+pub(crate) async fn initialize(root: &camino::Utf8Path) {
+    match fastn_package::initialize(FileSystemInitializer::new(root)).await {
+        Ok(package) => {
+            let _ = SERVED.set(Served {
+                root: root.to_path_buf(),
+                package,
+            });
+        }
+        Err(e) => {
+            tracing::warn!(msg = "failed to load the package db", root = %root, error = %e);
+        }
+    }
+}
+
+/// The db of the package at `root`, `None` if it is not the package being served or its db
+/// failed to load
+pub(crate) fn package(root: &camino::Utf8Path) -> Option<&'static fastn_package::Package> {
+    SERVED
+        .get()
+        .filter(|served| served.root == root)
+        .map(|served| &served.package)
+}
+
+/// Updates the `fastn_package` db for a file created, modified or deleted on the disk
+pub(crate) async fn file_changed(path: &std::path::Path) {
+    let served = match SERVED.get() {
+        Some(served) => served,
+        None => return,
+    };
+    let name = match relative_path(&served.root, path) {
+        Some(name) => name,
+        None => return,
+    };
+    if let Err(e) = fastn_package::file_changed(
+        &served.package,
+        FileSystemInitializer::new(&served.root),
+        name.as_str(),
+    )
+    .await
+    {
+        tracing::warn!(msg = "failed to update the package db", file = name, error = %e);
+    }
+}
@@ -1,5 +1,6 @@
 pub mod app;
 pub mod dependency;
+pub(crate) mod initializer;
 pub mod package_doc;
 pub mod redirects;
 pub mod user_group;
@@ -62,6 +63,18 @@ pub struct Package {
     /// Headers for the WASM backend
     pub backend_headers: Option<Vec<fastn_package::old_fastn::BackendHeader>>,
 
+    /// Host capabilities and limits for the WASM backend
+    pub backend_capabilities: Option<fastn_package::old_fastn::BackendCapabilities>,
+
+    /// Rules for requests forwarded to the `endpoint`, read from `fastn.proxy`
+    pub proxy_rules: Vec<fastn_package::old_fastn::ProxyRule>,
+
+    /// Form handlers, read from `fastn.form`
+    pub forms: Vec<fastn_package::old_fastn::Form>,
+
+    /// Rate and body size limits, read from `fastn.limit`
+    pub limits: Vec<fastn_package::old_fastn::Limit>,
+
     /// Installed Apps
     pub apps: Vec<app::App>,
 
@@ -100,6 +113,10 @@ impl Package {
             endpoint: None,
             backend: false,
             backend_headers: None,
+            backend_capabilities: None,
+            proxy_rules: vec![],
+            forms: vec![],
+            limits: vec![],
             apps: vec![],
             icon: None,
             redirects: None,
@@ -528,6 +545,10 @@ impl Package {
             .collect();
         package.fonts = fastn_document.get("fastn#font")?;
         package.sitemap_temp = fastn_document.get("fastn#sitemap")?;
+        package.backend_capabilities = fastn_document.get("fastn#backend-capabilities")?;
+        package.proxy_rules = fastn_document.get("fastn#proxy")?;
+        package.forms = fastn_document.get("fastn#form")?;
+        package.limits = fastn_document.get("fastn#limit")?;
         *self = package;
         Ok(())
     }
@@ -617,6 +638,10 @@ impl Package {
         package.fonts = fastn_doc.get("fastn#font")?;
         package.sitemap_temp = fastn_doc.get("fastn#sitemap")?;
         package.dynamic_urls_temp = fastn_doc.get("fastn#dynamic-urls")?;
+        package.backend_capabilities = fastn_doc.get("fastn#backend-capabilities")?;
+        package.proxy_rules = fastn_doc.get("fastn#proxy")?;
+        package.forms = fastn_doc.get("fastn#form")?;
+        package.limits = fastn_doc.get("fastn#limit")?;
 
         // TODO: resolve group dependent packages, there may be imported group from foreign package
         //   We need to make sure to resolve that package as well before moving ahead
@@ -662,6 +687,19 @@ impl Package {
             .to_owned()
     }
 
+    /// Returns the form handler declared for `path`
+    pub fn form(&self, path: &str) -> Option<&fastn_package::old_fastn::Form> {
+        self.forms.iter().find(|form| form.matches(path))
+    }
+
+    /// Returns the proxy rule with the longest `prefix` matching `path`
+    pub fn proxy_rule(&self, path: &str) -> Option<&fastn_package::old_fastn::ProxyRule> {
+        self.proxy_rules
+            .iter()
+            .filter(|rule| rule.matches(path))
+            .max_by_key(|rule| rule.prefix.trim_start_matches('/').len())
+    }
+
     // Output: Package's dependency which contains mount-point and endpoint
     // where request path starts-with dependency mount-point.
     // (endpoint, sanitized request path from mount-point)
@@ -731,6 +769,10 @@ impl PackageTempIntoPackage for fastn_package::old_fastn::PackageTemp {
             endpoint: self.endpoint,
             backend: self.backend,
             backend_headers: self.backend_headers,
+            backend_capabilities: None,
+            proxy_rules: vec![],
+            forms: vec![],
+            limits: vec![],
             apps: vec![],
             icon: self.icon,
             redirects: None,
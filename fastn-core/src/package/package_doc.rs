@@ -408,6 +408,15 @@ pub(crate) async fn read_ftd_2022(
     config
         .downloaded_assets
         .extend(lib.config.downloaded_assets);
+    config.request_dependent |= lib.config.request_dependent;
+    config.pagination = lib.config.pagination;
+
+    // the same document in the other languages of the package
+    let alternates = fastn_core::multilingual::alternates(
+        config,
+        fastn_core::multilingual::document_path(config, main.id.as_str()).as_str(),
+    )
+    .await;
 
     let font_style = config.get_font_style();
     let file_content = fastn_core::utils::replace_markers_2022(
@@ -417,6 +426,7 @@ pub(crate) async fn read_ftd_2022(
         main.id_to_path().as_str(),
         font_style.as_str(),
         base_url,
+        fastn_core::multilingual::hreflang_links(&alternates).as_str(),
     );
 
     Ok(file_content.into())
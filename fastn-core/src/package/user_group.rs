@@ -24,6 +24,41 @@ impl UserIdentity {
             value: value.to_string(),
         }
     }
+
+    /// `true` if `identity` matches this identity of a group, whose value can have `*`
+    /// wildcards, `email: *@ourcompany.com` matches every email of the domain. Keys of excluded
+    /// identities start with `-`, which is ignored.
+    pub fn matches(&self, identity: &UserIdentity) -> bool {
+        self.key
+            .trim_start_matches('-')
+            .eq_ignore_ascii_case(identity.key.trim_start_matches('-'))
+            && wildcard_match(
+                self.value.to_lowercase().as_str(),
+                identity.value.to_lowercase().as_str(),
+            )
+    }
+}
+
+fn wildcard_match(pattern: &str, value: &str) -> bool {
+    let mut parts = pattern.split('*');
+    let first = parts.next().unwrap_or_default();
+    let mut rest = match value.strip_prefix(first) {
+        Some(rest) => rest,
+        None => return false,
+    };
+    let parts = parts.collect::<Vec<_>>();
+    let (last, middle) = match parts.split_last() {
+        Some(v) => v,
+        // no `*` in the pattern
+        None => return rest.is_empty(),
+    };
+    for part in middle {
+        match rest.find(part) {
+            Some(i) => rest = &rest[i + part.len()..],
+            None => return false,
+        }
+    }
+    rest.ends_with(last)
 }
 
 impl ToString for UserIdentity {
@@ -44,10 +79,14 @@ pub struct UserGroup {
     pub groups: Vec<String>,
     pub excluded_groups: Vec<String>,
     pub description: Option<String>,
+
+    /// Files, relative to the package root, listing more identities of the group, see
+    /// `fastn_core::user_group::source_identities`
+    pub sources: Vec<String>,
 }
 
-// TODO: Keys should be dynamic
-/// This type is needed to deserialize ftd to rust
+/// This type is needed to deserialize ftd to rust. Identity keys without a field of their own
+/// are given as `identity: <key>: <value>`
 
 #[derive(serde::Deserialize, Debug)]
 pub struct UserGroupTemp {
@@ -154,6 +193,13 @@ pub struct UserGroupTemp {
     pub twitter_retweet: Vec<String>,
     #[serde(rename = "-twitter-retweet")]
     pub excluded_twitter_retweet: Vec<String>,
+    /// `<key>: <value>`
+    #[serde(rename = "identity")]
+    pub identity: Vec<String>,
+    #[serde(rename = "-identity")]
+    pub excluded_identity: Vec<String>,
+    #[serde(rename = "source")]
+    pub sources: Vec<String>,
 }
 
 #[derive(Debug, serde::Serialize)]
@@ -215,20 +261,46 @@ impl UserGroup {
             identities.extend(user_group.get_identities(config)?)
         }
         identities.extend(self.identities.clone());
+        identities.extend(self.source_identities(config)?);
+
+        Ok(identities)
+    }
 
+    /// Identities listed in the `source` files of the group
+    fn source_identities(
+        &self,
+        config: &fastn_core::Config,
+    ) -> fastn_core::Result<Vec<UserIdentity>> {
+        let mut identities = vec![];
+        for source in self.sources.iter() {
+            identities.extend(source_identities(&config.root, source.as_str())?);
+        }
         Ok(identities)
     }
 
     /// This function returns `true` if any of given
-    /// identities is part of group else return's `false`
+    /// identities is part of group else return's `false`. Identities matching an excluded
+    /// identity of the group never belong to it.
     pub fn belongs_to(
         &self,
         config: &fastn_core::Config,
         identities: &[&UserIdentity],
     ) -> fastn_core::Result<bool> {
-        for group_identity in self.identities.iter() {
+        if self
+            .excluded_identities
+            .iter()
+            .any(|excluded| identities.iter().any(|identity| excluded.matches(identity)))
+        {
+            return Ok(false);
+        }
+
+        for group_identity in self
+            .identities
+            .iter()
+            .chain(self.source_identities(config)?.iter())
+        {
             for identity in identities.iter() {
-                if group_identity.eq(identity) {
+                if group_identity.matches(identity) {
                     return Ok(true);
                 }
             }
@@ -422,6 +494,31 @@ impl UserGroupTemp {
             self.excluded_twitter_retweet,
         ));
 
+        for (excluded, identity) in self
+            .identity
+            .iter()
+            .map(|i| (false, i))
+            .chain(self.excluded_identity.iter().map(|i| (true, i)))
+        {
+            let (key, value) =
+                identity
+                    .split_once(':')
+                    .ok_or_else(|| fastn_core::Error::PackageError {
+                        message: format!(
+                            "user-group: `{}`, identity should be `<key>: <value>`, found: `{}`",
+                            self.id, identity
+                        ),
+                    })?;
+            if excluded {
+                excluded_identities.push(UserIdentity::from(
+                    format!("-{}", key.trim()).as_str(),
+                    value.trim(),
+                ));
+            } else {
+                identities.push(UserIdentity::from(key.trim(), value.trim()));
+            }
+        }
+
         Ok(UserGroup {
             id: self.id,
             description: self.description,
@@ -430,10 +527,139 @@ impl UserGroupTemp {
             title: self.title,
             groups: self.groups,
             excluded_groups: self.excluded_group,
+            sources: self.sources,
         })
     }
 }
 
+/// Identities read from `source` files, by path, with the time the file was modified when read
+#[allow(clippy::type_complexity)]
+static SOURCES: once_cell::sync::Lazy<
+    antidote::RwLock<
+        std::collections::HashMap<camino::Utf8PathBuf, (std::time::SystemTime, Vec<UserIdentity>)>,
+    >,
+> = once_cell::sync::Lazy::new(|| antidote::RwLock::new(Default::default()));
+
+/// Identities listed in the `source` of a user group, `source` is relative to `root` and is one
+/// of:
+///
+/// - a `.csv` file with `<key>,<value>` rows
+/// - a `.sqlite` or `.db` file, with a `members` table, or the one named after `#`, having `key`
+///   and `value` columns
+/// - a directory standing in for a directory service, with a file for each key, named
+///   `<key>.txt`, listing a value per line
+///
+/// Files are read again when they change, so membership is updated without restarting
+/// `fastn serve`.
+pub fn source_identities(
+    root: &camino::Utf8Path,
+    source: &str,
+) -> fastn_core::Result<Vec<UserIdentity>> {
+    let (path, table) = match source.rsplit_once('#') {
+        Some((path, table)) => (path, Some(table)),
+        None => (source, None),
+    };
+    let path = root.join(path.trim_start_matches('/'));
+    let modified = source_modified(path.as_path())?;
+    if let Some((read_at, identities)) = SOURCES.read().get(&path) {
+        if *read_at == modified {
+            return Ok(identities.clone());
+        }
+    }
+
+    let identities = if path.is_dir() {
+        read_directory_source(path.as_path())?
+    } else if matches!(path.extension(), Some("sqlite") | Some("db")) {
+        read_sqlite_source(path.as_path(), table.unwrap_or("members"))?
+    } else {
+        read_csv_source(path.as_path())?
+    };
+    SOURCES.write().insert(path, (modified, identities.clone()));
+    Ok(identities)
+}
+
+/// Latest modification time of `path`, or of the files in it if it is a directory
+fn source_modified(path: &camino::Utf8Path) -> fastn_core::Result<std::time::SystemTime> {
+    let mut modified = path.metadata()?.modified()?;
+    if path.is_dir() {
+        for entry in std::fs::read_dir(path)? {
+            modified = modified.max(entry?.metadata()?.modified()?);
+        }
+    }
+    Ok(modified)
+}
+
+fn read_csv_source(path: &camino::Utf8Path) -> fastn_core::Result<Vec<UserIdentity>> {
+    Ok(std::fs::read_to_string(path)?
+        .lines()
+        .map(|line| line.trim())
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .filter_map(|line| line.split_once(','))
+        .map(|(key, value)| (key.trim().trim_matches('"'), value.trim().trim_matches('"')))
+        // header row
+        .filter(|(key, value)| !(key.eq(&"key") && value.eq(&"value")))
+        .map(|(key, value)| UserIdentity::from(key, value))
+        .collect())
+}
+
+fn read_sqlite_source(
+    path: &camino::Utf8Path,
+    table: &str,
+) -> fastn_core::Result<Vec<UserIdentity>> {
+    if table.is_empty()
+        || !table
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+    {
+        return Err(fastn_core::Error::PackageError {
+            message: format!("user-group source: `{}`, invalid table: `{}`", path, table),
+        });
+    }
+    let sqlite_error = |e: rusqlite::Error| fastn_core::Error::PackageError {
+        message: format!("user-group source: `{}`, {}", path, e),
+    };
+    let conn =
+        rusqlite::Connection::open_with_flags(path, rusqlite::OpenFlags::SQLITE_OPEN_READ_ONLY)
+            .map_err(sqlite_error)?;
+    let mut stmt = conn
+        .prepare(format!("SELECT key, value FROM \"{}\"", table).as_str())
+        .map_err(sqlite_error)?;
+    let identities = stmt
+        .query_map([], |row| {
+            Ok(UserIdentity::from(
+                row.get::<_, String>(0)?.as_str(),
+                row.get::<_, String>(1)?.as_str(),
+            ))
+        })
+        .map_err(sqlite_error)?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(sqlite_error)?;
+    Ok(identities)
+}
+
+fn read_directory_source(path: &camino::Utf8Path) -> fastn_core::Result<Vec<UserIdentity>> {
+    let mut identities = vec![];
+    for entry in path.read_dir_utf8()? {
+        let entry = entry?;
+        let key = match entry
+            .path()
+            .file_name()
+            .and_then(|n| n.strip_suffix(".txt"))
+        {
+            Some(key) if entry.path().is_file() => key.to_string(),
+            _ => continue,
+        };
+        identities.extend(
+            std::fs::read_to_string(entry.path())?
+                .lines()
+                .map(|line| line.trim())
+                .filter(|line| !line.is_empty() && !line.starts_with('#'))
+                .map(|value| UserIdentity::from(key.as_str(), value)),
+        );
+    }
+    Ok(identities)
+}
+
 /// `get_identities` for a `document_name`
 /// This will get the identities from groups defined in sitemap
 pub fn get_identities(
@@ -564,6 +790,38 @@ pub async fn access_identities(
     }
 }
 
+/// Returns the ids of the user groups of the package the user making `req` belongs to
+pub async fn user_groups_of(
+    config: &fastn_core::Config,
+    req: &fastn_core::http::Request,
+) -> fastn_core::Result<Vec<String>> {
+    use itertools::Itertools;
+
+    let mut all_identities = vec![];
+    for group in config.package.groups.values() {
+        all_identities.extend(group.get_identities(config)?);
+    }
+    let identities =
+        match fastn_core::auth::get_auth_identities(req.cookies(), all_identities.as_slice()).await
+        {
+            Ok(ids) => ids,
+            Err(fastn_core::Error::GenericError(_err)) => vec![],
+            Err(e) => return Err(e),
+        };
+    if identities.is_empty() {
+        return Ok(vec![]);
+    }
+
+    let identities = identities.iter().collect_vec();
+    let mut groups = vec![];
+    for group in config.package.groups.values() {
+        if group.belongs_to(config, identities.as_slice())? {
+            groups.push(group.id.to_string());
+        }
+    }
+    Ok(groups)
+}
+
 pub mod processor {
     use itertools::Itertools;
 
@@ -662,4 +920,19 @@ mod tests {
     // TODO:
     #[test]
     fn get_identities() {}
+
+    #[test]
+    fn matches() {
+        let group = super::UserIdentity::from("email", "*@ourcompany.com");
+        assert!(group.matches(&super::UserIdentity::from("email", "Amit@OurCompany.com")));
+        assert!(!group.matches(&super::UserIdentity::from("email", "amit@example.com")));
+        assert!(!group.matches(&super::UserIdentity::from("github", "a@ourcompany.com")));
+
+        let excluded = super::UserIdentity::from("-email", "amit@*");
+        assert!(excluded.matches(&super::UserIdentity::from("email", "amit@ourcompany.com")));
+
+        let exact = super::UserIdentity::from("okta-group", "engineering");
+        assert!(exact.matches(&super::UserIdentity::from("okta-group", "Engineering")));
+        assert!(!exact.matches(&super::UserIdentity::from("okta-group", "engineering-2")));
+    }
 }
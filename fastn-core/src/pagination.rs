@@ -0,0 +1,180 @@
+//! Pagination of lists returned by processors.
+//!
+//! A processor returning a list, given a `page-size` header, returns only the items of the
+//! current page, and the `pagination` processor gives the details of the pages:
+//!
+//! ```ftd
+//! -- person list people:
+//! $processor$: pr.package-query
+//! db: people.sqlite
+//! page-size: 10
+//!
+//! SELECT * FROM people;
+//!
+//! -- pr.pagination-data pages:
+//! $processor$: pr.pagination
+//! ```
+//!
+//! The page is the `page` path parameter of a dynamic url, the `page` query parameter or the
+//! `/page/<n>/` suffix of the url, and `1` otherwise. `fastn build` renders the other pages of a
+//! document at `<url>/page/<n>/`. A document should have a single paginated list, as all its
+//! lists share the page.
+
+/// Details of the pages of the last paginated list of the current document
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+pub struct Pagination {
+    pub page: usize,
+    #[serde(rename = "page-size")]
+    pub page_size: usize,
+    /// number of items in the list
+    pub total: usize,
+    pub pages: usize,
+    #[serde(rename = "next-url")]
+    pub next_url: Option<String>,
+    #[serde(rename = "prev-url")]
+    pub prev_url: Option<String>,
+}
+
+/// `/blog/page/2/` is `("/blog/", 2)`
+pub(crate) fn split_page_url(path: &str) -> Option<(String, usize)> {
+    let (base, page) = path
+        .trim_end_matches('/')
+        .rsplit_once('/')
+        .and_then(|(base, page)| Some((base.strip_suffix("page")?, page.parse().ok()?)))?;
+    if !base.ends_with('/') && !base.is_empty() {
+        return None;
+    }
+    Some((format!("/{}", base.trim_start_matches('/')), page))
+}
+
+/// The url of `page` of the document at `base`, the first page is the document itself
+fn page_url(base: &str, page: usize) -> String {
+    if page == 1 {
+        return base.to_string();
+    }
+    format!("{}/page/{}/", base.trim_end_matches('/'), page)
+}
+
+/// The page asked for by the current request, or the document being built
+fn current_page(config: &fastn_core::Config) -> usize {
+    let from_path = config
+        .named_parameters
+        .iter()
+        .find(|(name, _)| name.eq("page"))
+        .and_then(|(_, value)| match value {
+            ftd::Value::Integer { value } => usize::try_from(*value).ok(),
+            ftd::Value::String { text, .. } => text.parse().ok(),
+            _ => None,
+        });
+    let from_query = || {
+        config
+            .request
+            .as_ref()
+            .and_then(|req| req.query().get("page").cloned())
+            .and_then(|page| match page {
+                serde_json::Value::Number(n) => n.as_u64().map(|n| n as usize),
+                serde_json::Value::String(s) => s.parse().ok(),
+                _ => None,
+            })
+    };
+    from_path.or_else(from_query).unwrap_or(1).max(1)
+}
+
+/// The url of the current document, without the `/page/<n>/` suffix
+fn document_url(config: &fastn_core::Config, base_url: &str) -> String {
+    let path = match config.request.as_ref() {
+        Some(req) => req.path().to_string(),
+        None => format!(
+            "{}/{}",
+            base_url.trim_end_matches('/'),
+            config.doc_id().unwrap_or_default().trim_start_matches('/')
+        ),
+    };
+    match split_page_url(path.as_str()) {
+        Some((base, _)) => base,
+        None => path,
+    }
+}
+
+/// Removes the `page-size` header of a processor, so the processor does not see it
+pub(crate) fn take_page_size(
+    value: &mut ftd::ast::VariableValue,
+    kind: &ftd::interpreter::Kind,
+    doc_id: &str,
+) -> ftd::interpreter::Result<Option<usize>> {
+    let headers = match value {
+        ftd::ast::VariableValue::Record { headers, .. } => headers,
+        _ => return Ok(None),
+    };
+    let header = match headers.0.iter().position(|h| h.key.eq("page-size")) {
+        Some(index) => headers.0.remove(index),
+        None => return Ok(None),
+    };
+    if !kind.is_list() {
+        return ftd::interpreter::utils::e2(
+            "`page-size` can only be used with a processor returning a list",
+            doc_id,
+            header.line_number,
+        );
+    }
+    match header.value.string(doc_id)?.trim().parse::<usize>() {
+        Ok(page_size) if page_size > 0 => Ok(Some(page_size)),
+        _ => ftd::interpreter::utils::e2(
+            "`page-size` should be a positive integer",
+            doc_id,
+            header.line_number,
+        ),
+    }
+}
+
+/// Keeps the items of the current page in the list `value`, and stores the details of the
+/// pages in `config.pagination`
+pub(crate) fn paginate(
+    value: ftd::interpreter::Value,
+    page_size: usize,
+    config: &mut fastn_core::Config,
+    base_url: &str,
+) -> ftd::interpreter::Value {
+    let (mut data, kind) = match value {
+        ftd::interpreter::Value::List { data, kind } => (data, kind),
+        t => return t,
+    };
+    let total = data.len();
+    let pages = ((total + page_size - 1) / page_size).max(1);
+    let page = current_page(config).min(pages);
+    let url = document_url(config, base_url);
+
+    data = data
+        .into_iter()
+        .skip((page - 1) * page_size)
+        .take(page_size)
+        .collect();
+    config.pagination = Some(Pagination {
+        page,
+        page_size,
+        total,
+        pages,
+        next_url: (page < pages).then(|| page_url(url.as_str(), page + 1)),
+        prev_url: (page > 1).then(|| page_url(url.as_str(), page - 1)),
+    });
+    ftd::interpreter::Value::List { data, kind }
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn split_page_url() {
+        assert_eq!(
+            super::split_page_url("/blog/page/2/"),
+            Some(("/blog/".to_string(), 2))
+        );
+        assert_eq!(
+            super::split_page_url("/page/3/"),
+            Some(("/".to_string(), 3))
+        );
+        assert_eq!(super::split_page_url("/blog/homepage/2/"), None);
+        assert_eq!(super::split_page_url("/blog/page/two/"), None);
+        assert_eq!(super::page_url("/blog/", 1), "/blog/");
+        assert_eq!(super::page_url("/blog/", 2), "/blog/page/2/");
+    }
+}
@@ -14,6 +14,114 @@ fn client_builder() -> reqwest::Client {
 static CLIENT: once_cell::sync::Lazy<std::sync::Arc<reqwest::Client>> =
     once_cell::sync::Lazy::new(|| std::sync::Arc::new(client_builder()));
 
+/// Headers carrying the identity of the user to the upstream, which are never taken from the
+/// client request
+pub(crate) const IDENTITY_HEADER: &str = "X-FASTN-IDENTITY";
+pub(crate) const GROUPS_HEADER: &str = "X-FASTN-GROUPS";
+pub(crate) const TIMESTAMP_HEADER: &str = "X-FASTN-TIMESTAMP";
+pub(crate) const SIGNATURE_HEADER: &str = "X-FASTN-SIGNATURE";
+
+/// HMAC-SHA256 of `message` keyed with `key`, as lowercase hex.
+///
+/// Upstreams verify [`IDENTITY_HEADER`] and [`GROUPS_HEADER`] by computing it, keyed with
+/// `SECRET_KEY`, over `"<timestamp>\n<method>\n<path>\n<identity>\n<groups>"`. `timestamp` is
+/// the value of [`TIMESTAMP_HEADER`], in seconds since the unix epoch, and `path` is the path the
+/// upstream received, without the query string. Upstreams should reject requests whose timestamp
+/// is more than five minutes away from their clock, so that a captured request can not be
+/// replayed later, or against another route.
+pub(crate) fn sign(key: &str, message: &str) -> String {
+    use hmac::Mac;
+
+    let mut mac = hmac::Hmac::<sha2::Sha256>::new_from_slice(key.as_bytes())
+        .expect("HMAC accepts keys of any length");
+    mac.update(message.as_bytes());
+    mac.finalize()
+        .into_bytes()
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+/// Percent-encodes `value`, as in `application/x-www-form-urlencoded`, so that it is a valid
+/// header value and can not contain the `,` and `:` separators of the identity headers
+fn encode(value: &str) -> String {
+    url::form_urlencoded::byte_serialize(value.as_bytes()).collect()
+}
+
+/// Adds the identity headers required by `rule` to `req_headers`, signed for a request with the
+/// method of `req` to `path` on the upstream.
+///
+/// [`IDENTITY_HEADER`] is a comma separated list of `<provider>:<username>` and [`GROUPS_HEADER`]
+/// a comma separated list of group ids, each part encoded with [`encode`]. Fails if `SECRET_KEY`
+/// is not set, as the key `fastn_core::auth::secret_key()` falls back to is public.
+pub(crate) async fn add_identity_headers(
+    config: &fastn_core::Config,
+    req: &fastn_core::http::Request,
+    rule: &fastn_package::old_fastn::ProxyRule,
+    path: &str,
+    req_headers: &mut std::collections::HashMap<String, String>,
+) -> fastn_core::Result<()> {
+    if !rule.forward_identity && !rule.forward_groups {
+        return Ok(());
+    }
+    let key = std::env::var("SECRET_KEY").map_err(|_| {
+        fastn_core::Error::generic(format!(
+            "SECRET_KEY has to be set to forward the identity of the user to `{}`",
+            rule.prefix
+        ))
+    })?;
+    let identity = if rule.forward_identity {
+        fastn_core::auth::get_logged_in_identities(req.cookies())
+            .await
+            .into_iter()
+            .map(|(provider, username)| format!("{}:{}", encode(&provider), encode(&username)))
+            .collect::<Vec<_>>()
+            .join(",")
+    } else {
+        "".to_string()
+    };
+    let groups = if rule.forward_groups {
+        fastn_core::user_group::user_groups_of(config, req)
+            .await?
+            .iter()
+            .map(|group| encode(group))
+            .collect::<Vec<_>>()
+            .join(",")
+    } else {
+        "".to_string()
+    };
+    let timestamp = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+        .to_string();
+    req_headers.insert(
+        SIGNATURE_HEADER.to_string(),
+        sign(
+            key.as_str(),
+            format!(
+                "{}\n{}\n/{}\n{}\n{}",
+                timestamp,
+                req.method(),
+                path.trim_start_matches('/'),
+                identity,
+                groups
+            )
+            .as_str(),
+        ),
+    );
+    req_headers.insert(TIMESTAMP_HEADER.to_string(), timestamp);
+    req_headers.insert(IDENTITY_HEADER.to_string(), identity);
+    req_headers.insert(GROUPS_HEADER.to_string(), groups);
+    Ok(())
+}
+
+fn to_header_value(name: &str, value: &str) -> fastn_core::Result<reqwest::header::HeaderValue> {
+    reqwest::header::HeaderValue::from_str(value).map_err(|e| {
+        fastn_core::Error::generic(format!("invalid value for header `{}`: {}", name, e))
+    })
+}
+
 // This method will connect client request to the out of the world
 #[tracing::instrument(skip_all)]
 pub(crate) async fn get_out(
@@ -22,6 +130,7 @@ pub(crate) async fn get_out(
     path: &str,
     package_name: &str,
     req_headers: &std::collections::HashMap<String, String>,
+    rule: Option<&fastn_package::old_fastn::ProxyRule>,
 ) -> fastn_core::Result<fastn_core::http::Response> {
     let headers = req.headers();
     // TODO: It should be part of fastn_core::Request::uri()
@@ -57,7 +166,25 @@ pub(crate) async fn get_out(
         )?,
     );
 
-    *proxy_request.headers_mut() = headers.to_owned();
+    *proxy_request.headers_mut() = headers
+        .iter()
+        .filter(|(name, _)| {
+            rule.map(|rule| rule.allows_header(name.as_str()))
+                .unwrap_or(true)
+        })
+        .map(|(name, value)| (name.clone(), value.clone()))
+        .collect();
+    for header in [
+        IDENTITY_HEADER,
+        GROUPS_HEADER,
+        TIMESTAMP_HEADER,
+        SIGNATURE_HEADER,
+    ] {
+        proxy_request.headers_mut().remove(header);
+    }
+    if let Some(timeout) = rule.and_then(|rule| rule.timeout) {
+        *proxy_request.timeout_mut() = Some(std::time::Duration::from_secs(timeout.max(1) as u64));
+    }
 
     // TODO: Some extra headers, possibly Authentication header
     // Authentication header can come from system environment variable
@@ -77,8 +204,10 @@ pub(crate) async fn get_out(
 
     for (header_key, header_value) in req_headers {
         proxy_request.headers_mut().insert(
-            reqwest::header::HeaderName::from_bytes(header_key.as_bytes()).unwrap(),
-            reqwest::header::HeaderValue::from_str(header_value.as_str()).unwrap(),
+            reqwest::header::HeaderName::from_bytes(header_key.as_bytes()).map_err(|e| {
+                fastn_core::Error::generic(format!("invalid header `{}`: {}", header_key, e))
+            })?,
+            to_header_value(header_key, header_value)?,
         );
     }
 
@@ -90,14 +219,17 @@ pub(crate) async fn get_out(
     if let Some(ip) = req.get_ip() {
         proxy_request.headers_mut().insert(
             reqwest::header::FORWARDED,
-            reqwest::header::HeaderValue::from_str(ip.as_str()).unwrap(),
+            to_header_value(reqwest::header::FORWARDED.as_str(), ip.as_str())?,
         );
     }
 
-    if let Some(cookies) = req.cookies_string() {
+    let forward_cookies = rule
+        .map(|rule| rule.allows_header(reqwest::header::COOKIE.as_str()))
+        .unwrap_or(true);
+    if let Some(cookies) = req.cookies_string().filter(|_| forward_cookies) {
         proxy_request.headers_mut().insert(
             reqwest::header::COOKIE,
-            reqwest::header::HeaderValue::from_str(cookies.as_str()).unwrap(),
+            to_header_value(reqwest::header::COOKIE.as_str(), cookies.as_str())?,
         );
     }
 
@@ -107,9 +239,30 @@ pub(crate) async fn get_out(
 
     *proxy_request.body_mut() = Some(req.body().to_vec().into());
 
-    Ok(fastn_core::http::ResponseBuilder::from_reqwest(
-        CLIENT.execute(proxy_request).await?,
-        package_name,
-    )
-    .await)
+    let response = CLIENT.execute(proxy_request).await?;
+    if rule.map(|rule| rule.stream).unwrap_or(false)
+        && response.status() != actix_web::http::StatusCode::FOUND
+    {
+        return Ok(fastn_core::http::ResponseBuilder::stream_reqwest(response));
+    }
+    Ok(fastn_core::http::ResponseBuilder::from_reqwest(response, package_name).await)
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn sign() {
+        // RFC 4231, test case 2
+        assert_eq!(
+            super::sign("Jefe", "what do ya want for nothing?"),
+            "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+    }
+
+    #[test]
+    fn encode() {
+        assert_eq!(super::encode("github:amitu"), "github%3Aamitu");
+        assert_eq!(super::encode("a,b c"), "a%2Cb+c");
+        assert_eq!(super::encode("åsa"), "%C3%A5sa");
+    }
 }
@@ -0,0 +1,285 @@
+//! Rate and request body size limits of `fastn serve`.
+//!
+//! Routes get their limits from `fastn.limit` in `FASTN.ftd`, and the others from the
+//! `--rate-limit` and `--max-body-size` flags. Requests are counted with a token bucket per
+//! route and client, the client being the ip, or the identity of the logged in user for limits
+//! with `per: identity`. `FASTN.ftd` is read once when the server starts.
+
+/// Buckets not used for this long are dropped when there are too many of them
+const IDLE: std::time::Duration = std::time::Duration::from_secs(3600);
+const MAX_BUCKETS: usize = 10_000;
+
+struct Bucket {
+    tokens: f64,
+    updated: std::time::Instant,
+}
+
+pub(crate) struct Limiter {
+    limits: Vec<fastn_package::old_fastn::Limit>,
+    /// Requests per minute per ip for routes without a `requests` limit
+    rate_limit: Option<u32>,
+    /// Bytes, for routes without a `max-body` limit
+    max_body_size: usize,
+    buckets: antidote::Mutex<std::collections::HashMap<String, Bucket>>,
+}
+
+impl Limiter {
+    pub(crate) fn new(
+        package: Option<&fastn_core::Package>,
+        rate_limit: Option<u32>,
+        max_body_size: usize,
+    ) -> Limiter {
+        Limiter {
+            limits: package.map(|p| p.limits.clone()).unwrap_or_default(),
+            rate_limit,
+            max_body_size,
+            buckets: antidote::Mutex::new(Default::default()),
+        }
+    }
+
+    /// The limit with the longest route matching `path`, of the ones `with` returns `true` for
+    fn limit(
+        &self,
+        path: &str,
+        with: impl Fn(&fastn_package::old_fastn::Limit) -> bool,
+    ) -> Option<&fastn_package::old_fastn::Limit> {
+        self.limits
+            .iter()
+            .filter(|limit| limit.matches(path) && with(limit))
+            .max_by_key(|limit| limit.route.trim_matches('/').len())
+    }
+
+    /// The `max-body` of the longest matching route applies, even if it does not set one
+    pub(crate) fn max_body_size(&self, path: &str) -> usize {
+        self.limit(path, |_| true)
+            .and_then(|limit| limit.max_body)
+            .map(|max_body| max_body.max(0) as usize)
+            .unwrap_or(self.max_body_size)
+    }
+
+    /// The largest body any route accepts, bodies are read up to this size
+    pub(crate) fn largest_body_size(&self) -> usize {
+        self.limits
+            .iter()
+            .filter_map(|limit| limit.max_body)
+            .map(|max_body| max_body.max(0) as usize)
+            .chain(std::iter::once(self.max_body_size))
+            .max()
+            .unwrap_or(self.max_body_size)
+    }
+
+    /// The response for `req` if it is over its limits
+    pub(crate) fn check(
+        &self,
+        req: &actix_web::dev::ServiceRequest,
+    ) -> Option<fastn_core::http::Response> {
+        let path = req.path();
+        let content_length = req
+            .headers()
+            .get(actix_web::http::header::CONTENT_LENGTH)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.parse::<usize>().ok());
+        if let Some(content_length) = content_length {
+            if content_length > self.max_body_size(path) {
+                return Some(payload_too_large(self.max_body_size(path)));
+            }
+        }
+
+        // a route which only limits the body size keeps the rate limit of its parent
+        let limit = self.limit(path, |limit| limit.requests.is_some());
+        let (route, requests, window, per_identity) = match (limit, self.rate_limit) {
+            (Some(limit), _) => (
+                limit.route.as_str(),
+                limit.requests.unwrap_or_default().max(0) as f64,
+                limit.window.max(1) as f64,
+                limit.per_identity(),
+            ),
+            (None, Some(rate_limit)) => ("*", rate_limit as f64, 60.0, false),
+            (None, None) => return None,
+        };
+        let client = match per_identity.then(|| identity(req)).flatten() {
+            Some(identity) => identity,
+            None => req
+                .peer_addr()
+                .map(|addr| addr.ip().to_string())
+                .unwrap_or_default(),
+        };
+
+        let retry_after = self.take(format!("{}|{}", route, client), requests, window)?;
+        tracing::info!(msg = "rate-limited", path = path, route = route);
+        Some(
+            actix_web::HttpResponse::TooManyRequests()
+                .insert_header((
+                    actix_web::http::header::RETRY_AFTER,
+                    retry_after.to_string(),
+                ))
+                .body("too many requests"),
+        )
+    }
+
+    /// Takes a token from the bucket of `key`, returns the seconds to wait if there is none left
+    fn take(&self, key: String, requests: f64, window: f64) -> Option<u64> {
+        self.take_at(key, requests, window, std::time::Instant::now())
+    }
+
+    fn take_at(
+        &self,
+        key: String,
+        requests: f64,
+        window: f64,
+        now: std::time::Instant,
+    ) -> Option<u64> {
+        let rate = requests / window;
+        let mut buckets = self.buckets.lock();
+        if buckets.len() > MAX_BUCKETS {
+            buckets.retain(|_, bucket| now.duration_since(bucket.updated) < IDLE);
+        }
+        let bucket = buckets.entry(key).or_insert(Bucket {
+            tokens: requests,
+            updated: now,
+        });
+        bucket.tokens =
+            (bucket.tokens + now.duration_since(bucket.updated).as_secs_f64() * rate).min(requests);
+        bucket.updated = now;
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            return None;
+        }
+        if rate <= 0.0 {
+            return Some(window as u64);
+        }
+        // rounded to milliseconds first, so that float errors do not add a second
+        let wait = ((1.0 - bucket.tokens) / rate * 1000.0).round() / 1000.0;
+        Some(wait.ceil() as u64)
+    }
+}
+
+pub(crate) fn payload_too_large(max_body_size: usize) -> fastn_core::http::Response {
+    actix_web::HttpResponse::PayloadTooLarge().body(format!(
+        "request body is larger than {} bytes",
+        max_body_size
+    ))
+}
+
+/// `<provider>:<hash of the user details>` of the first valid auth cookie of `req`
+fn identity(req: &actix_web::dev::ServiceRequest) -> Option<String> {
+    fastn_core::auth::AuthProviders::AUTH_ITER
+        .iter()
+        .find_map(|provider| {
+            let cookie = req.cookie(provider.as_str())?;
+            let user_detail = fastn_core::auth::utils::decrypt(cookie.value()).ok()?;
+            Some(format!(
+                "{}:{}",
+                provider.as_str(),
+                fastn_core::utils::generate_hash(user_detail.as_str())
+            ))
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    fn limit(
+        route: &str,
+        requests: Option<i64>,
+        max_body: Option<i64>,
+    ) -> fastn_package::old_fastn::Limit {
+        fastn_package::old_fastn::Limit {
+            route: route.to_string(),
+            requests,
+            window: 60,
+            per: "ip".to_string(),
+            max_body,
+        }
+    }
+
+    fn limiter(limits: Vec<fastn_package::old_fastn::Limit>) -> super::Limiter {
+        super::Limiter {
+            limits,
+            rate_limit: None,
+            max_body_size: 1000,
+            buckets: antidote::Mutex::new(Default::default()),
+        }
+    }
+
+    #[test]
+    fn take() {
+        let limiter = limiter(vec![]);
+        let start = std::time::Instant::now();
+        let at = |secs: u64| start + std::time::Duration::from_secs(secs);
+        let take = |key: &str, secs| limiter.take_at(key.to_string(), 2.0, 60.0, at(secs));
+
+        assert_eq!(take("a", 0), None);
+        assert_eq!(take("a", 0), None);
+        // one token every 30 seconds
+        assert_eq!(take("a", 0), Some(30));
+        assert_eq!(take("a", 10), Some(20));
+        // buckets are per key
+        assert_eq!(take("b", 10), None);
+
+        assert_eq!(take("a", 30), None);
+        assert_eq!(take("a", 30), Some(30));
+
+        // refills up to `requests`, not more
+        assert_eq!(take("a", 1000), None);
+        assert_eq!(take("a", 1000), None);
+        assert_eq!(take("a", 1000), Some(30));
+    }
+
+    #[test]
+    fn take_no_requests() {
+        let limiter = limiter(vec![]);
+        let now = std::time::Instant::now();
+        assert_eq!(limiter.take_at("a".to_string(), 0.0, 60.0, now), Some(60));
+        assert_eq!(
+            limiter.take_at(
+                "a".to_string(),
+                0.0,
+                60.0,
+                now + std::time::Duration::from_secs(3600)
+            ),
+            Some(60)
+        );
+    }
+
+    #[test]
+    fn max_body_size() {
+        let limiter = limiter(vec![
+            limit("/api/", Some(10), Some(100)),
+            limit("/api/upload/", None, Some(10_000)),
+            limit("/api/upload/small/", None, None),
+            limit("/negative/", None, Some(-1)),
+        ]);
+        assert_eq!(limiter.max_body_size("/"), 1000);
+        assert_eq!(limiter.max_body_size("/apis/"), 1000);
+        assert_eq!(limiter.max_body_size("/api/"), 100);
+        assert_eq!(limiter.max_body_size("/api/users/"), 100);
+        // the longest matching route wins
+        assert_eq!(limiter.max_body_size("/api/upload/"), 10_000);
+        assert_eq!(limiter.max_body_size("/api/uploads/"), 100);
+        // even if it does not set `max-body`
+        assert_eq!(limiter.max_body_size("/api/upload/small/"), 1000);
+        assert_eq!(limiter.max_body_size("/negative/"), 0);
+        assert_eq!(limiter.largest_body_size(), 10_000);
+    }
+
+    #[test]
+    fn requests_limit() {
+        let limiter = limiter(vec![
+            limit("/api/", Some(10), None),
+            limit("/api/upload/", None, Some(10_000)),
+            limit("/api/upload/bulk/", Some(1), None),
+        ]);
+        let route = |path| {
+            limiter
+                .limit(path, |l| l.requests.is_some())
+                .map(|l| l.route.as_str())
+        };
+        assert_eq!(route("/"), None);
+        assert_eq!(route("/api/users/"), Some("/api/"));
+        // `/api/upload/` only sets `max-body`, the rate limit of `/api/` still applies
+        assert_eq!(route("/api/upload/"), Some("/api/"));
+        assert_eq!(limiter.max_body_size("/api/upload/"), 10_000);
+        assert_eq!(route("/api/upload/bulk/"), Some("/api/upload/bulk/"));
+        assert_eq!(limiter.max_body_size("/api/upload/bulk/"), 1000);
+    }
+}
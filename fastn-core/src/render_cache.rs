@@ -0,0 +1,200 @@
+//! Rendered html of documents served by `fastn serve`.
+//!
+//! Only documents which do not use request dependent processors are stored, see
+//! `fastn_core::Library2022::process`. Entries are keyed by the document, the package and its
+//! `FASTN.ftd`, the edition and the user groups the user belongs to, and are dropped on any file
+//! change seen by the watcher and on `/-/clear-cache/`.
+
+#[derive(Clone)]
+pub(crate) struct Entry {
+    pub html: Vec<u8>,
+    pub etag: String,
+    pub last_modified: std::time::SystemTime,
+}
+
+static CACHE: once_cell::sync::Lazy<antidote::RwLock<std::collections::HashMap<String, Entry>>> =
+    once_cell::sync::Lazy::new(|| antidote::RwLock::new(Default::default()));
+
+pub(crate) fn clear() {
+    CACHE.write().clear();
+}
+
+pub(crate) async fn key(
+    config: &fastn_core::Config,
+    document_id: &str,
+) -> fastn_core::Result<String> {
+    let fastn_ftd = match config.package.fastn_path.as_ref() {
+        Some(path) => tokio::fs::read_to_string(path).await.unwrap_or_default(),
+        None => "".to_string(),
+    };
+    let groups = match config.request.as_ref() {
+        Some(req)
+            if fastn_core::auth::AuthProviders::AUTH_ITER
+                .iter()
+                .any(|provider| req.cookie(provider.as_str()).is_some()) =>
+        {
+            fastn_core::user_group::user_groups_of(config, req).await?
+        }
+        _ => vec![],
+    };
+    Ok(format!(
+        "{}|{}|{}|{:?}|{}",
+        document_id,
+        config.package.name,
+        fastn_core::utils::generate_hash(fastn_ftd.as_str()),
+        config.ftd_edition,
+        groups.join(",")
+    ))
+}
+
+pub(crate) fn get(key: &str) -> Option<Entry> {
+    CACHE.read().get(key).cloned()
+}
+
+pub(crate) fn insert(key: String, html: Vec<u8>) -> Entry {
+    let entry = Entry {
+        etag: format!(
+            "\"{}\"",
+            fastn_core::utils::generate_hash(String::from_utf8_lossy(html.as_slice()).as_ref())
+        ),
+        last_modified: std::time::SystemTime::now(),
+        html,
+    };
+    CACHE.write().insert(key, entry.clone());
+    entry
+}
+
+/// `true` if the conditional headers of `req` match `entry`, in which case a `304 Not Modified`
+/// can be sent instead of the html
+pub(crate) fn is_fresh(req: &fastn_core::http::Request, entry: &Entry) -> bool {
+    let header = |name: actix_web::http::header::HeaderName| {
+        req.headers()
+            .get(name.as_str())
+            .and_then(|v| v.to_str().ok())
+            .map(|v| v.to_string())
+    };
+    if let Some(if_none_match) = header(actix_web::http::header::IF_NONE_MATCH) {
+        return if_none_match
+            .split(',')
+            .any(|etag| etag.trim().eq(entry.etag.as_str()) || etag.trim().eq("*"));
+    }
+    if let Some(if_modified_since) = header(actix_web::http::header::IF_MODIFIED_SINCE) {
+        if let Ok(since) = if_modified_since
+            .parse::<actix_web::http::header::HttpDate>()
+            .map(std::time::SystemTime::from)
+        {
+            // Http dates have a precision of a second
+            return entry
+                .last_modified
+                .duration_since(since)
+                .map(|d| d.as_secs() == 0)
+                .unwrap_or(true);
+        }
+    }
+    false
+}
+
+pub(crate) fn response(
+    req: &fastn_core::http::Request,
+    entry: Entry,
+) -> fastn_core::http::Response {
+    let fresh = is_fresh(req, &entry);
+    let mut builder = if fresh {
+        actix_web::HttpResponse::NotModified()
+    } else {
+        actix_web::HttpResponse::Ok()
+    };
+    builder
+        .insert_header((actix_web::http::header::ETAG, entry.etag.as_str()))
+        .insert_header(actix_web::http::header::LastModified(
+            entry.last_modified.into(),
+        ));
+    if fresh {
+        return builder.finish();
+    }
+    builder
+        .content_type(mime_guess::mime::TEXT_HTML_UTF_8)
+        .body(entry.html)
+}
+
+#[cfg(test)]
+mod tests {
+    fn request(headers: &[(&str, &str)]) -> fastn_core::http::Request {
+        let mut req = actix_web::test::TestRequest::get();
+        for header in headers {
+            req = req.insert_header(*header);
+        }
+        fastn_core::http::Request::from_actix(req.to_http_request(), Default::default())
+    }
+
+    fn entry() -> super::Entry {
+        super::Entry {
+            html: b"<html></html>".to_vec(),
+            etag: "\"abc\"".to_string(),
+            last_modified: std::time::UNIX_EPOCH + std::time::Duration::from_secs(1_000_000_500),
+        }
+    }
+
+    fn http_date(secs: u64) -> String {
+        actix_web::http::header::HttpDate::from(
+            std::time::UNIX_EPOCH + std::time::Duration::from_secs(secs),
+        )
+        .to_string()
+    }
+
+    #[test]
+    fn is_fresh_if_none_match() {
+        let entry = entry();
+        assert!(super::is_fresh(
+            &request(&[("if-none-match", "\"abc\"")]),
+            &entry
+        ));
+        assert!(super::is_fresh(
+            &request(&[("if-none-match", "\"xyz\", \"abc\"")]),
+            &entry
+        ));
+        assert!(super::is_fresh(&request(&[("if-none-match", "*")]), &entry));
+        assert!(!super::is_fresh(
+            &request(&[("if-none-match", "\"xyz\"")]),
+            &entry
+        ));
+        assert!(!super::is_fresh(
+            &request(&[("if-none-match", "abc")]),
+            &entry
+        ));
+        assert!(!super::is_fresh(&request(&[]), &entry));
+    }
+
+    #[test]
+    fn is_fresh_if_modified_since() {
+        let entry = entry();
+        let since = |secs| request(&[("if-modified-since", http_date(secs).as_str())]);
+        assert!(super::is_fresh(&since(1_000_000_500), &entry));
+        assert!(super::is_fresh(&since(1_000_000_600), &entry));
+        assert!(!super::is_fresh(&since(1_000_000_400), &entry));
+        assert!(!super::is_fresh(
+            &request(&[("if-modified-since", "yesterday")]),
+            &entry
+        ));
+
+        // If-None-Match takes precedence
+        assert!(!super::is_fresh(
+            &request(&[
+                ("if-none-match", "\"xyz\""),
+                ("if-modified-since", http_date(1_000_000_600).as_str())
+            ]),
+            &entry
+        ));
+    }
+
+    #[test]
+    fn response() {
+        let response = super::response(&request(&[("if-none-match", "\"abc\"")]), entry());
+        assert_eq!(response.status(), actix_web::http::StatusCode::NOT_MODIFIED);
+        assert_eq!(response.headers().get("etag").unwrap(), "\"abc\"");
+
+        let response = super::response(&request(&[]), entry());
+        assert_eq!(response.status(), actix_web::http::StatusCode::OK);
+        assert!(response.headers().get("last-modified").is_some());
+    }
+}
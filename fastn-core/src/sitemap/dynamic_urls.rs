@@ -103,6 +103,52 @@ impl DynamicUrls {
         false
     }
 
+    /// Fails if a url of the `sitemap` also matches a dynamic url. The sitemap url would always
+    /// win, so the dynamic url would silently never be served for it.
+    pub fn check_conflicts(
+        &self,
+        sitemap: Option<&fastn_core::sitemap::Sitemap>,
+    ) -> Result<(), fastn_core::sitemap::ParseError> {
+        fn toc_urls<'a>(toc: &'a fastn_core::sitemap::toc::TocItem, urls: &mut Vec<&'a str>) {
+            urls.push(toc.id.as_str());
+            for child in toc.children.iter() {
+                toc_urls(child, urls);
+            }
+        }
+
+        let sitemap = match sitemap {
+            Some(sitemap) => sitemap,
+            None => return Ok(()),
+        };
+        let mut urls = vec![];
+        for section in sitemap.sections.iter() {
+            urls.push(section.id.as_str());
+            for subsection in section.subsections.iter() {
+                urls.extend(subsection.id.as_deref());
+                for toc in subsection.toc.iter() {
+                    toc_urls(toc, &mut urls);
+                }
+            }
+        }
+
+        for url in urls {
+            // links to other sites and to headings can not conflict
+            let url = url.split_once('#').map(|(url, _)| url).unwrap_or(url);
+            if url.contains("://") || url.is_empty() {
+                continue;
+            }
+            if let Ok((Some(document), _)) = self.resolve_document(url) {
+                return Err(fastn_core::sitemap::ParseError::InvalidDynamicUrls {
+                    message: format!(
+                        "sitemap url `{}` is also matched by the dynamic url of document `{}`",
+                        url, document
+                    ),
+                });
+            }
+        }
+        Ok(())
+    }
+
     #[tracing::instrument(name = "dynamic-urls-resolve-document")]
     pub fn resolve_document(&self, path: &str) -> fastn_core::Result<ResolveDocOutput> {
         fn resolve_in_toc(
@@ -203,6 +249,254 @@ impl DynamicUrls {
     }
 }
 
+/// A page `fastn build` renders for a dynamic url
+#[derive(Debug, Clone, PartialEq)]
+pub struct Prerender {
+    /// `/person/ada/`
+    pub url: String,
+    pub document: String,
+    pub path_params: Vec<(String, ftd::Value)>,
+}
+
+impl DynamicUrls {
+    /// Pages to render for the dynamic urls which declare the values of their parameters, with
+    /// one of:
+    ///
+    /// ```ftd
+    /// -- fastn.dynamic-urls:
+    ///
+    /// # People
+    /// - Person
+    ///   url: /person/<string:name>/
+    ///   document: person.ftd
+    ///   prerender-values: ["ada", {"name": "grace"}]
+    /// - Post
+    ///   url: /blog/<slug:post>/
+    ///   document: post.ftd
+    ///   prerender-db: blog.sqlite
+    ///   prerender-query: SELECT slug AS post FROM posts
+    /// - Product
+    ///   url: /product/<integer:id>/<slug:name>/
+    ///   document: product.ftd
+    ///   prerender-data: products.csv
+    /// ```
+    ///
+    /// Each value is an object with a key for every path parameter, or just the value of the
+    /// first path parameter. `prerender-query` columns and the header row of a
+    /// `prerender-data` csv file are named after the parameters, a json `prerender-data` file
+    /// has the same format as `prerender-values`. Query parameters are not known when
+    /// pre-rendering, so pages needing them are left to `fastn serve`.
+    pub fn prerender(&self, root: &camino::Utf8Path) -> fastn_core::Result<Vec<Prerender>> {
+        fn toc_items<'a>(
+            toc: &'a fastn_core::sitemap::toc::TocItem,
+            items: &mut Vec<DynamicItem<'a>>,
+        ) {
+            items.push((&toc.document, &toc.path_parameters, &toc.extra_data));
+            for child in toc.children.iter() {
+                toc_items(child, items);
+            }
+        }
+
+        let mut items = vec![];
+        for section in self.sections.iter() {
+            items.push((
+                &section.document,
+                &section.path_parameters,
+                &section.extra_data,
+            ));
+            for subsection in section.subsections.iter() {
+                items.push((
+                    &subsection.document,
+                    &subsection.path_parameters,
+                    &subsection.extra_data,
+                ));
+                for toc in subsection.toc.iter() {
+                    toc_items(toc, &mut items);
+                }
+            }
+        }
+
+        let mut pages = vec![];
+        for (document, params, extra_data) in items {
+            let document = match document {
+                Some(document) if !params.is_empty() => document,
+                _ => continue,
+            };
+            for values in prerender_values(root, extra_data)? {
+                let url = fill_url(params, &values)?;
+                let (matched, path_params) =
+                    fastn_core::sitemap::utils::url_match(url.as_str(), params)?;
+                if !matched {
+                    return Err(fastn_core::Error::PackageError {
+                        message: format!(
+                            "dynamic-urls: prerender value {} of `{}` does not match its url",
+                            serde_json::Value::Object(values),
+                            document
+                        ),
+                    });
+                }
+                pages.push(Prerender {
+                    url,
+                    document: document.to_string(),
+                    path_params,
+                });
+            }
+        }
+        Ok(pages)
+    }
+}
+
+/// document, path parameters and the key values of an item of the dynamic urls
+type DynamicItem<'a> = (
+    &'a Option<String>,
+    &'a Vec<fastn_core::sitemap::PathParams>,
+    &'a std::collections::BTreeMap<String, String>,
+);
+
+type ParamValues = serde_json::Map<String, serde_json::Value>;
+
+fn prerender_values(
+    root: &camino::Utf8Path,
+    extra_data: &std::collections::BTreeMap<String, String>,
+) -> fastn_core::Result<Vec<ParamValues>> {
+    let mut values = vec![];
+    if let Some(json) = extra_data.get("prerender-values") {
+        values.extend(values_from_json(json.as_str())?);
+    }
+    if let Some(query) = extra_data.get("prerender-query") {
+        let db = extra_data
+            .get("prerender-db")
+            .ok_or_else(|| fastn_core::Error::PackageError {
+                message: "dynamic-urls: `prerender-query` needs `prerender-db`".to_string(),
+            })?;
+        values.extend(values_from_query(root.join(db).as_path(), query.as_str())?);
+    }
+    if let Some(path) = extra_data.get("prerender-data") {
+        let path = root.join(path);
+        let content = std::fs::read_to_string(&path)?;
+        if path.extension().eq(&Some("csv")) {
+            values.extend(values_from_csv(content.as_str()));
+        } else {
+            values.extend(values_from_json(content.as_str())?);
+        }
+    }
+    Ok(values)
+}
+
+/// `["ada", {"name": "grace"}]`, a bare value is stored with an empty key, and is used for the
+/// first path parameter of the url
+fn values_from_json(json: &str) -> fastn_core::Result<Vec<ParamValues>> {
+    let values: Vec<serde_json::Value> = serde_json::from_str(json)?;
+    Ok(values
+        .into_iter()
+        .map(|value| match value {
+            serde_json::Value::Object(values) => values,
+            value => ParamValues::from_iter([("".to_string(), value)]),
+        })
+        .collect())
+}
+
+fn values_from_csv(csv: &str) -> Vec<ParamValues> {
+    let mut lines = csv
+        .lines()
+        .map(|line| line.trim())
+        .filter(|line| !line.is_empty());
+    let header = match lines.next() {
+        Some(header) => header.split(',').map(|h| h.trim()).collect::<Vec<_>>(),
+        None => return vec![],
+    };
+    lines
+        .map(|line| {
+            header
+                .iter()
+                .zip(line.split(','))
+                .map(|(key, value)| {
+                    (
+                        key.to_string(),
+                        serde_json::Value::String(value.trim().trim_matches('"').to_string()),
+                    )
+                })
+                .collect()
+        })
+        .collect()
+}
+
+fn values_from_query(db: &camino::Utf8Path, query: &str) -> fastn_core::Result<Vec<ParamValues>> {
+    let sqlite_error = |e: rusqlite::Error| fastn_core::Error::PackageError {
+        message: format!("dynamic-urls: prerender-query on `{}`: {}", db, e),
+    };
+    let conn =
+        rusqlite::Connection::open_with_flags(db, rusqlite::OpenFlags::SQLITE_OPEN_READ_ONLY)
+            .map_err(sqlite_error)?;
+    let mut stmt = conn.prepare(query).map_err(sqlite_error)?;
+    let columns = stmt
+        .column_names()
+        .into_iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>();
+    let mut rows = stmt.query([]).map_err(sqlite_error)?;
+    let mut values = vec![];
+    while let Some(row) = rows.next().map_err(sqlite_error)? {
+        let mut row_values = ParamValues::new();
+        for (i, column) in columns.iter().enumerate() {
+            let value = match row.get_ref(i).map_err(sqlite_error)? {
+                rusqlite::types::ValueRef::Integer(v) => serde_json::Value::from(v),
+                rusqlite::types::ValueRef::Real(v) => serde_json::Value::from(v),
+                rusqlite::types::ValueRef::Text(v) => {
+                    serde_json::Value::String(String::from_utf8_lossy(v).to_string())
+                }
+                rusqlite::types::ValueRef::Null | rusqlite::types::ValueRef::Blob(_) => continue,
+            };
+            row_values.insert(column.to_string(), value);
+        }
+        values.push(row_values);
+    }
+    Ok(values)
+}
+
+/// The url of the path parameters for `values`, `/person/<string:name>/` and `{"name": "ada"}`
+/// gives `/person/ada/`
+fn fill_url(
+    params: &[fastn_core::sitemap::PathParams],
+    values: &ParamValues,
+) -> fastn_core::Result<String> {
+    let mut first = true;
+    let mut url = String::new();
+    for param in params {
+        match param {
+            fastn_core::sitemap::PathParams::ValueParam { value, .. } => {
+                url.push('/');
+                url.push_str(value);
+            }
+            fastn_core::sitemap::PathParams::NamedParm { name, optional, .. } => {
+                let value = values
+                    .get(name)
+                    .or_else(|| first.then(|| values.get("")).flatten());
+                first = false;
+                let value = match value {
+                    Some(serde_json::Value::String(v)) => v.to_string(),
+                    Some(serde_json::Value::Null) | None if *optional => continue,
+                    Some(serde_json::Value::Null) | None => {
+                        return Err(fastn_core::Error::PackageError {
+                            message: format!(
+                                "dynamic-urls: prerender value {} has no `{}`",
+                                serde_json::Value::Object(values.clone()),
+                                name
+                            ),
+                        })
+                    }
+                    Some(v) => v.to_string(),
+                };
+                url.push('/');
+                url.push_str(value.trim_matches('/'));
+            }
+            fastn_core::sitemap::PathParams::QueryParam { .. } => {}
+        }
+    }
+    url.push('/');
+    Ok(url)
+}
+
 #[cfg(test)]
 mod tests {
 
@@ -333,4 +627,55 @@ mod tests {
         });
         assert_eq!(left, right)
     }
+
+    #[test]
+    fn prerender() {
+        let dynamic_urls = fastn_core::sitemap::DynamicUrls::parse(
+            &std::collections::HashMap::new(),
+            "abrark.com",
+            r#"
+# Dynamic Urls Section
+- Person
+  url: /person/<string:name>/<integer:age?>/
+  document: person.ftd
+  prerender-values: ["ada", {"name": "grace", "age": 85}]
+"#,
+        )
+        .unwrap();
+
+        let pages = dynamic_urls
+            .prerender(camino::Utf8Path::new("."))
+            .unwrap()
+            .into_iter()
+            .map(|p| (p.url, p.path_params))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            pages,
+            vec![
+                (
+                    "/person/ada/".to_string(),
+                    vec![(
+                        "name".to_string(),
+                        ftd::Value::String {
+                            text: "ada".to_string(),
+                            source: ftd::TextSource::Default
+                        }
+                    )]
+                ),
+                (
+                    "/person/grace/85/".to_string(),
+                    vec![
+                        (
+                            "name".to_string(),
+                            ftd::Value::String {
+                                text: "grace".to_string(),
+                                source: ftd::TextSource::Default
+                            }
+                        ),
+                        ("age".to_string(), ftd::Value::Integer { value: 85 })
+                    ]
+                ),
+            ]
+        )
+    }
 }
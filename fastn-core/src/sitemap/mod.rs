@@ -49,17 +49,27 @@ pub enum SitemapElement {
     TocItem(toc::TocItem),
 }
 
+/// Parts of a dynamic url, see `fastn_core::sitemap::utils::parse_named_params` for the syntax
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum PathParams {
     NamedParm {
         index: usize,
         name: String,
+        /// see `fastn_core::sitemap::utils::PARAM_TYPES`
         param_type: String,
+        /// `<string:name?>`, the segment can be left out
+        optional: bool,
     },
     ValueParam {
         index: usize,
         value: String,
     },
+    /// `?<integer:page?>`, a parameter from the query string of the request
+    QueryParam {
+        name: String,
+        param_type: String,
+        optional: bool,
+    },
 }
 
 impl PathParams {
@@ -68,6 +78,16 @@ impl PathParams {
             index,
             name,
             param_type,
+            optional: false,
+        }
+    }
+
+    pub fn optional(index: usize, name: String, param_type: String) -> Self {
+        PathParams::NamedParm {
+            index,
+            name,
+            param_type,
+            optional: true,
         }
     }
 
@@ -75,8 +95,16 @@ impl PathParams {
         PathParams::ValueParam { index, value }
     }
 
+    pub fn query(name: String, param_type: String, optional: bool) -> Self {
+        PathParams::QueryParam {
+            name,
+            param_type,
+            optional,
+        }
+    }
+
     pub fn is_named_param(&self) -> bool {
-        matches!(self, Self::NamedParm { .. })
+        matches!(self, Self::NamedParm { .. } | Self::QueryParam { .. })
     }
 }
 
@@ -626,6 +654,7 @@ impl Sitemap {
             } else if crate::http::url_regex()
                 .find(section.get_file_id().as_str())
                 .is_some()
+                || section.extra_data.contains_key("backend")
             {
                 (None, None)
             } else {
@@ -693,7 +722,9 @@ impl Sitemap {
                         Some(config.root.join(file_name.as_str())),
                         Some(config.root.join(file_name.as_str())),
                     )
-                } else if crate::http::url_regex().find(id.as_str()).is_some() {
+                } else if crate::http::url_regex().find(id.as_str()).is_some()
+                    || subsection.extra_data.contains_key("backend")
+                {
                     (None, None)
                 } else {
                     match fastn_core::Config::get_file_name(current_package_root, id.as_str()) {
@@ -753,6 +784,7 @@ impl Sitemap {
                 || crate::http::url_regex()
                     .find(toc.get_file_id().as_str())
                     .is_some()
+                || toc.extra_data.contains_key("backend")
             {
                 (None, None)
             } else {
@@ -1693,9 +1725,11 @@ fn construct_tree(elements: Vec<(toc::TocItem, usize)>, smallest_level: usize) -
     stack_tree
 }
 
+/// `query` is the query string of the request, dynamic urls can have query parameters
 pub fn resolve(
     package: &fastn_core::Package,
     path: &str,
+    query: Option<&str>,
 ) -> fastn_core::Result<fastn_core::sitemap::dynamic_urls::ResolveDocOutput> {
     // resolve in sitemap
     if let Some(sitemap) = package.sitemap.as_ref() {
@@ -1706,7 +1740,10 @@ pub fn resolve(
 
     // resolve in dynamic-urls
     if let Some(dynamic_urls) = package.dynamic_urls.as_ref() {
-        return dynamic_urls.resolve_document(path);
+        return match query.filter(|q| !q.is_empty()) {
+            Some(query) => dynamic_urls.resolve_document(format!("{}?{}", path, query).as_str()),
+            None => dynamic_urls.resolve_document(path),
+        };
     };
 
     Ok((None, vec![]))
@@ -17,100 +17,307 @@ pub fn url_match(
     sitemap_params: &[fastn_core::sitemap::PathParams],
 ) -> fastn_core::Result<(bool, Vec<(String, ftd::Value)>)> {
     use itertools::Itertools;
+
+    // request_url: /abrark/foo/28/?page=2
+    let (request_path, query) = request_url.split_once('?').unwrap_or((request_url, ""));
     // request_attrs: [abrark, foo, 28]
-    let request_parts = request_url.trim_matches('/').split('/').collect_vec();
-    // This should go to config request [username: abrark, age: 28]
-    if request_parts.len().ne(&sitemap_params.len()) {
-        return Ok((false, vec![]));
-    }
+    let request_parts = request_path
+        .trim_matches('/')
+        .split('/')
+        .filter(|part| !part.is_empty())
+        .collect_vec();
 
     // match logic
     // req: [a, ak, foo]
     // d-urls: [(0, a, None), (1, username, Some(string)), (2, foo, None)]
     // [(param_name, value)]
+    // Optional and `path` parameters are only allowed at the end, see `parse_named_params`, so
+    // matching never has to backtrack.
     let mut path_parameters: Vec<(String, ftd::Value)> = vec![];
     let mut count = 0;
-    for req_part in request_parts {
-        match &sitemap_params[count] {
+    for param in sitemap_params {
+        match param {
             fastn_core::sitemap::PathParams::ValueParam { index: _, value } => {
-                count += 1;
-                if req_part.eq(value) {
-                    continue;
-                } else {
+                if request_parts.get(count).ne(&Some(&value.as_str())) {
                     return Ok((false, vec![]));
                 }
+                count += 1;
             }
             fastn_core::sitemap::PathParams::NamedParm {
                 index: _,
                 name,
                 param_type,
-            } => {
-                count += 1;
-                if let Ok(value) = get_value_type(req_part, param_type) {
-                    path_parameters.push((name.to_string(), value));
-                } else {
+                optional,
+            } if param_type.eq("path") => {
+                // catch-all, the rest of the url
+                let rest = request_parts[count.min(request_parts.len())..].join("/");
+                count = request_parts.len();
+                if rest.is_empty() {
+                    if *optional {
+                        continue;
+                    }
                     return Ok((false, vec![]));
                 }
+                path_parameters.push((
+                    name.to_string(),
+                    ftd::Value::String {
+                        text: rest,
+                        source: ftd::TextSource::Default,
+                    },
+                ));
             }
+            fastn_core::sitemap::PathParams::NamedParm {
+                index: _,
+                name,
+                param_type,
+                optional,
+            } => match request_parts.get(count) {
+                Some(req_part) => {
+                    count += 1;
+                    match get_value_type(req_part, param_type) {
+                        Ok(value) => path_parameters.push((name.to_string(), value)),
+                        Err(_) => return Ok((false, vec![])),
+                    }
+                }
+                None if *optional => {}
+                None => return Ok((false, vec![])),
+            },
+            fastn_core::sitemap::PathParams::QueryParam { .. } => {}
         };
     }
-    return Ok((true, path_parameters));
-
-    fn get_value_type(value: &str, r#type: &str) -> fastn_core::Result<ftd::Value> {
-        match r#type {
-            "string" => Ok(ftd::Value::String {
-                text: value.to_string(),
-                source: ftd::TextSource::Default,
-            }),
-            "integer" => {
-                let value = value.parse::<i64>()?;
-                Ok(ftd::Value::Integer { value })
+    if count.ne(&request_parts.len()) {
+        return Ok((false, vec![]));
+    }
+
+    let query = url::form_urlencoded::parse(query.as_bytes()).collect_vec();
+    for param in sitemap_params {
+        if let fastn_core::sitemap::PathParams::QueryParam {
+            name,
+            param_type,
+            optional,
+        } = param
+        {
+            match query.iter().find(|(key, _)| key.eq(name)) {
+                Some((_, value)) => match get_value_type(value, param_type) {
+                    Ok(value) => path_parameters.push((name.to_string(), value)),
+                    Err(_) => return Ok((false, vec![])),
+                },
+                None if *optional => {}
+                None => return Ok((false, vec![])),
             }
-            "decimal" => {
-                let value = value.parse::<f64>()?;
-                Ok(ftd::Value::Decimal { value })
+        }
+    }
+    Ok((true, path_parameters))
+}
+
+/// Types a dynamic url parameter can have, besides `enum(<a>|<b>|..)` which allows only the
+/// listed values, and `regex(<pattern>)` which allows values matching the whole pattern.
+/// `slug` allows lowercase letters, digits and `-`, and `path` is a catch-all for the rest of
+/// the url.
+pub const PARAM_TYPES: [&str; 6] = ["string", "integer", "decimal", "boolean", "slug", "path"];
+
+/// Compiled `regex(<pattern>)` parameter types, by pattern
+static PARAM_REGEXES: once_cell::sync::Lazy<
+    antidote::RwLock<std::collections::HashMap<String, regex::Regex>>,
+> = once_cell::sync::Lazy::new(|| antidote::RwLock::new(Default::default()));
+
+fn param_regex(pattern: &str) -> Result<regex::Regex, regex::Error> {
+    if let Some(regex) = PARAM_REGEXES.read().get(pattern) {
+        return Ok(regex.clone());
+    }
+    let regex = regex::Regex::new(format!("^(?:{})$", pattern).as_str())?;
+    PARAM_REGEXES
+        .write()
+        .insert(pattern.to_string(), regex.clone());
+    Ok(regex)
+}
+
+/// Error message if `param_type` is not a type a dynamic url parameter can have
+fn check_param_type(param_type: &str) -> Result<(), String> {
+    if PARAM_TYPES.contains(&param_type) {
+        return Ok(());
+    }
+    if let Some(values) = param_type
+        .strip_prefix("enum(")
+        .and_then(|v| v.strip_suffix(')'))
+    {
+        if values.split('|').any(|v| v.trim().is_empty()) {
+            return Err(format!("empty value in: {}", param_type));
+        }
+        return Ok(());
+    }
+    if let Some(pattern) = param_type
+        .strip_prefix("regex(")
+        .and_then(|v| v.strip_suffix(')'))
+    {
+        return param_regex(pattern)
+            .map(|_| ())
+            .map_err(|e| format!("invalid pattern in: {}, {}", param_type, e));
+    }
+    Err(format!(
+        "unknown type: {}, expected one of {}, enum(..) or regex(..)",
+        param_type,
+        PARAM_TYPES.join(", ")
+    ))
+}
+
+fn get_value_type(value: &str, r#type: &str) -> fastn_core::Result<ftd::Value> {
+    let string = || ftd::Value::String {
+        text: value.to_string(),
+        source: ftd::TextSource::Default,
+    };
+    let mismatch = || {
+        Err(fastn_core::Error::generic(format!(
+            "`{}` is not a valid `{}`",
+            value, r#type
+        )))
+    };
+    match r#type {
+        "string" | "path" => Ok(string()),
+        "integer" => {
+            let value = value.parse::<i64>()?;
+            Ok(ftd::Value::Integer { value })
+        }
+        "decimal" => {
+            let value = value.parse::<f64>()?;
+            Ok(ftd::Value::Decimal { value })
+        }
+        "boolean" => {
+            let value = value.parse::<bool>()?;
+            Ok(ftd::Value::Boolean { value })
+        }
+        "slug" => {
+            if !value.is_empty()
+                && value
+                    .chars()
+                    .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-')
+            {
+                Ok(string())
+            } else {
+                mismatch()
             }
-            "boolean" => {
-                let value = value.parse::<bool>()?;
-                Ok(ftd::Value::Boolean { value })
+        }
+        t => {
+            if let Some(values) = t.strip_prefix("enum(").and_then(|v| v.strip_suffix(')')) {
+                if values.split('|').any(|v| v.trim().eq(value)) {
+                    return Ok(string());
+                }
+                return mismatch();
             }
-            _ => unimplemented!(),
+            if let Some(pattern) = t.strip_prefix("regex(").and_then(|v| v.strip_suffix(')')) {
+                if param_regex(pattern)
+                    .map(|r| r.is_match(value))
+                    .unwrap_or(false)
+                {
+                    return Ok(string());
+                }
+                return mismatch();
+            }
+            Err(fastn_core::Error::generic(format!(
+                "unknown dynamic url parameter type: {}",
+                t
+            )))
         }
     }
 }
 
+/// `<type:name>`, or `<type:name?>` for an optional parameter, returns `(type, name, optional)`
+fn parse_param(part: &str) -> Result<(String, String, bool), fastn_core::sitemap::ParseError> {
+    let error = |message: String| fastn_core::sitemap::ParseError::InvalidDynamicUrls {
+        message: format!("dynamic-urls format is wrong for: {}, {}", part, message),
+    };
+    let inner = part[1..part.len() - 1].trim();
+    // the name can not have `:`, the type can, in a regex
+    let (type_part, param_name_part) = inner
+        .rsplit_once(':')
+        .map(|(t, n)| (t.trim(), n.trim()))
+        .unwrap_or_default();
+    let (param_name_part, optional) = match param_name_part.strip_suffix('?') {
+        Some(name) => (name.trim(), true),
+        None => (param_name_part, false),
+    };
+    if type_part.is_empty() || param_name_part.is_empty() {
+        return Err(error("expected <type:name>".to_string()));
+    }
+    check_param_type(type_part).map_err(error)?;
+    Ok((type_part.to_string(), param_name_part.to_string(), optional))
+}
+
 /// Please check test case: `parse_path_params_test_0`
 /// This method is for parsing the dynamic params from fastn.dynamic-urls
+///
+/// `/b/<string:username>/<slug:post?>/?<integer:page?>&<enum(asc|desc):order>`
+///
+/// Optional segments, `<type:name?>`, and the catch-all `<path:name>`, which has to be the last
+/// segment, can only come after all the other segments. Query parameters come after `?`,
+/// separated by `&`. Patterns of `regex(..)` types can not have `/`, or `&` in query parameters.
 pub fn parse_named_params(
     url: &str,
 ) -> Result<Vec<fastn_core::sitemap::PathParams>, fastn_core::sitemap::ParseError> {
     let mut output = vec![];
+    // `?` also marks optional parameters, the query starts at the first one outside `<..>`
+    let mut depth = 0;
+    let query_start = url.char_indices().find_map(|(i, c)| {
+        match c {
+            '<' => depth += 1,
+            '>' => depth -= 1,
+            '?' if depth == 0 => return Some(i),
+            _ => {}
+        }
+        None
+    });
+    let (url, query) = match query_start {
+        Some(i) => (&url[..i], &url[i + 1..]),
+        None => (url, ""),
+    };
     let url = url.trim().trim_matches('/');
+    let is_param = |part: &str| part.contains(':') && part.starts_with('<') && part.ends_with('>');
 
     // b/<string:username>/<integer:age>/foo
     let parts: Vec<&str> = url.split('/').collect();
     // parts: [b, <string:username>, <integer:age>, foo]
     let mut index = 0;
+    // the segment after which only optional segments are allowed
+    let mut only_optional_after: Option<&str> = None;
+    let mut catch_all: Option<&str> = None;
     for part in parts.into_iter().map(|x| x.trim()) {
         if !part.is_empty() {
-            if part.contains(':') && part.starts_with('<') && part.ends_with('>') {
+            if let Some(catch_all) = catch_all {
+                return Err(fastn_core::sitemap::ParseError::InvalidDynamicUrls {
+                    message: format!("`{}` has to be the last segment of: {}", catch_all, url),
+                });
+            }
+            if is_param(part) {
                 // <string:username>
-                if let Some(colon_index) = part.find(':') {
-                    let type_part = part[1..colon_index].trim();
-                    let param_name_part = part[colon_index + 1..part.len() - 1].trim();
-                    if type_part.is_empty() || param_name_part.is_empty() {
-                        return Err(fastn_core::sitemap::ParseError::InvalidDynamicUrls {
-                            message: format!("dynamic-urls format is wrong for: {}", part),
-                        });
-                    }
-                    output.push(fastn_core::sitemap::PathParams::named(
-                        index,
-                        param_name_part.to_string(),
-                        type_part.to_string(), // TODO: check the type which are supported in the sitemap
-                    ));
-                    index += 1;
+                let (type_part, param_name_part, optional) = parse_param(part)?;
+                if type_part.eq("path") {
+                    catch_all = Some(part);
+                } else if optional {
+                    only_optional_after = only_optional_after.or(Some(part));
+                } else if let Some(optional) = only_optional_after {
+                    return Err(fastn_core::sitemap::ParseError::InvalidDynamicUrls {
+                        message: format!(
+                            "optional segment `{}` can only be followed by optional segments in: {}",
+                            optional, url
+                        ),
+                    });
                 }
+                output.push(fastn_core::sitemap::PathParams::NamedParm {
+                    index,
+                    name: param_name_part,
+                    param_type: type_part,
+                    optional,
+                });
+                index += 1;
             } else {
+                if let Some(optional) = only_optional_after {
+                    return Err(fastn_core::sitemap::ParseError::InvalidDynamicUrls {
+                        message: format!(
+                            "optional segment `{}` can only be followed by optional segments in: {}",
+                            optional, url
+                        ),
+                    });
+                }
                 // b
                 output.push(fastn_core::sitemap::PathParams::value(
                     index,
@@ -120,6 +327,29 @@ pub fn parse_named_params(
             }
         }
     }
+
+    // <integer:page?>&<enum(asc|desc):order>
+    for part in query.split('&').map(|x| x.trim()) {
+        if part.is_empty() {
+            continue;
+        }
+        if !is_param(part) {
+            return Err(fastn_core::sitemap::ParseError::InvalidDynamicUrls {
+                message: format!("query parameter should be <type:name>, found: {}", part),
+            });
+        }
+        let (type_part, param_name_part, optional) = parse_param(part)?;
+        if type_part.eq("path") {
+            return Err(fastn_core::sitemap::ParseError::InvalidDynamicUrls {
+                message: format!("query parameter can not be of type path: {}", part),
+            });
+        }
+        output.push(fastn_core::sitemap::PathParams::query(
+            param_name_part,
+            type_part,
+            optional,
+        ));
+    }
     Ok(output)
 }
 
@@ -326,4 +556,116 @@ mod tests {
             ]
         );
     }
+
+    // cargo test --package fastn --lib sitemap::utils::tests::parse_path_params_test_03
+    #[test]
+    fn parse_path_params_test_03() {
+        let output = super::parse_named_params(
+            "/b/<enum(new|old):kind>/<path:rest?>/?<integer:page?>&<regex([a-z]+):q>",
+        );
+        let test_output = vec![
+            fastn_core::sitemap::PathParams::value(0, "b".to_string()),
+            fastn_core::sitemap::PathParams::named(
+                1,
+                "kind".to_string(),
+                "enum(new|old)".to_string(),
+            ),
+            fastn_core::sitemap::PathParams::optional(2, "rest".to_string(), "path".to_string()),
+            fastn_core::sitemap::PathParams::query("page".to_string(), "integer".to_string(), true),
+            fastn_core::sitemap::PathParams::query(
+                "q".to_string(),
+                "regex([a-z]+)".to_string(),
+                false,
+            ),
+        ];
+        assert_eq!(Ok(test_output), output)
+    }
+
+    // cargo test --package fastn --lib sitemap::utils::tests::parse_path_params_test_04
+    #[test]
+    fn parse_path_params_test_04() {
+        // unknown type
+        assert!(super::parse_named_params("/b/<date:day>/").is_err());
+        // optional segment followed by a required one
+        assert!(super::parse_named_params("/b/<string:name?>/foo/").is_err());
+        // catch-all not at the end
+        assert!(super::parse_named_params("/b/<path:rest>/<string:name>/").is_err());
+        // invalid regex
+        assert!(super::parse_named_params("/b/<regex([a-z):name>/").is_err());
+    }
+
+    // cargo test --package fastn --lib sitemap::utils::tests::url_match_6 -- --nocapture
+    #[test]
+    fn url_match_6() {
+        // sitemap_url: /blog/<slug:post>/<path:rest?>/?<integer:page?>
+        let params =
+            super::parse_named_params("/blog/<slug:post>/<path:rest?>/?<integer:page?>").unwrap();
+
+        let output = super::url_match("/blog/hello-world/", &params).unwrap();
+        assert!(output.0);
+        assert_eq!(
+            output.1,
+            vec![(
+                "post".to_string(),
+                ftd::Value::String {
+                    text: "hello-world".to_string(),
+                    source: TextSource::Default
+                }
+            )]
+        );
+
+        let output = super::url_match("/blog/hello-world/a/b/?page=2", &params).unwrap();
+        assert!(output.0);
+        assert_eq!(
+            output.1,
+            vec![
+                (
+                    "post".to_string(),
+                    ftd::Value::String {
+                        text: "hello-world".to_string(),
+                        source: TextSource::Default
+                    }
+                ),
+                (
+                    "rest".to_string(),
+                    ftd::Value::String {
+                        text: "a/b".to_string(),
+                        source: TextSource::Default
+                    }
+                ),
+                ("page".to_string(), ftd::Value::Integer { value: 2 })
+            ]
+        );
+
+        // not a slug
+        assert!(!super::url_match("/blog/Hello_World/", &params).unwrap().0);
+        // page is not an integer
+        assert!(
+            !super::url_match("/blog/hello/?page=two", &params)
+                .unwrap()
+                .0
+        );
+    }
+
+    // cargo test --package fastn --lib sitemap::utils::tests::url_match_7
+    #[test]
+    fn url_match_7() {
+        // sitemap_url: /<enum(en|hi):lang>/<regex([0-9]{4}):year>/<decimal:v>/<boolean:draft>/
+        let params = super::parse_named_params(
+            "/<enum(en|hi):lang>/<regex([0-9]{4}):year>/<decimal:v>/<boolean:draft>/",
+        )
+        .unwrap();
+        assert!(super::url_match("/hi/2023/1.5/true/", &params).unwrap().0);
+        assert!(!super::url_match("/fr/2023/1.5/true/", &params).unwrap().0);
+        assert!(!super::url_match("/hi/23/1.5/true/", &params).unwrap().0);
+        assert!(!super::url_match("/hi/2023/1.5/yes/", &params).unwrap().0);
+        // required query parameter
+        let params = super::parse_named_params("/search/?<string:q>").unwrap();
+        assert!(
+            super::url_match("/search/?q=fastn%20docs", &params)
+                .unwrap()
+                .0
+        );
+        assert!(!super::url_match("/search/", &params).unwrap().0);
+    }
 }
@@ -0,0 +1,468 @@
+//! Responses for static assets served by `fastn serve`.
+//!
+//! Text assets are sent brotli or gzip compressed as per the `Accept-Encoding` of the request,
+//! preferring a precompressed `.br` / `.gz` sibling on disk (`fastn build --compress` writes
+//! them). Media files honour single `Range` requests. Assets with a content hash in their name,
+//! like the default css and js, are cached forever, everything else is revalidated using `ETag`.
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub(crate) enum Encoding {
+    Brotli,
+    Gzip,
+}
+
+impl Encoding {
+    const ALL: [Encoding; 2] = [Encoding::Brotli, Encoding::Gzip];
+
+    fn as_str(&self) -> &'static str {
+        match self {
+            Encoding::Brotli => "br",
+            Encoding::Gzip => "gzip",
+        }
+    }
+
+    /// Extension of the precompressed sibling of a file
+    fn extension(&self) -> &'static str {
+        match self {
+            Encoding::Brotli => "br",
+            Encoding::Gzip => "gz",
+        }
+    }
+
+    /// `best` trades time for size, and is used when compressing ahead of time
+    fn compress(&self, content: &[u8], best: bool) -> std::io::Result<Vec<u8>> {
+        use std::io::Write;
+
+        match self {
+            Encoding::Brotli => {
+                let mut writer =
+                    brotli::CompressorWriter::new(Vec::new(), 4096, if best { 11 } else { 5 }, 22);
+                writer.write_all(content)?;
+                writer.flush()?;
+                Ok(writer.into_inner())
+            }
+            Encoding::Gzip => {
+                let mut encoder = flate2::write::GzEncoder::new(
+                    Vec::new(),
+                    if best {
+                        flate2::Compression::best()
+                    } else {
+                        flate2::Compression::default()
+                    },
+                );
+                encoder.write_all(content)?;
+                encoder.finish()
+            }
+        }
+    }
+}
+
+/// The headers of the request which decide how an asset is sent
+#[derive(Debug, Default)]
+pub(crate) struct AssetRequest {
+    accept_encoding: Option<String>,
+    range: Option<String>,
+    if_none_match: Option<String>,
+}
+
+impl AssetRequest {
+    pub(crate) fn from_request(req: &fastn_core::http::Request) -> AssetRequest {
+        let header = |name: &str| {
+            req.headers()
+                .get(name)
+                .and_then(|v| v.to_str().ok())
+                .map(ToString::to_string)
+        };
+        AssetRequest {
+            accept_encoding: header("accept-encoding"),
+            range: header("range"),
+            if_none_match: header("if-none-match"),
+        }
+    }
+
+    pub(crate) fn from_actix(req: &actix_web::HttpRequest) -> AssetRequest {
+        let header = |name: &str| {
+            req.headers()
+                .get(name)
+                .and_then(|v| v.to_str().ok())
+                .map(ToString::to_string)
+        };
+        AssetRequest {
+            accept_encoding: header("accept-encoding"),
+            range: header("range"),
+            if_none_match: header("if-none-match"),
+        }
+    }
+
+    /// Encodings the client accepts, most preferred first
+    fn encodings(&self) -> Vec<Encoding> {
+        let accept_encoding = match self.accept_encoding.as_ref() {
+            Some(v) => v,
+            None => return vec![],
+        };
+        let qualities: Vec<(String, f32)> = accept_encoding
+            .split(',')
+            .map(|part| {
+                let mut params = part.split(';');
+                let name = params.next().unwrap_or_default().trim().to_lowercase();
+                let q = params
+                    .filter_map(|p| p.trim().strip_prefix("q="))
+                    .find_map(|q| q.trim().parse::<f32>().ok())
+                    .unwrap_or(1.0);
+                (name, q)
+            })
+            .collect();
+        let quality = |name: &str| qualities.iter().find(|(n, _)| n == name).map(|(_, q)| *q);
+        // an encoding named by the client takes precedence over `*`
+        let mut accepted: Vec<(Encoding, f32)> = Encoding::ALL
+            .into_iter()
+            .filter_map(|e| quality(e.as_str()).or_else(|| quality("*")).map(|q| (e, q)))
+            .filter(|(_, q)| *q > 0.0)
+            .collect();
+        // stable sort keeps brotli ahead of gzip when both are equally preferred
+        accepted.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+        accepted.into_iter().map(|(e, _)| e).collect()
+    }
+
+    /// The `(start, end)` inclusive byte range asked for, `Err(())` if it can not be satisfied.
+    /// Multiple ranges are not supported, and such requests get the full content.
+    fn range(&self, len: usize) -> Option<Result<(usize, usize), ()>> {
+        let spec = self.range.as_ref()?.trim().strip_prefix("bytes=")?;
+        if spec.contains(',') {
+            return None;
+        }
+        let (start, end) = spec.split_once('-')?;
+        let (start, end) = match (start.trim(), end.trim()) {
+            ("", "") => return None,
+            // suffix range, the last `n` bytes
+            ("", n) => {
+                let n: usize = n.parse().ok()?;
+                if n == 0 {
+                    return Some(Err(()));
+                }
+                (len.saturating_sub(n), len.saturating_sub(1))
+            }
+            (s, "") => (s.parse().ok()?, len.saturating_sub(1)),
+            (s, e) => {
+                let (s, e): (usize, usize) = (s.parse().ok()?, e.parse().ok()?);
+                if e < s {
+                    return None;
+                }
+                (s, e.min(len.saturating_sub(1)))
+            }
+        };
+        if len == 0 || start >= len {
+            return Some(Err(()));
+        }
+        Some(Ok((start, end)))
+    }
+}
+
+/// `true` if compressing the asset is worth it
+fn is_compressible(mime: &mime_guess::Mime) -> bool {
+    if mime.type_() == mime_guess::mime::TEXT {
+        return true;
+    }
+    if mime.type_() == mime_guess::mime::IMAGE {
+        return mime.subtype() == mime_guess::mime::SVG;
+    }
+    mime.type_() == mime_guess::mime::APPLICATION
+        && matches!(
+            mime.subtype().as_str(),
+            "javascript" | "json" | "xml" | "wasm" | "xhtml+xml" | "rss+xml" | "atom+xml"
+        )
+}
+
+fn is_media(mime: &mime_guess::Mime) -> bool {
+    [
+        mime_guess::mime::IMAGE,
+        mime_guess::mime::AUDIO,
+        mime_guess::mime::VIDEO,
+    ]
+    .contains(&mime.type_())
+}
+
+fn etag(content: &[u8]) -> String {
+    use sha2::Digest;
+
+    format!("\"{:x}\"", sha2::Sha256::digest(content))
+}
+
+/// Compressed content of immutable assets, keyed by their name, which has the hash of the
+/// content in it
+#[allow(clippy::type_complexity)]
+static IMMUTABLE: once_cell::sync::Lazy<
+    antidote::RwLock<std::collections::HashMap<(String, Encoding), Vec<u8>>>,
+> = once_cell::sync::Lazy::new(|| antidote::RwLock::new(Default::default()));
+
+fn compressed(
+    name: &str,
+    content: &[u8],
+    on_disk: Option<&camino::Utf8Path>,
+    immutable: bool,
+    encoding: Encoding,
+) -> Option<Vec<u8>> {
+    if let Some(path) = on_disk {
+        let sibling = camino::Utf8PathBuf::from(format!("{}.{}", path, encoding.extension()));
+        let modified = |path: &camino::Utf8Path| path.metadata().and_then(|m| m.modified()).ok();
+        // a sibling older than the file is stale
+        if modified(sibling.as_path()) >= modified(path) {
+            if let Ok(content) = std::fs::read(sibling) {
+                return Some(content);
+            }
+        }
+    }
+    if immutable {
+        if let Some(content) = IMMUTABLE.read().get(&(name.to_string(), encoding)) {
+            return Some(content.clone());
+        }
+    }
+    let content = match encoding.compress(content, immutable) {
+        Ok(content) => content,
+        Err(e) => {
+            tracing::error!(msg = "compression-error", path = name, error = %e);
+            return None;
+        }
+    };
+    if immutable {
+        IMMUTABLE
+            .write()
+            .insert((name.to_string(), encoding), content.clone());
+    }
+    Some(content)
+}
+
+/// Response for the asset `name` with `content`. `on_disk` is the path the content was read from,
+/// used to look for precompressed siblings, and `immutable` is set for assets with a content hash
+/// in their name. `content_hash`, the hex encoded sha256 of the content, saves hashing it again
+/// when it is already known.
+pub(crate) fn response(
+    req: &AssetRequest,
+    name: &str,
+    content: Vec<u8>,
+    on_disk: Option<&camino::Utf8Path>,
+    immutable: bool,
+    content_hash: Option<String>,
+) -> fastn_core::http::Response {
+    use actix_web::http::header;
+
+    let mime = mime_guess::from_path(name).first_or_octet_stream();
+    let mut builder = actix_web::HttpResponse::Ok();
+    builder.insert_header((
+        header::CACHE_CONTROL,
+        if immutable {
+            "public, max-age=31536000, immutable"
+        } else {
+            "no-cache"
+        },
+    ));
+
+    if !immutable {
+        let etag = content_hash
+            .map(|hash| format!("\"{}\"", hash))
+            .unwrap_or_else(|| etag(content.as_slice()));
+        let fresh = req
+            .if_none_match
+            .as_ref()
+            .map(|v| v.split(',').any(|e| e.trim() == etag || e.trim() == "*"))
+            .unwrap_or(false);
+        builder.insert_header((header::ETAG, etag));
+        if fresh {
+            builder.status(actix_web::http::StatusCode::NOT_MODIFIED);
+            return builder.finish();
+        }
+    }
+
+    if is_media(&mime) {
+        builder.insert_header((header::ACCEPT_RANGES, "bytes"));
+        match req.range(content.len()) {
+            Some(Ok((start, end))) => {
+                builder
+                    .status(actix_web::http::StatusCode::PARTIAL_CONTENT)
+                    .insert_header((
+                        header::CONTENT_RANGE,
+                        format!("bytes {}-{}/{}", start, end, content.len()),
+                    ));
+                return builder
+                    .content_type(mime)
+                    .body(content[start..=end].to_vec());
+            }
+            Some(Err(())) => {
+                builder
+                    .status(actix_web::http::StatusCode::RANGE_NOT_SATISFIABLE)
+                    .insert_header((header::CONTENT_RANGE, format!("bytes */{}", content.len())));
+                return builder.finish();
+            }
+            None => {}
+        }
+    }
+
+    if is_compressible(&mime) {
+        builder.insert_header((header::VARY, "Accept-Encoding"));
+        for encoding in req.encodings() {
+            if let Some(compressed) =
+                compressed(name, content.as_slice(), on_disk, immutable, encoding)
+            {
+                builder.insert_header((header::CONTENT_ENCODING, encoding.as_str()));
+                return builder.content_type(mime).body(compressed);
+            }
+        }
+    }
+
+    builder.content_type(mime).body(content)
+}
+
+/// Writes `.br` and `.gz` siblings of the compressible files in `dir`, used by
+/// `fastn build --compress`
+pub(crate) async fn write_precompressed(dir: &camino::Utf8Path) -> fastn_core::Result<()> {
+    for entry in ignore::WalkBuilder::new(dir)
+        .hidden(false)
+        .git_ignore(false)
+        .build()
+        .flatten()
+    {
+        let path = match camino::Utf8PathBuf::from_path_buf(entry.into_path()) {
+            Ok(path) if path.is_file() => path,
+            _ => continue,
+        };
+        if Encoding::ALL
+            .iter()
+            .any(|e| path.extension() == Some(e.extension()))
+            || !is_compressible(&mime_guess::from_path(path.as_str()).first_or_octet_stream())
+        {
+            continue;
+        }
+        let content = tokio::fs::read(path.as_path()).await?;
+        for encoding in Encoding::ALL {
+            fastn_core::utils::update(
+                camino::Utf8PathBuf::from(format!("{}.{}", path, encoding.extension())),
+                encoding.compress(content.as_slice(), true)?.as_slice(),
+            )
+            .await?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Encoding;
+
+    fn range(header: &str, len: usize) -> Option<Result<(usize, usize), ()>> {
+        super::AssetRequest {
+            range: Some(header.to_string()),
+            ..Default::default()
+        }
+        .range(len)
+    }
+
+    fn encodings(header: &str) -> Vec<Encoding> {
+        super::AssetRequest {
+            accept_encoding: Some(header.to_string()),
+            ..Default::default()
+        }
+        .encodings()
+    }
+
+    #[test]
+    fn request_range() {
+        assert_eq!(range("bytes=0-9", 100), Some(Ok((0, 9))));
+        assert_eq!(range("bytes=90-", 100), Some(Ok((90, 99))));
+        assert_eq!(range("bytes=-10", 100), Some(Ok((90, 99))));
+        // suffix longer than the content is the whole content
+        assert_eq!(range("bytes=-500", 100), Some(Ok((0, 99))));
+        // end past the content is clipped
+        assert_eq!(range("bytes=50-500", 100), Some(Ok((50, 99))));
+
+        assert_eq!(range("bytes=100-", 100), Some(Err(())));
+        assert_eq!(range("bytes=200-300", 100), Some(Err(())));
+        assert_eq!(range("bytes=-0", 100), Some(Err(())));
+        assert_eq!(range("bytes=0-", 0), Some(Err(())));
+
+        // ignored, the full content is sent
+        assert_eq!(range("bytes=0-1,5-6", 100), None);
+        assert_eq!(range("bytes=9-0", 100), None);
+        assert_eq!(range("bytes=-", 100), None);
+        assert_eq!(range("bytes=a-b", 100), None);
+        assert_eq!(range("items=0-9", 100), None);
+        assert_eq!(super::AssetRequest::default().range(100), None);
+    }
+
+    #[test]
+    fn request_encodings() {
+        assert_eq!(super::AssetRequest::default().encodings(), vec![]);
+        assert_eq!(encodings("identity"), vec![]);
+        assert_eq!(encodings("gzip"), vec![Encoding::Gzip]);
+        assert_eq!(
+            encodings("gzip, deflate, br"),
+            vec![Encoding::Brotli, Encoding::Gzip]
+        );
+        assert_eq!(
+            encodings("br;q=0.5, gzip"),
+            vec![Encoding::Gzip, Encoding::Brotli]
+        );
+        assert_eq!(encodings("br;q=0, gzip"), vec![Encoding::Gzip]);
+        assert_eq!(encodings("*"), vec![Encoding::Brotli, Encoding::Gzip]);
+        assert_eq!(encodings("*, br;q=0"), vec![Encoding::Gzip]);
+        assert_eq!(encodings("GZIP;q=0.8"), vec![Encoding::Gzip]);
+    }
+
+    #[test]
+    fn response() {
+        use actix_web::http::{header, StatusCode};
+
+        let content = (0..100).collect::<Vec<u8>>();
+        let req = super::AssetRequest {
+            range: Some("bytes=10-19".to_string()),
+            ..Default::default()
+        };
+        let response = super::response(&req, "a.png", content.clone(), None, false, None);
+        assert_eq!(response.status(), StatusCode::PARTIAL_CONTENT);
+        assert_eq!(
+            response.headers().get(header::CONTENT_RANGE).unwrap(),
+            "bytes 10-19/100"
+        );
+
+        // ranges are only served for media
+        let response = super::response(&req, "a.txt", content.clone(), None, false, None);
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let req = super::AssetRequest {
+            range: Some("bytes=100-".to_string()),
+            ..Default::default()
+        };
+        let response = super::response(&req, "a.png", content.clone(), None, false, None);
+        assert_eq!(response.status(), StatusCode::RANGE_NOT_SATISFIABLE);
+        assert_eq!(
+            response.headers().get(header::CONTENT_RANGE).unwrap(),
+            "bytes */100"
+        );
+
+        let req = super::AssetRequest {
+            accept_encoding: Some("gzip".to_string()),
+            if_none_match: Some("\"abc\"".to_string()),
+            ..Default::default()
+        };
+        let response = super::response(
+            &req,
+            "a.css",
+            content.clone(),
+            None,
+            false,
+            Some("abc".to_string()),
+        );
+        assert_eq!(response.status(), StatusCode::NOT_MODIFIED);
+
+        let response = super::response(&req, "a-0123.css", content, None, true, None);
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            response.headers().get(header::CONTENT_ENCODING).unwrap(),
+            "gzip"
+        );
+        assert_eq!(
+            response.headers().get(header::CACHE_CONTROL).unwrap(),
+            "public, max-age=31536000, immutable"
+        );
+        assert!(response.headers().get(header::ETAG).is_none());
+    }
+}
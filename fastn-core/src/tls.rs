@@ -0,0 +1,134 @@
+/// How `fastn serve` serves over https. HTTP/2 is negotiated with clients which support it.
+#[derive(Debug, Clone)]
+pub enum Tls {
+    /// PEM encoded certificate chain and private key, `--tls-cert` and `--tls-key`
+    Files {
+        cert: camino::Utf8PathBuf,
+        key: camino::Utf8PathBuf,
+    },
+    /// A throwaway certificate for `localhost` and the bind address, for local development only,
+    /// `--tls-self-signed`
+    SelfSigned,
+}
+
+impl Tls {
+    pub(crate) fn acceptor(
+        &self,
+        bind_address: &str,
+    ) -> fastn_core::Result<openssl::ssl::SslAcceptorBuilder> {
+        let mut acceptor =
+            openssl::ssl::SslAcceptor::mozilla_intermediate_v5(openssl::ssl::SslMethod::tls())
+                .map_err(tls_error)?;
+        match self {
+            Tls::Files { cert, key } => {
+                acceptor
+                    .set_private_key_file(key, openssl::ssl::SslFiletype::PEM)
+                    .map_err(|e| {
+                        fastn_core::Error::generic(format!("failed to read {}: {}", key, e))
+                    })?;
+                acceptor.set_certificate_chain_file(cert).map_err(|e| {
+                    fastn_core::Error::generic(format!("failed to read {}: {}", cert, e))
+                })?;
+            }
+            Tls::SelfSigned => {
+                let (key, cert) = self_signed(bind_address).map_err(tls_error)?;
+                acceptor.set_private_key(&key).map_err(tls_error)?;
+                acceptor.set_certificate(&cert).map_err(tls_error)?;
+            }
+        }
+        acceptor.check_private_key().map_err(tls_error)?;
+        Ok(acceptor)
+    }
+}
+
+fn tls_error(e: openssl::error::ErrorStack) -> fastn_core::Error {
+    fastn_core::Error::generic(format!("tls-error: {}", e))
+}
+
+fn self_signed(
+    bind_address: &str,
+) -> Result<
+    (
+        openssl::pkey::PKey<openssl::pkey::Private>,
+        openssl::x509::X509,
+    ),
+    openssl::error::ErrorStack,
+> {
+    let key = openssl::pkey::PKey::from_rsa(openssl::rsa::Rsa::generate(2048)?)?;
+
+    let mut name = openssl::x509::X509NameBuilder::new()?;
+    name.append_entry_by_text("CN", "localhost")?;
+    let name = name.build();
+
+    let mut serial = openssl::bn::BigNum::new()?;
+    serial.rand(128, openssl::bn::MsbOption::MAYBE_ZERO, false)?;
+
+    let mut builder = openssl::x509::X509::builder()?;
+    builder.set_version(2)?;
+    builder.set_serial_number(serial.to_asn1_integer()?.as_ref())?;
+    builder.set_subject_name(&name)?;
+    builder.set_issuer_name(&name)?;
+    builder.set_pubkey(&key)?;
+    builder.set_not_before(openssl::asn1::Asn1Time::days_from_now(0)?.as_ref())?;
+    builder.set_not_after(openssl::asn1::Asn1Time::days_from_now(30)?.as_ref())?;
+
+    let mut san = openssl::x509::extension::SubjectAlternativeName::new();
+    san.dns("localhost").ip("127.0.0.1").ip("::1");
+    if bind_address.parse::<std::net::IpAddr>().is_ok() {
+        san.ip(bind_address);
+    } else {
+        san.dns(bind_address);
+    }
+    let san = san.build(&builder.x509v3_context(None, None))?;
+    builder.append_extension(san)?;
+    builder.sign(&key, openssl::hash::MessageDigest::sha256())?;
+
+    Ok((key, builder.build()))
+}
+
+#[cfg(test)]
+mod tests {
+    fn alt_names(cert: &openssl::x509::X509) -> Vec<String> {
+        cert.subject_alt_names()
+            .unwrap()
+            .iter()
+            .map(|name| match (name.dnsname(), name.ipaddress()) {
+                (Some(dns), _) => dns.to_string(),
+                (_, Some(ip)) => match <[u8; 4]>::try_from(ip) {
+                    Ok(v4) => std::net::IpAddr::from(v4).to_string(),
+                    Err(_) => std::net::IpAddr::from(<[u8; 16]>::try_from(ip).unwrap()).to_string(),
+                },
+                _ => unreachable!(),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn self_signed() {
+        let (key, cert) = super::self_signed("0.0.0.0").unwrap();
+        assert!(cert.verify(&key).unwrap());
+        assert_eq!(
+            alt_names(&cert),
+            vec!["localhost", "127.0.0.1", "::1", "0.0.0.0"]
+        );
+
+        let (_, cert) = super::self_signed("fastn.local").unwrap();
+        assert_eq!(alt_names(&cert).last().unwrap(), "fastn.local");
+    }
+
+    #[test]
+    fn acceptor() {
+        assert!(super::Tls::SelfSigned.acceptor("127.0.0.1").is_ok());
+
+        let missing = super::Tls::Files {
+            cert: "missing/cert.pem".into(),
+            key: "missing/key.pem".into(),
+        };
+        match missing.acceptor("127.0.0.1") {
+            Err(fastn_core::Error::GenericError(e)) => {
+                assert!(e.starts_with("failed to read missing/key.pem"), "{}", e)
+            }
+            _ => panic!("missing files are an error"),
+        }
+    }
+}
@@ -0,0 +1,641 @@
+//! Translatable strings of documents, for `fastn translate`.
+//!
+//! `fastn translate extract` writes the captions, bodies and string headers of the sections of
+//! every document of the original package to an XLIFF or PO file. Translators fill in the
+//! targets, and `fastn translate merge` writes the translated document: the original document
+//! with each translated string replaced in place, so the structure of the document is kept.
+//!
+//! Every merged string is kept in the translation memory, `.translation-memory.json`, and
+//! `fastn translate extract` fills in the target of strings already translated, so once the
+//! original changes only the changed strings need translating.
+
+pub(crate) const TRANSLATION_MEMORY: &str = ".translation-memory.json";
+
+/// Sections whose strings are never translated
+const SKIP_SECTIONS: &[&str] = &["import", "end", "record", "or-type"];
+
+/// Headers holding ids, urls, styles and other values that are not shown as text
+const SKIP_HEADERS: &[&str] = &[
+    "id",
+    "link",
+    "src",
+    "url",
+    "href",
+    "role",
+    "region",
+    "classes",
+    "align",
+    "align-self",
+    "align-content",
+    "text-align",
+    "color",
+    "background",
+    "border-color",
+    "light",
+    "dark",
+    "width",
+    "height",
+    "min-width",
+    "max-width",
+    "min-height",
+    "max-height",
+    "padding",
+    "margin",
+    "spacing",
+    "anchor",
+    "display",
+    "overflow",
+    "cursor",
+    "resize",
+    "white-space",
+    "text-transform",
+    "loading",
+    "lang",
+    "type",
+    "kind",
+    "if",
+];
+
+/// Format of the files `fastn translate extract` writes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TranslationFormat {
+    /// XLIFF 1.2, `.xlf`
+    Xliff,
+    /// gettext, `.po`
+    Po,
+}
+
+impl std::str::FromStr for TranslationFormat {
+    type Err = fastn_core::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "xliff" | "xlf" => Ok(TranslationFormat::Xliff),
+            "po" => Ok(TranslationFormat::Po),
+            t => Err(fastn_core::Error::UsageError {
+                message: format!("unknown translation format: {}, expected xliff or po", t),
+            }),
+        }
+    }
+}
+
+impl TranslationFormat {
+    pub(crate) fn extension(&self) -> &'static str {
+        match self {
+            TranslationFormat::Xliff => "xlf",
+            TranslationFormat::Po => "po",
+        }
+    }
+
+    pub(crate) fn from_path(path: &camino::Utf8Path) -> Option<TranslationFormat> {
+        path.extension()?.parse().ok()
+    }
+}
+
+/// A translatable string of a document
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Unit {
+    /// `<line number>.<caption|body|header>`, unique within the document
+    pub id: String,
+    /// `caption`, `body` or the name of the header
+    pub key: String,
+    pub line_number: usize,
+    pub source: String,
+}
+
+/// A unit as written to, or read from, an XLIFF or PO file
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Translation {
+    pub id: String,
+    pub source: String,
+    pub target: Option<String>,
+}
+
+/// The units of a document, in the order they appear in the document
+pub(crate) fn extract_units(content: &str, doc_id: &str) -> fastn_core::Result<Vec<Unit>> {
+    let sections = ftd::p1::parse(content, doc_id)?;
+    let mut units = vec![];
+    for section in sections.iter() {
+        section_units(section, &mut units);
+    }
+    units.sort_by_key(|u| u.line_number);
+    Ok(units)
+}
+
+fn section_units(section: &ftd::p1::Section, units: &mut Vec<Unit>) {
+    if section.is_commented || SKIP_SECTIONS.contains(&section.name.as_str()) {
+        return;
+    }
+    // the caption and body of a processor section are its arguments, not text
+    let processed = section
+        .headers
+        .0
+        .iter()
+        .any(|h| matches!(h, ftd::p1::Header::KV(kv) if kv.key.eq("$processor$")));
+
+    if !processed {
+        if let Some(ftd::p1::Header::KV(kv)) = section.caption.as_ref() {
+            push_unit(units, kv.line_number, "caption", kv.value.as_deref());
+        }
+        if !section.name.ends_with("code") {
+            if let Some(body) = section.body.as_ref() {
+                push_unit(units, body.line_number, "body", Some(body.value.as_str()));
+            }
+        }
+    }
+
+    for header in section.headers.0.iter() {
+        match header {
+            ftd::p1::Header::KV(kv)
+                if !processed
+                    && !kv.key.starts_with('$')
+                    && !SKIP_HEADERS.contains(&kv.key.as_str()) =>
+            {
+                push_unit(units, kv.line_number, kv.key.as_str(), kv.value.as_deref())
+            }
+            ftd::p1::Header::Section(s) => {
+                for section in s.section.iter() {
+                    section_units(section, units);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    for section in section.sub_sections.iter() {
+        section_units(section, units);
+    }
+}
+
+fn push_unit(units: &mut Vec<Unit>, line_number: usize, key: &str, value: Option<&str>) {
+    let value = match value {
+        Some(v) if is_translatable(v) => v.trim(),
+        _ => return,
+    };
+    let id = format!("{}.{}", line_number, key);
+    if units.iter().any(|u| u.id.eq(&id)) {
+        return;
+    }
+    units.push(Unit {
+        id,
+        key: key.to_string(),
+        line_number,
+        source: value.to_string(),
+    });
+}
+
+/// references, numbers, booleans and urls are not translated
+fn is_translatable(value: &str) -> bool {
+    let value = value.trim();
+    !value.is_empty()
+        && !value.starts_with('$')
+        && value.chars().any(char::is_alphabetic)
+        && value.parse::<f64>().is_err()
+        && value.parse::<bool>().is_err()
+        && !value.contains("://")
+        && !value.starts_with('/')
+        && !value.starts_with('#')
+}
+
+/// Replaces the source of every unit having a target with the target, in `content`. Returns the
+/// translated content and the ids of the units whose source was not found.
+pub(crate) fn merge_units(
+    content: &str,
+    units: &[Unit],
+    targets: &std::collections::BTreeMap<String, String>,
+) -> (String, Vec<String>) {
+    let line_starts: Vec<usize> = std::iter::once(0)
+        .chain(content.match_indices('\n').map(|(i, _)| i + 1))
+        .collect();
+    let mut merged = String::new();
+    let mut cursor = 0;
+    let mut missed = vec![];
+
+    for unit in units {
+        let target = match targets.get(&unit.id) {
+            Some(t) if !t.trim().is_empty() => t.trim(),
+            _ => continue,
+        };
+        // the line number of a body is past its first line, but a body comes after the headers
+        // of its section, which are behind the cursor by then
+        let mut start = cursor;
+        if unit.key.ne("body") {
+            let line_start = line_starts
+                .get(unit.line_number.saturating_sub(1))
+                .copied()
+                .unwrap_or(content.len());
+            start = line_start.max(cursor);
+            if start == line_start {
+                // skip the section or header name, `-- ftd.text: text` has the caption after `:`
+                let line_end = content[start..]
+                    .find('\n')
+                    .map(|i| start + i)
+                    .unwrap_or(content.len());
+                if let Some(colon) = content[start..line_end].find(':') {
+                    start += colon + 1;
+                }
+            }
+        }
+        match content[start..].find(unit.source.as_str()) {
+            Some(at) => {
+                let at = start + at;
+                merged.push_str(&content[cursor..at]);
+                if unit.key.eq("body") {
+                    merged.push_str(target);
+                } else {
+                    // captions and headers are single line
+                    merged.push_str(target.replace('\n', " ").as_str());
+                }
+                cursor = at + unit.source.len();
+            }
+            None => missed.push(unit.id.to_string()),
+        }
+    }
+    merged.push_str(&content[cursor..]);
+    (merged, missed)
+}
+
+/// The translations of a document, as written to or read from an XLIFF or PO file
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct TranslationFile {
+    /// id of the original document, `index.ftd`
+    pub document: String,
+    pub source_language: Option<String>,
+    pub target_language: Option<String>,
+    pub translations: Vec<Translation>,
+}
+
+impl TranslationFile {
+    pub(crate) fn render(&self, format: TranslationFormat) -> String {
+        match format {
+            TranslationFormat::Xliff => self.to_xliff(),
+            TranslationFormat::Po => self.to_po(),
+        }
+    }
+
+    pub(crate) fn parse(content: &str, format: TranslationFormat) -> fastn_core::Result<Self> {
+        match format {
+            TranslationFormat::Xliff => TranslationFile::from_xliff(content),
+            TranslationFormat::Po => TranslationFile::from_po(content),
+        }
+    }
+
+    fn to_xliff(&self) -> String {
+        let mut xliff = String::from(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+            <xliff version=\"1.2\" xmlns=\"urn:oasis:names:tc:xliff:document:1.2\">\n",
+        );
+        xliff.push_str(
+            format!(
+                "  <file original=\"{}\" source-language=\"{}\" target-language=\"{}\" \
+                datatype=\"plaintext\">\n    <body>\n",
+                xml_escape(self.document.as_str()),
+                xml_escape(self.source_language.as_deref().unwrap_or_default()),
+                xml_escape(self.target_language.as_deref().unwrap_or_default()),
+            )
+            .as_str(),
+        );
+        for t in self.translations.iter() {
+            xliff.push_str(
+                format!(
+                    "      <trans-unit id=\"{}\">\n        <source>{}</source>\n",
+                    xml_escape(t.id.as_str()),
+                    xml_escape(t.source.as_str())
+                )
+                .as_str(),
+            );
+            match t.target.as_ref() {
+                Some(target) => xliff.push_str(
+                    format!(
+                        "        <target state=\"translated\">{}</target>\n",
+                        xml_escape(target.as_str())
+                    )
+                    .as_str(),
+                ),
+                None => xliff.push_str("        <target state=\"new\"></target>\n"),
+            }
+            xliff.push_str("      </trans-unit>\n");
+        }
+        xliff.push_str("    </body>\n  </file>\n</xliff>\n");
+        xliff
+    }
+
+    fn from_xliff(content: &str) -> fastn_core::Result<Self> {
+        static FILE: once_cell::sync::Lazy<regex::Regex> =
+            once_cell::sync::Lazy::new(|| regex::Regex::new(r#"<file\s[^>]*>"#).unwrap());
+        static UNIT: once_cell::sync::Lazy<regex::Regex> = once_cell::sync::Lazy::new(|| {
+            regex::Regex::new(r#"(?s)<trans-unit\s[^>]*?id="([^"]*)"[^>]*>(.*?)</trans-unit>"#)
+                .unwrap()
+        });
+        static SOURCE: once_cell::sync::Lazy<regex::Regex> = once_cell::sync::Lazy::new(|| {
+            regex::Regex::new(r#"(?s)<source(?:\s[^>]*)?>(.*?)</source>"#).unwrap()
+        });
+        static TARGET: once_cell::sync::Lazy<regex::Regex> = once_cell::sync::Lazy::new(|| {
+            regex::Regex::new(r#"(?s)<target(?:\s[^>]*)?>(.*?)</target>"#).unwrap()
+        });
+
+        let file = FILE
+            .find(content)
+            .ok_or_else(|| fastn_core::Error::UsageError {
+                message: "xliff: `<file>` element not found".to_string(),
+            })?
+            .as_str();
+        let attribute = |name: &str| {
+            regex::Regex::new(format!(r#"\s{}="([^"]*)""#, name).as_str())
+                .ok()?
+                .captures(file)
+                .map(|c| xml_unescape(&c[1]))
+        };
+        let document = attribute("original").ok_or_else(|| fastn_core::Error::UsageError {
+            message: "xliff: `original` attribute of `<file>` not found".to_string(),
+        })?;
+
+        let mut translations = vec![];
+        for unit in UNIT.captures_iter(content) {
+            let source = match SOURCE.captures(&unit[2]) {
+                Some(source) => xml_unescape(&source[1]),
+                None => continue,
+            };
+            let target = TARGET
+                .captures(&unit[2])
+                .map(|t| xml_unescape(&t[1]))
+                .filter(|t| !t.trim().is_empty());
+            translations.push(Translation {
+                id: xml_unescape(&unit[1]),
+                source,
+                target,
+            });
+        }
+
+        Ok(TranslationFile {
+            document,
+            source_language: attribute("source-language").filter(|v| !v.is_empty()),
+            target_language: attribute("target-language").filter(|v| !v.is_empty()),
+            translations,
+        })
+    }
+
+    fn to_po(&self) -> String {
+        let mut po = format!(
+            "# Translation of `{}`, written by `fastn translate extract`\n\
+            msgid \"\"\n\
+            msgstr \"\"\n\
+            \"Content-Type: text/plain; charset=UTF-8\\n\"\n",
+            self.document
+        );
+        if let Some(language) = self.target_language.as_ref() {
+            po.push_str(format!("\"Language: {}\\n\"\n", po_escape(language)).as_str());
+        }
+        po.push_str(format!("\"X-Fastn-Document: {}\\n\"\n", po_escape(&self.document)).as_str());
+        for t in self.translations.iter() {
+            po.push_str(
+                format!(
+                    "\n#: {}:{}\nmsgctxt \"{}\"\nmsgid \"{}\"\nmsgstr \"{}\"\n",
+                    self.document,
+                    t.id.split_once('.').map(|(l, _)| l).unwrap_or_default(),
+                    po_escape(t.id.as_str()),
+                    po_escape(t.source.as_str()),
+                    po_escape(t.target.as_deref().unwrap_or_default()),
+                )
+                .as_str(),
+            );
+        }
+        po
+    }
+
+    fn from_po(content: &str) -> fastn_core::Result<Self> {
+        #[derive(Default)]
+        struct Entry {
+            context: Option<String>,
+            id: Option<String>,
+            string: Option<String>,
+        }
+
+        let mut entries = vec![];
+        let mut current = Entry::default();
+        // 0: msgctxt, 1: msgid, 2: msgstr
+        let mut field = None;
+        for line in content.lines().map(str::trim) {
+            let (name, rest) = if let Some(rest) = line.strip_prefix("msgctxt ") {
+                (Some(0), rest)
+            } else if let Some(rest) = line.strip_prefix("msgid ") {
+                (Some(1), rest)
+            } else if let Some(rest) = line.strip_prefix("msgstr ") {
+                (Some(2), rest)
+            } else if line.starts_with('"') {
+                (None, line)
+            } else {
+                continue;
+            };
+            if let Some(name) = name {
+                if name < 2 && current.string.is_some() {
+                    entries.push(std::mem::take(&mut current));
+                }
+                field = Some(name);
+            }
+            let rest = rest.trim();
+            let value = po_unescape(
+                rest.strip_prefix('"')
+                    .and_then(|v| v.strip_suffix('"'))
+                    .unwrap_or(rest),
+            );
+            let slot = match field {
+                Some(0) => &mut current.context,
+                Some(1) => &mut current.id,
+                Some(2) => &mut current.string,
+                _ => {
+                    return Err(fastn_core::Error::UsageError {
+                        message: format!("po: string without a keyword: {}", line),
+                    })
+                }
+            };
+            slot.get_or_insert_with(String::new)
+                .push_str(value.as_str());
+        }
+        if current.id.is_some() {
+            entries.push(current);
+        }
+
+        let mut file = TranslationFile {
+            document: String::new(),
+            source_language: None,
+            target_language: None,
+            translations: vec![],
+        };
+        for entry in entries {
+            let source = entry.id.unwrap_or_default();
+            let target = entry.string.filter(|t| !t.trim().is_empty());
+            match entry.context {
+                Some(id) => file.translations.push(Translation { id, source, target }),
+                None if source.is_empty() => {
+                    for header in target.unwrap_or_default().lines() {
+                        match header.split_once(':') {
+                            Some(("X-Fastn-Document", v)) => file.document = v.trim().to_string(),
+                            Some(("Language", v)) => {
+                                file.target_language = Some(v.trim().to_string())
+                            }
+                            _ => {}
+                        }
+                    }
+                }
+                None => {}
+            }
+        }
+        if file.document.is_empty() {
+            return Err(fastn_core::Error::UsageError {
+                message: "po: `X-Fastn-Document` header not found".to_string(),
+            });
+        }
+        Ok(file)
+    }
+}
+
+fn xml_escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+fn xml_unescape(s: &str) -> String {
+    s.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+fn po_escape(s: &str) -> String {
+    s.replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+        .replace('\t', "\\t")
+}
+
+fn po_unescape(s: &str) -> String {
+    let mut unescaped = String::new();
+    let mut chars = s.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            unescaped.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') => unescaped.push('\n'),
+            Some('t') => unescaped.push('\t'),
+            Some(c) => unescaped.push(c),
+            None => unescaped.push('\\'),
+        }
+    }
+    unescaped
+}
+
+/// Translations of strings merged so far, source to target
+#[derive(Debug, Default, serde::Serialize, serde::Deserialize)]
+pub(crate) struct TranslationMemory(pub std::collections::BTreeMap<String, String>);
+
+impl TranslationMemory {
+    pub(crate) async fn read(config: &fastn_core::Config) -> fastn_core::Result<Self> {
+        let path = config.root.join(TRANSLATION_MEMORY);
+        if !path.exists() {
+            return Ok(Default::default());
+        }
+        Ok(serde_json::from_str(
+            tokio::fs::read_to_string(path).await?.as_str(),
+        )?)
+    }
+
+    pub(crate) async fn write(&self, config: &fastn_core::Config) -> fastn_core::Result<()> {
+        fastn_core::utils::update(
+            config.root.join(TRANSLATION_MEMORY),
+            serde_json::to_string_pretty(self)?.as_bytes(),
+        )
+        .await
+    }
+
+    pub(crate) fn get(&self, source: &str) -> Option<&String> {
+        self.0.get(source.trim())
+    }
+
+    pub(crate) fn insert(&mut self, source: &str, target: &str) {
+        self.0
+            .insert(source.trim().to_string(), target.trim().to_string());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    const DOC: &str = indoc::indoc! {"
+        -- import: fastn/processors as pr
+
+        -- ftd.text: Hello World
+        role: $inherited.types.copy-small
+        link: https://fastn.com
+
+        -- ftd.column:
+        id: main
+
+        -- ftd.text:
+        title: Hello text
+
+        A long body
+        of text
+
+        -- end: ftd.column
+    "};
+
+    #[test]
+    fn extract_units() {
+        let units = super::extract_units(DOC, "index.ftd").unwrap();
+        assert_eq!(
+            units.iter().map(|u| u.source.as_str()).collect::<Vec<_>>(),
+            vec!["Hello World", "Hello text", "A long body\nof text"]
+        );
+    }
+
+    #[test]
+    fn merge_units() {
+        let units = super::extract_units(DOC, "index.ftd").unwrap();
+        let targets: std::collections::BTreeMap<String, String> = units
+            .iter()
+            .map(|u| (u.id.to_string(), u.source.to_uppercase()))
+            .collect();
+        let (merged, missed) = super::merge_units(DOC, &units, &targets);
+        assert!(missed.is_empty());
+        assert!(merged.contains("-- ftd.text: HELLO WORLD\n"));
+        assert!(merged.contains("title: HELLO TEXT\n"));
+        assert!(merged.contains("A LONG BODY\nOF TEXT\n"));
+        assert!(merged.contains("-- end: ftd.column"));
+    }
+
+    #[test]
+    fn round_trip() {
+        let file = super::TranslationFile {
+            document: "index.ftd".to_string(),
+            source_language: Some("en".to_string()),
+            target_language: Some("hi".to_string()),
+            translations: vec![
+                super::Translation {
+                    id: "3.caption".to_string(),
+                    source: "Hello \"World\" & <you>".to_string(),
+                    target: Some("नमस्ते".to_string()),
+                },
+                super::Translation {
+                    id: "12.body".to_string(),
+                    source: "A long body\nof text".to_string(),
+                    target: None,
+                },
+            ],
+        };
+        for format in [
+            super::TranslationFormat::Xliff,
+            super::TranslationFormat::Po,
+        ] {
+            let parsed =
+                super::TranslationFile::parse(file.render(format).as_str(), format).unwrap();
+            assert_eq!(parsed.document, file.document);
+            assert_eq!(parsed.translations, file.translations);
+        }
+    }
+}
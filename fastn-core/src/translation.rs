@@ -73,7 +73,14 @@ impl TranslatedDocument {
                 translated_latest,
             } => {
                 // Gets the diff on original file between last_marked_on and original_latest timestamp
-                let diff = get_diff(config, original, last_marked_on, original_latest).await?;
+                let diff = get_diff(
+                    config,
+                    original.get_id().as_str(),
+                    last_marked_on,
+                    original_latest,
+                )
+                .await?
+                .replace("---", "\\---");
                 let translated_data = TranslationData {
                     diff: Some(diff),
                     last_marked_on: Some(*last_marked_on),
@@ -105,30 +112,6 @@ impl TranslatedDocument {
         // )
         // .await?;
         // return Ok(());
-
-        /// Gets the diff on original file between last_marked_on and original_latest timestamp
-        async fn get_diff(
-            config: &fastn_core::Config,
-            original: &fastn_core::File,
-            last_marked_on: &u128,
-            original_latest: &u128,
-        ) -> fastn_core::Result<String> {
-            let last_marked_on_path = fastn_core::utils::history_path(
-                original.get_id().as_str(),
-                config.original_path()?.as_str(),
-                last_marked_on,
-            );
-            let last_marked_on_data = tokio::fs::read_to_string(last_marked_on_path).await?;
-            let original_latest_path = fastn_core::utils::history_path(
-                original.get_id().as_str(),
-                config.original_path()?.as_str(),
-                original_latest,
-            );
-            let original_latest_data = tokio::fs::read_to_string(original_latest_path).await?;
-
-            let patch = diffy::create_patch(&last_marked_on_data, &original_latest_data);
-            Ok(patch.to_string().replace("---", "\\---"))
-        }
     }
 
     pub async fn get_translated_document(
@@ -250,7 +233,7 @@ pub(crate) fn get_translation_status_counts(
     Ok(translation_status_count)
 }
 
-#[derive(serde::Deserialize, Debug, Clone)]
+#[derive(serde::Deserialize, serde::Serialize, Debug, Clone, Default)]
 pub struct TranslationStatusSummary {
     #[serde(rename = "never-marked")]
     pub never_marked: i32,
@@ -280,3 +263,20 @@ impl ToString for TranslationStatusSummary {
         )
     }
 }
+
+/// Gets the diff on original file `id` between last_marked_on and original_latest timestamp
+pub(crate) async fn get_diff(
+    config: &fastn_core::Config,
+    id: &str,
+    last_marked_on: &u128,
+    original_latest: &u128,
+) -> fastn_core::Result<String> {
+    let last_marked_on_path =
+        fastn_core::utils::history_path(id, config.original_path()?.as_str(), last_marked_on);
+    let last_marked_on_data = tokio::fs::read_to_string(last_marked_on_path).await?;
+    let original_latest_path =
+        fastn_core::utils::history_path(id, config.original_path()?.as_str(), original_latest);
+    let original_latest_data = tokio::fs::read_to_string(original_latest_path).await?;
+
+    Ok(diffy::create_patch(&last_marked_on_data, &original_latest_data).to_string())
+}
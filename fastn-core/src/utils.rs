@@ -212,7 +212,10 @@ pub(crate) fn language_to_human(language: &str) -> String {
 }
 
 pub(crate) fn nanos_to_rfc3339(nanos: &u128) -> String {
-    nanos.to_string() // TODO
+    match i64::try_from(*nanos) {
+        Ok(nanos) => chrono::TimeZone::timestamp_nanos(&chrono::Utc, nanos).to_rfc3339(),
+        Err(_) => nanos.to_string(),
+    }
 }
 
 pub(crate) fn history_path(id: &str, base_path: &str, timestamp: &u128) -> camino::Utf8PathBuf {
@@ -559,6 +562,7 @@ pub fn replace_markers_2022(
     main_id: &str,
     font_style: &str,
     base_url: &str,
+    alternate_links: &str,
 ) -> String {
     ftd::html::utils::trim_all_lines(
         s.replace(
@@ -572,7 +576,12 @@ pub fn replace_markers_2022(
         .replace("__ftd_data__", html_ui.variables.as_str())
         .replace(
             "__ftd_canonical_url__",
-            config.package.generate_canonical_url(main_id).as_str(),
+            format!(
+                "{}{}",
+                config.package.generate_canonical_url(main_id),
+                alternate_links
+            )
+            .as_str(),
         )
         .replace(
             "__favicon_html_tag__",
@@ -625,7 +634,7 @@ pub fn replace_markers_2022(
             .as_str(),
         )
         .replace("__ftd_body_events__", html_ui.outer_events.as_str())
-        .replace("__ftd_element_css__", "")
+        .replace("__ftd_element_css__", html_ui.element_css.as_str())
         .replace("__base_url__", base_url)
         .as_str(),
     )
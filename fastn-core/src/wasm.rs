@@ -1,166 +1,940 @@
-use std::str::FromStr;
-
-#[derive(Default)]
-pub struct HostExports {}
-
-impl fastn_utils::backend_host_export::host::Host for HostExports {
-    fn http(
-        &mut self,
-        request: fastn_utils::backend_host_export::host::Httprequest<'_>,
-    ) -> fastn_utils::backend_host_export::host::Httpresponse {
-        let url = request.path.to_string();
-        let request_method = request.method.to_string();
-        let request_body = request.payload.to_string();
+//! WASM backends.
+//!
+//! A backend is a wasm module which exports `memory`, `alloc(size: i32) -> i32` and
+//! `handle_request(ptr: i32, len: i32) -> i64`. Requests and responses cross the boundary as a
+//! message: a little-endian `u32` length, that many bytes of JSON head, and then the raw body.
+//! Values returned to the guest, and by `handle_request`, point to such a message in guest memory
+//! packed as `(ptr << 32) | len`.
+//!
+//! The guest can call back into the host through the `fastn` import module:
+//!
+//! - `http(ptr: i32, len: i32) -> i64`: sends an [`OutgoingRequest`] message and returns the
+//!   [`Response`] message. Hosts not listed in `allowed-hosts` get a `403` response, and
+//!   redirects are only followed to hosts that are listed.
+//! - `sqlite(ptr: i32, len: i32) -> i64`: runs a [`QueryRequest`] against one of the package
+//!   `databases` and returns a [`QueryResult`] message.
+//! - `kv(ptr: i32, len: i32) -> i64`: runs a [`KvRequest`] against the key-value store of the
+//!   package and returns a [`KvResult`] message, with the value as body.
+//! - `log(level: i32, ptr: i32, len: i32)`: logs the utf-8 string at `ptr` to `tracing`, with
+//!   levels `0` (error) to `4` (trace).
+//! - `identity() -> i64`: returns a message whose head maps each auth provider the user is
+//!   logged in with to their username.
+//!
+//! Failures of a host function are reported to the guest in the returned message and do not
+//! abort the request. Every request runs with the `fuel`, `timeout` and `memory` limits of
+//! `fastn.backend-capabilities`.
+//!
+//! Backends are declared per route with a `backend: <file>.wasm` key in the sitemap, which is
+//! inherited by the routes nested below it, or for the whole package with `backend: true` in
+//! `FASTN.ftd`, in which case `backend.wasm` handles every request that is not a document.
+
+/// Interval at which the epoch of [`ENGINE`] is incremented, used to enforce `timeout`.
+const EPOCH_TICK: std::time::Duration = std::time::Duration::from_millis(100);
+
+/// Redirects followed by the `http` host function, past these the redirect itself is returned to
+/// the guest.
+const MAX_REDIRECTS: usize = 10;
+
+static ENGINE: once_cell::sync::Lazy<wasmtime::Engine> = once_cell::sync::Lazy::new(|| {
+    let mut config = wasmtime::Config::new();
+    config.async_support(true);
+    config.consume_fuel(true);
+    config.epoch_interruption(true);
+    config.wasm_backtrace_details(wasmtime::WasmBacktraceDetails::Disable);
+    let engine = wasmtime::Engine::new(&config).expect("failed to create wasm engine");
+    let ticker = engine.clone();
+    std::thread::spawn(move || loop {
+        std::thread::sleep(EPOCH_TICK);
+        ticker.increment_epoch();
+    });
+    engine
+});
+
+/// Compiled modules keyed by the `.wasm` file they were read from. A module is compiled again
+/// only when the modification time of its file changes.
+#[allow(clippy::type_complexity)]
+static MODULES: once_cell::sync::Lazy<
+    async_lock::RwLock<
+        std::collections::HashMap<
+            camino::Utf8PathBuf,
+            (Option<std::time::SystemTime>, wasmtime::Module),
+        >,
+    >,
+> = once_cell::sync::Lazy::new(|| async_lock::RwLock::new(Default::default()));
+
+pub struct HostExports {
+    client: reqwest::Client,
+    capabilities: fastn_package::old_fastn::BackendCapabilities,
+    limits: wasmtime::StoreLimits,
+    package_name: String,
+    package_root: camino::Utf8PathBuf,
+    identities: std::collections::BTreeMap<String, String>,
+}
+
+impl HostExports {
+    async fn new(
+        req: &fastn_core::http::Request,
+        package: &fastn_core::Package,
+        package_root: camino::Utf8PathBuf,
+    ) -> WasmRunnerResult<HostExports> {
+        let capabilities = package.backend_capabilities.clone().unwrap_or_default();
+        let limits = wasmtime::StoreLimitsBuilder::new()
+            .memory_size(capabilities.memory.max(1) as usize * 1024 * 1024)
+            .build();
+        let identities = fastn_core::auth::get_logged_in_identities(req.cookies()).await;
+        Ok(HostExports {
+            client: client(capabilities.allowed_hosts.clone())?,
+            capabilities,
+            limits,
+            package_name: package.name.to_string(),
+            package_root,
+            identities,
+        })
+    }
+
+    async fn http(
+        &self,
+        request: OutgoingRequest,
+        body: Vec<u8>,
+    ) -> WasmRunnerResult<(Response, Vec<u8>)> {
+        let url = url::Url::parse(request.url.as_str()).map_err(|e| {
+            WASMError::InvalidMessage(format!("invalid url {}: {}", request.url, e))
+        })?;
+        let host = url.host_str().unwrap_or_default();
+        if !is_allowed_host(self.capabilities.allowed_hosts.as_slice(), host) {
+            return Err(WASMError::Capability(format!(
+                "host `{}` is not in `allowed-hosts`",
+                host
+            )));
+        }
+
+        let method = reqwest::Method::from_bytes(request.method.as_bytes()).map_err(|_| {
+            WASMError::WasmFunctionInvoke(format!("invalid method: {}", request.method))
+        })?;
         let mut headers = reqwest::header::HeaderMap::new();
-        request
-            .headers
-            .clone()
-            .into_iter()
-            .for_each(|(header_key, header_val)| {
-                headers.insert(
-                    reqwest::header::HeaderName::from_str(header_key).unwrap(),
-                    reqwest::header::HeaderValue::from_str(header_val).unwrap(),
-                );
-            });
-        let resp = std::thread::spawn(move || {
-            let request_client = reqwest::blocking::Client::new();
-            match request_method.as_str() {
-                "GET" => request_client.get(url).headers(headers),
-                "POST" => request_client.post(url).headers(headers).body(request_body),
-                "PATCH" => request_client
-                    .patch(url)
-                    .headers(headers)
-                    .body(request_body),
-                _ => panic!("METHOD not allowed"),
-            }
+        for (key, value) in request.headers {
+            headers.insert(
+                reqwest::header::HeaderName::from_bytes(key.as_bytes())?,
+                reqwest::header::HeaderValue::from_str(value.as_str())?,
+            );
+        }
+
+        let response = self
+            .client
+            .request(method, url)
+            .headers(headers)
+            .body(body)
             .send()
-            .unwrap()
-            .text()
-            .unwrap()
+            .await
+            .map_err(|e| {
+                if e.is_redirect() {
+                    WASMError::Capability(e.to_string())
+                } else {
+                    WASMError::Http(e)
+                }
+            })?;
+        let head = Response {
+            status: response.status().as_u16(),
+            headers: response
+                .headers()
+                .iter()
+                .map(|(key, value)| {
+                    (
+                        key.as_str().to_string(),
+                        String::from_utf8_lossy(value.as_bytes()).to_string(),
+                    )
+                })
+                .collect(),
+        };
+        Ok((head, response.bytes().await?.to_vec()))
+    }
+
+    async fn sqlite(&self, request: QueryRequest) -> WasmRunnerResult<QueryResult> {
+        if !self.capabilities.databases.contains(&request.db) {
+            return Err(WASMError::Capability(format!(
+                "database `{}` is not in `databases`",
+                request.db
+            )));
+        }
+        let db_path = self.package_root.join(request.db.trim_start_matches('/'));
+        tokio::task::spawn_blocking(move || -> WasmRunnerResult<QueryResult> {
+            let conn = rusqlite::Connection::open_with_flags(
+                db_path,
+                rusqlite::OpenFlags::SQLITE_OPEN_READ_WRITE,
+            )?;
+            let mut stmt = conn.prepare(request.query.as_str())?;
+            let params = rusqlite::params_from_iter(request.params.iter().map(json_to_sql));
+            let mut result = QueryResult {
+                columns: stmt.column_names().iter().map(|c| c.to_string()).collect(),
+                ..Default::default()
+            };
+            if result.columns.is_empty() {
+                result.changes = stmt.execute(params)?;
+                return Ok(result);
+            }
+            let count = result.columns.len();
+            let mut rows = stmt.query(params)?;
+            while let Some(row) = rows.next()? {
+                result.rows.push(row_to_json(row, count)?);
+            }
+            Ok(result)
+        })
+        .await
+        .map_err(|e| WASMError::WasmFunctionInvoke(e.to_string()))?
+    }
+
+    async fn kv(
+        &self,
+        request: KvRequest,
+        value: Vec<u8>,
+    ) -> WasmRunnerResult<(KvResult, Vec<u8>)> {
+        let db_path = self.package_root.join(".fastn").join("kv.sqlite");
+        let package_name = self.package_name.clone();
+        tokio::task::spawn_blocking(move || -> WasmRunnerResult<(KvResult, Vec<u8>)> {
+            if let Some(parent) = db_path.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            let conn = rusqlite::Connection::open(db_path)?;
+            conn.execute(
+                "CREATE TABLE IF NOT EXISTS kv (package TEXT NOT NULL, key TEXT NOT NULL, \
+                value BLOB NOT NULL, PRIMARY KEY (package, key))",
+                [],
+            )?;
+            match request.op.as_str() {
+                "get" => {
+                    let value: Option<Vec<u8>> = conn
+                        .query_row(
+                            "SELECT value FROM kv WHERE package = ?1 AND key = ?2",
+                            [package_name.as_str(), request.key.as_str()],
+                            |row| row.get(0),
+                        )
+                        .map(Some)
+                        .or_else(|e| match e {
+                            rusqlite::Error::QueryReturnedNoRows => Ok(None),
+                            e => Err(e),
+                        })?;
+                    Ok(match value {
+                        Some(value) => (
+                            KvResult {
+                                found: true,
+                                error: None,
+                            },
+                            value,
+                        ),
+                        None => (KvResult::default(), vec![]),
+                    })
+                }
+                "set" => {
+                    conn.execute(
+                        "INSERT OR REPLACE INTO kv (package, key, value) VALUES (?1, ?2, ?3)",
+                        rusqlite::params![package_name, request.key, value],
+                    )?;
+                    Ok((KvResult::default(), vec![]))
+                }
+                "delete" => {
+                    let changes = conn.execute(
+                        "DELETE FROM kv WHERE package = ?1 AND key = ?2",
+                        [package_name.as_str(), request.key.as_str()],
+                    )?;
+                    Ok((
+                        KvResult {
+                            found: changes > 0,
+                            error: None,
+                        },
+                        vec![],
+                    ))
+                }
+                op => Err(WASMError::InvalidMessage(format!(
+                    "unknown kv operation: {}",
+                    op
+                ))),
+            }
         })
-        .join()
-        .unwrap();
-        fastn_utils::backend_host_export::host::Httpresponse { data: resp }
+        .await
+        .map_err(|e| WASMError::WasmFunctionInvoke(e.to_string()))?
+    }
+}
+
+fn is_allowed_host(allowed_hosts: &[String], host: &str) -> bool {
+    allowed_hosts.iter().any(|allowed| allowed.eq(host))
+}
+
+/// The client of the `http` host function. Every redirect is checked against `allowed-hosts`,
+/// or a host that is allowed could send the guest to one that is not.
+fn client(allowed_hosts: Vec<String>) -> WasmRunnerResult<reqwest::Client> {
+    let policy = reqwest::redirect::Policy::custom(move |attempt| {
+        let host = attempt.url().host_str().unwrap_or_default().to_string();
+        if !is_allowed_host(allowed_hosts.as_slice(), host.as_str()) {
+            attempt.error(format!(
+                "redirect to host `{}` that is not in `allowed-hosts`",
+                host
+            ))
+        } else if attempt.previous().len() > MAX_REDIRECTS {
+            attempt.stop()
+        } else {
+            attempt.follow()
+        }
+    });
+    Ok(reqwest::Client::builder().redirect(policy).build()?)
+}
+
+fn json_to_sql(value: &serde_json::Value) -> rusqlite::types::Value {
+    match value {
+        serde_json::Value::Null => rusqlite::types::Value::Null,
+        serde_json::Value::Bool(b) => rusqlite::types::Value::Integer(*b as i64),
+        serde_json::Value::Number(n) => match n.as_i64() {
+            Some(i) => rusqlite::types::Value::Integer(i),
+            None => rusqlite::types::Value::Real(n.as_f64().unwrap_or_default()),
+        },
+        serde_json::Value::String(s) => rusqlite::types::Value::Text(s.to_string()),
+        v => rusqlite::types::Value::Text(v.to_string()),
+    }
+}
+
+fn row_to_json(r: &rusqlite::Row, count: usize) -> rusqlite::Result<Vec<serde_json::Value>> {
+    let mut row: Vec<serde_json::Value> = vec![];
+    for i in 0..count {
+        row.push(match r.get::<usize, rusqlite::types::Value>(i)? {
+            rusqlite::types::Value::Null => serde_json::Value::Null,
+            rusqlite::types::Value::Integer(i) => serde_json::Value::Number(i.into()),
+            rusqlite::types::Value::Real(f) => serde_json::Number::from_f64(f)
+                .map(serde_json::Value::Number)
+                .unwrap_or(serde_json::Value::Null),
+            rusqlite::types::Value::Text(t) => serde_json::Value::String(t),
+            rusqlite::types::Value::Blob(b) => {
+                serde_json::Value::Array(b.into_iter().map(serde_json::Value::from).collect())
+            }
+        });
     }
+    Ok(row)
 }
 
-pub struct Context<I, E> {
-    pub imports: I,
-    pub exports: E,
+/// Head of the request message passed to `handle_request`.
+#[derive(serde::Serialize, serde::Deserialize, Debug)]
+pub struct IncomingRequest {
+    pub method: String,
+    pub path: String,
+    pub query: String,
+    pub headers: Vec<(String, String)>,
+}
+
+/// Head of the request message the guest passes to the `http` host function.
+#[derive(serde::Serialize, serde::Deserialize, Debug)]
+pub struct OutgoingRequest {
+    pub method: String,
+    pub url: String,
+    #[serde(default)]
+    pub headers: Vec<(String, String)>,
+}
+
+/// Head of a response message, in either direction.
+#[derive(serde::Serialize, serde::Deserialize, Debug)]
+pub struct Response {
+    pub status: u16,
+    #[serde(default)]
+    pub headers: Vec<(String, String)>,
+}
+
+/// Head of the message the guest passes to the `sqlite` host function. `db` is a path, relative
+/// to the package root, listed in `databases`.
+#[derive(serde::Serialize, serde::Deserialize, Debug)]
+pub struct QueryRequest {
+    pub db: String,
+    pub query: String,
+    #[serde(default)]
+    pub params: Vec<serde_json::Value>,
+}
+
+/// Head of the message returned by the `sqlite` host function. Queries that return no columns
+/// report the number of rows they changed instead.
+#[derive(serde::Serialize, serde::Deserialize, Debug, Default)]
+pub struct QueryResult {
+    pub columns: Vec<String>,
+    pub rows: Vec<Vec<serde_json::Value>>,
+    pub changes: usize,
+    pub error: Option<String>,
+}
+
+/// Head of the message the guest passes to the `kv` host function. `op` is one of `get`, `set`
+/// (with the value as body) or `delete`.
+#[derive(serde::Serialize, serde::Deserialize, Debug)]
+pub struct KvRequest {
+    pub op: String,
+    pub key: String,
+}
+
+/// Head of the message returned by the `kv` host function.
+#[derive(serde::Serialize, serde::Deserialize, Debug, Default)]
+pub struct KvResult {
+    pub found: bool,
+    pub error: Option<String>,
 }
 
 #[derive(thiserror::Error, Debug)]
 pub enum WASMError {
     #[error("Wasmtime Error: {}", _0)]
-    WasmTime(#[from] wit_bindgen_host_wasmtime_rust::anyhow::Error),
+    WasmTime(#[from] wasmtime::Error),
+
+    #[error("Memory Access Error: {}", _0)]
+    MemoryAccess(#[from] wasmtime::MemoryAccessError),
 
     #[error("JSON Parsing Error: {}", _0)]
     SerdeJson(#[from] serde_json::Error),
 
+    #[error("IO Error: {}", _0)]
+    IO(#[from] std::io::Error),
+
+    #[error("HTTP Error: {}", _0)]
+    Http(#[from] reqwest::Error),
+
+    #[error("Invalid Header Name: {}", _0)]
+    HeaderName(#[from] reqwest::header::InvalidHeaderName),
+
+    #[error("Invalid Header Value: {}", _0)]
+    HeaderValue(#[from] reqwest::header::InvalidHeaderValue),
+
+    #[error("SQLite Error: {}", _0)]
+    Sqlite(#[from] rusqlite::Error),
+
+    #[error("CapabilityError: {}", _0)]
+    Capability(String),
+
+    #[error("TimeoutError: backend did not respond within {} seconds", _0)]
+    Timeout(i64),
+
+    #[error("InvalidMessage: {}", _0)]
+    InvalidMessage(String),
+
+    #[error("MissingExport: `{}` is not exported by the backend", _0)]
+    MissingExport(String),
+
     #[error("WasmFunctionInvokeError: {}", _0)]
     WasmFunctionInvoke(String),
 }
 
 pub type WasmRunnerResult<T> = std::result::Result<T, WASMError>;
 
+fn encode_message<T: serde::Serialize>(head: &T, body: &[u8]) -> WasmRunnerResult<Vec<u8>> {
+    let head = serde_json::to_vec(head)?;
+    let mut message = Vec::with_capacity(4 + head.len() + body.len());
+    message.extend_from_slice(&(head.len() as u32).to_le_bytes());
+    message.extend_from_slice(head.as_slice());
+    message.extend_from_slice(body);
+    Ok(message)
+}
+
+fn decode_message<T: serde::de::DeserializeOwned>(
+    message: &[u8],
+) -> WasmRunnerResult<(T, Vec<u8>)> {
+    if message.len() < 4 {
+        return Err(WASMError::InvalidMessage(
+            "message is shorter than its length prefix".to_string(),
+        ));
+    }
+    let (len, rest) = message.split_at(4);
+    let len = u32::from_le_bytes([len[0], len[1], len[2], len[3]]) as usize;
+    if rest.len() < len {
+        return Err(WASMError::InvalidMessage(format!(
+            "head of {} bytes does not fit in a message of {} bytes",
+            len,
+            message.len()
+        )));
+    }
+    let (head, body) = rest.split_at(len);
+    Ok((serde_json::from_slice(head)?, body.to_vec()))
+}
+
+fn unpack(packed: i64) -> (i32, i32) {
+    ((packed >> 32) as i32, (packed & 0xffff_ffff) as i32)
+}
+
+fn pack(ptr: i32, len: i32) -> i64 {
+    ((ptr as i64) << 32) | (len as u32 as i64)
+}
+
+/// The exports used to move messages in and out of the guest.
+struct GuestMemory {
+    memory: wasmtime::Memory,
+    alloc: wasmtime::TypedFunc<i32, i32>,
+}
+
+impl GuestMemory {
+    fn from_instance(
+        store: &mut wasmtime::Store<HostExports>,
+        instance: &wasmtime::Instance,
+    ) -> WasmRunnerResult<GuestMemory> {
+        Ok(GuestMemory {
+            memory: instance
+                .get_memory(&mut *store, "memory")
+                .ok_or_else(|| WASMError::MissingExport("memory".to_string()))?,
+            alloc: instance.get_typed_func::<i32, i32>(&mut *store, "alloc")?,
+        })
+    }
+
+    fn from_caller(
+        caller: &mut wasmtime::Caller<'_, HostExports>,
+    ) -> WasmRunnerResult<GuestMemory> {
+        let memory = caller
+            .get_export("memory")
+            .and_then(|export| export.into_memory())
+            .ok_or_else(|| WASMError::MissingExport("memory".to_string()))?;
+        let alloc = caller
+            .get_export("alloc")
+            .and_then(|export| export.into_func())
+            .ok_or_else(|| WASMError::MissingExport("alloc".to_string()))?
+            .typed::<i32, i32>(&*caller)?;
+        Ok(GuestMemory { memory, alloc })
+    }
+
+    async fn write(
+        &self,
+        mut store: impl wasmtime::AsContextMut<Data = HostExports>,
+        bytes: &[u8],
+    ) -> WasmRunnerResult<(i32, i32)> {
+        let len = bytes.len() as i32;
+        let ptr = self.alloc.call_async(&mut store, len).await?;
+        self.memory.write(&mut store, ptr as usize, bytes)?;
+        Ok((ptr, len))
+    }
+
+    /// Copies `len` bytes at `ptr` out of guest memory. Both come from the guest, so they are
+    /// checked against the size of the memory before anything is allocated for them.
+    fn read(
+        &self,
+        store: impl wasmtime::AsContext,
+        ptr: i32,
+        len: i32,
+    ) -> WasmRunnerResult<Vec<u8>> {
+        // pointers are unsigned in wasm, only `len` can be negative
+        let start = ptr as u32 as usize;
+        let size = self.memory.data_size(&store);
+        match usize::try_from(len)
+            .ok()
+            .and_then(|len| start.checked_add(len))
+        {
+            Some(end) if end <= size => Ok(self.memory.data(&store)[start..end].to_vec()),
+            _ => Err(WASMError::InvalidMessage(format!(
+                "{} bytes at {} are outside the {} bytes of guest memory",
+                len, start, size
+            ))),
+        }
+    }
+}
+
+/// Writes `head` and `body` into guest memory, returning the packed pointer.
+async fn reply<T: serde::Serialize>(
+    caller: &mut wasmtime::Caller<'_, HostExports>,
+    memory: &GuestMemory,
+    head: &T,
+    body: &[u8],
+) -> WasmRunnerResult<i64> {
+    let (ptr, len) = memory
+        .write(caller, encode_message(head, body)?.as_slice())
+        .await?;
+    Ok(pack(ptr, len))
+}
+
+fn add_to_linker(linker: &mut wasmtime::Linker<HostExports>) -> WasmRunnerResult<()> {
+    linker.func_wrap2_async(
+        "fastn",
+        "http",
+        |mut caller: wasmtime::Caller<'_, HostExports>, ptr: i32, len: i32| {
+            Box::new(async move {
+                let memory = GuestMemory::from_caller(&mut caller)?;
+                let (response, body) = match decode_message::<OutgoingRequest>(
+                    memory.read(&caller, ptr, len)?.as_slice(),
+                ) {
+                    Ok((request, body)) => caller.data().http(request, body).await,
+                    Err(e) => Err(e),
+                }
+                .unwrap_or_else(|e| {
+                    let status = match e {
+                        WASMError::Capability(_) => 403,
+                        WASMError::InvalidMessage(_) => 400,
+                        _ => 502,
+                    };
+                    let response = Response {
+                        status,
+                        headers: vec![],
+                    };
+                    (response, e.to_string().into_bytes())
+                });
+                Ok::<i64, wasmtime::Error>(
+                    reply(&mut caller, &memory, &response, body.as_slice()).await?,
+                )
+            })
+        },
+    )?;
+    linker.func_wrap2_async(
+        "fastn",
+        "sqlite",
+        |mut caller: wasmtime::Caller<'_, HostExports>, ptr: i32, len: i32| {
+            Box::new(async move {
+                let memory = GuestMemory::from_caller(&mut caller)?;
+                let result = match decode_message::<QueryRequest>(
+                    memory.read(&caller, ptr, len)?.as_slice(),
+                ) {
+                    Ok((request, _)) => caller.data().sqlite(request).await,
+                    Err(e) => Err(e),
+                }
+                .unwrap_or_else(|e| QueryResult {
+                    error: Some(e.to_string()),
+                    ..Default::default()
+                });
+                Ok::<i64, wasmtime::Error>(reply(&mut caller, &memory, &result, &[]).await?)
+            })
+        },
+    )?;
+    linker.func_wrap2_async(
+        "fastn",
+        "kv",
+        |mut caller: wasmtime::Caller<'_, HostExports>, ptr: i32, len: i32| {
+            Box::new(async move {
+                let memory = GuestMemory::from_caller(&mut caller)?;
+                let (result, value) =
+                    match decode_message::<KvRequest>(memory.read(&caller, ptr, len)?.as_slice()) {
+                        Ok((request, value)) => caller.data().kv(request, value).await,
+                        Err(e) => Err(e),
+                    }
+                    .unwrap_or_else(|e| {
+                        let result = KvResult {
+                            found: false,
+                            error: Some(e.to_string()),
+                        };
+                        (result, vec![])
+                    });
+                Ok::<i64, wasmtime::Error>(
+                    reply(&mut caller, &memory, &result, value.as_slice()).await?,
+                )
+            })
+        },
+    )?;
+    linker.func_wrap(
+        "fastn",
+        "log",
+        |mut caller: wasmtime::Caller<'_, HostExports>, level: i32, ptr: i32, len: i32| {
+            let memory = GuestMemory::from_caller(&mut caller)?;
+            let message =
+                String::from_utf8_lossy(memory.read(&caller, ptr, len)?.as_slice()).to_string();
+            let package = caller.data().package_name.as_str();
+            match level {
+                0 => tracing::error!(package, "{}", message),
+                1 => tracing::warn!(package, "{}", message),
+                2 => tracing::info!(package, "{}", message),
+                3 => tracing::debug!(package, "{}", message),
+                _ => tracing::trace!(package, "{}", message),
+            }
+            Ok::<(), wasmtime::Error>(())
+        },
+    )?;
+    linker.func_wrap0_async(
+        "fastn",
+        "identity",
+        |mut caller: wasmtime::Caller<'_, HostExports>| {
+            Box::new(async move {
+                let memory = GuestMemory::from_caller(&mut caller)?;
+                let identities = caller.data().identities.clone();
+                Ok::<i64, wasmtime::Error>(reply(&mut caller, &memory, &identities, &[]).await?)
+            })
+        },
+    )?;
+    Ok(())
+}
+
+async fn get_module(path: &camino::Utf8Path) -> WasmRunnerResult<wasmtime::Module> {
+    let modified = tokio::fs::metadata(path).await?.modified().ok();
+    if let Some((compiled_at, module)) = MODULES.read().await.get(path) {
+        if compiled_at.eq(&modified) {
+            return Ok(module.clone());
+        }
+    }
+
+    let module = wasmtime::Module::new(&ENGINE, tokio::fs::read(path).await?)?;
+    MODULES
+        .write()
+        .await
+        .insert(path.to_owned(), (modified, module.clone()));
+    Ok(module)
+}
+
+/// Returns the `.wasm` file declared with the `backend` key for `path` in the sitemap.
+pub(crate) fn sitemap_backend(
+    config: &fastn_core::Config,
+    path: &str,
+) -> Option<camino::Utf8PathBuf> {
+    let extra_data = config
+        .package
+        .sitemap
+        .as_ref()?
+        .get_extra_data_by_id(path)?;
+    let backend = extra_data.get("backend")?;
+    Some(config.root.join(backend.trim().trim_start_matches('/')))
+}
+
 pub async fn handle_wasm(
     req: fastn_core::http::Request,
     wasm_module: camino::Utf8PathBuf,
-    backend_headers: Option<Vec<fastn_core::package::BackendHeader>>,
+    package: &fastn_core::Package,
+    package_root: camino::Utf8PathBuf,
 ) -> fastn_core::http::Response {
     pub async fn inner(
         req: fastn_core::http::Request,
         wasm_module: camino::Utf8PathBuf,
-        backend_headers: Option<Vec<fastn_core::package::BackendHeader>>,
+        package: &fastn_core::Package,
+        package_root: camino::Utf8PathBuf,
     ) -> WasmRunnerResult<actix_web::HttpResponse> {
-        let mut wasm_config = wit_bindgen_host_wasmtime_rust::wasmtime::Config::new();
-        wasm_config.cache_config_load_default().unwrap();
-        wasm_config.wasm_backtrace_details(
-            wit_bindgen_host_wasmtime_rust::wasmtime::WasmBacktraceDetails::Disable,
-        );
+        let module = get_module(&wasm_module).await?;
 
-        let engine = wit_bindgen_host_wasmtime_rust::wasmtime::Engine::new(&wasm_config)?;
-        let module = wit_bindgen_host_wasmtime_rust::wasmtime::Module::from_file(
-            &engine,
-            wasm_module.as_str(),
-        )?;
-
-        let mut linker: wit_bindgen_host_wasmtime_rust::wasmtime::Linker<
-            fastn_core::wasm::Context<
-                fastn_core::wasm::HostExports,
-                fastn_utils::backend_host_import::guest_backend::GuestBackendData,
-            >,
-        > = wit_bindgen_host_wasmtime_rust::wasmtime::Linker::new(&engine);
-        let mut store = wit_bindgen_host_wasmtime_rust::wasmtime::Store::new(
-            &engine,
-            fastn_core::wasm::Context {
-                imports: fastn_core::wasm::HostExports {},
-                exports: fastn_utils::backend_host_import::guest_backend::GuestBackendData {},
-            },
-        );
+        let mut linker = wasmtime::Linker::new(&ENGINE);
+        add_to_linker(&mut linker)?;
+        let host = HostExports::new(&req, package, package_root).await?;
+        let timeout = host.capabilities.timeout.max(1);
+        let fuel = host.capabilities.fuel.max(0) as u64;
+        let mut store = wasmtime::Store::new(&ENGINE, host);
+        store.limiter(|host| &mut host.limits);
+        store.add_fuel(fuel)?;
+        store.set_epoch_deadline((timeout as u128 * 1000 / EPOCH_TICK.as_millis()).max(1) as u64);
 
-        fastn_utils::backend_host_export::host::add_to_linker(&mut linker, |cx| &mut cx.imports)?;
+        let instance = linker.instantiate_async(&mut store, &module).await?;
+        let memory = GuestMemory::from_instance(&mut store, &instance)?;
+        let handle_request =
+            instance.get_typed_func::<(i32, i32), i64>(&mut store, "handle_request")?;
 
-        let (import, _i) =
-            fastn_utils::backend_host_import::guest_backend::GuestBackend::instantiate(
-                &mut store,
-                &module,
-                &mut linker,
-                |cx| &mut cx.exports,
-            )?;
-
-        let uri = req.uri().to_string();
-        // TODO: Fix body
-        let b = req.body().to_vec();
-        let body_str = if let Ok(b) = std::str::from_utf8(&b) {
-            b
-        } else {
-            ""
-        };
-        let mut headers = vec![];
-
-        req.headers()
+        let mut headers: Vec<(String, String)> = req
+            .headers()
             .iter()
-            .for_each(|(header_name, header_value)| {
-                headers.push((
+            .map(|(header_name, header_value)| {
+                (
                     header_name.as_str().to_string(),
-                    header_value
-                        .to_str()
-                        .expect("Unable to parse header value")
-                        .to_string(),
-                ));
-            });
-        if let Some(b_headers) = backend_headers {
+                    String::from_utf8_lossy(header_value.as_bytes()).to_string(),
+                )
+            })
+            .collect();
+        if let Some(b_headers) = package.backend_headers.clone() {
             b_headers.into_iter().for_each(|header| {
                 let hk = header.header_key;
                 headers.push((format!("X-fastn-{hk}"), header.header_value));
             })
         };
-        let headers: Vec<(&str, &str)> = headers
-            .iter()
-            .map(|(k, v)| (k.as_str(), v.as_str()))
-            .collect();
-        let request = fastn_utils::backend_host_import::guest_backend::Httprequest {
-            path: uri.as_str(),
-            headers: &(headers)[..],
-            querystring: req.query_string(),
-            method: req.method(),
-            payload: body_str,
+        let request = IncomingRequest {
+            method: req.method().to_string(),
+            path: req.path().to_string(),
+            query: req.query_string().to_string(),
+            headers,
         };
-        fastn_core::time("WASM Guest function").it(match import.handlerequest(&mut store, request) {
-            Ok(data) => Ok(actix_web::HttpResponse::Ok()
-                .content_type(actix_web::http::header::ContentType::json())
-                .status(if data.success {
-                    actix_web::http::StatusCode::OK
-                } else {
-                    actix_web::http::StatusCode::BAD_REQUEST
-                })
-                .body(data.data)),
-            Err(err) => Err(WASMError::WasmFunctionInvoke(err.to_string())),
-        })
+
+        let (ptr, len) = memory
+            .write(&mut store, encode_message(&request, req.body())?.as_slice())
+            .await?;
+        // The epoch deadline only interrupts the guest; host calls it is waiting on are bounded
+        // by the same timeout here.
+        let packed = tokio::time::timeout(
+            std::time::Duration::from_secs(timeout as u64),
+            handle_request.call_async(&mut store, (ptr, len)),
+        )
+        .await
+        .map_err(|_| WASMError::Timeout(timeout))?;
+        let (ptr, len) = unpack(fastn_core::time("WASM Guest function").it(packed)?);
+        let (response, body): (Response, Vec<u8>) =
+            decode_message(memory.read(&store, ptr, len)?.as_slice())?;
+
+        let status = actix_web::http::StatusCode::from_u16(response.status).map_err(|_| {
+            WASMError::WasmFunctionInvoke(format!("invalid status code: {}", response.status))
+        })?;
+        let mut builder = actix_web::HttpResponse::build(status);
+        for (key, value) in response.headers {
+            builder.append_header((key, value));
+        }
+        Ok(builder.body(body))
+    }
+    fastn_core::time("WASM Execution: ").it(
+        match inner(req, wasm_module, package, package_root).await {
+            Ok(resp) => resp,
+            Err(err) => fastn_core::server_error!("{}", err.to_string()),
+        },
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    /// The package of the `27-wasm-backend` test, its `backend.wasm` responds with the head of
+    /// the request
+    async fn config() -> fastn_core::Config {
+        let root = camino::Utf8PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("tests/27-wasm-backend/input/amitu");
+        fastn_core::Config::read(Some(root.to_string()), false, None)
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn sitemap_backend() {
+        let config = config().await;
+        assert_eq!(
+            super::sitemap_backend(&config, "/echo/"),
+            Some(config.root.join("backend.wasm"))
+        );
+        assert_eq!(super::sitemap_backend(&config, "/"), None);
+    }
+
+    #[tokio::test]
+    async fn handle_wasm() {
+        let config = config().await;
+        let req = actix_web::test::TestRequest::post()
+            .uri("/echo/?q=1")
+            .insert_header(("x-test", "yes"))
+            .to_http_request();
+        let req = fastn_core::http::Request::from_actix(
+            req,
+            actix_web::web::Bytes::from_static(b"hello"),
+        );
+        let response = super::handle_wasm(
+            req,
+            config.root.join("backend.wasm"),
+            &config.package,
+            config.root.clone(),
+        )
+        .await;
+        assert_eq!(response.status(), actix_web::http::StatusCode::OK);
+        assert_eq!(
+            response.headers().get("content-type").unwrap(),
+            "application/json"
+        );
+        let body = actix_web::body::to_bytes(response.into_body())
+            .await
+            .unwrap();
+        let request: super::IncomingRequest = serde_json::from_slice(&body).unwrap();
+        assert_eq!(request.method, "POST");
+        assert_eq!(request.path, "/echo/");
+        assert_eq!(request.query, "q=1");
+        assert!(request
+            .headers
+            .contains(&("x-test".to_string(), "yes".to_string())));
+        assert!(request
+            .headers
+            .contains(&("X-fastn-APP-NAME".to_string(), "amitu".to_string())));
+    }
+
+    /// A backend whose `handle_request` returns `packed`, whatever the request
+    fn backend(name: &str, packed: i64) -> camino::Utf8PathBuf {
+        let wat = format!(
+            r#"(module
+                (memory (export "memory") 1)
+                (func (export "alloc") (param i32) (result i32) (i32.const 1024))
+                (func (export "handle_request") (param i32 i32) (result i64) (i64.const {})))"#,
+            packed
+        );
+        let path = camino::Utf8PathBuf::from_path_buf(std::env::temp_dir())
+            .unwrap()
+            .join(format!("fastn-wasm-{}.wat", name));
+        std::fs::write(&path, wat).unwrap();
+        path
+    }
+
+    #[tokio::test]
+    async fn read_out_of_bounds() {
+        let config = config().await;
+        for (name, packed) in [
+            ("negative-len", super::pack(1024, -1)),
+            ("past-end", super::pack(65530, 100)),
+            ("overflow", super::pack(-8, 16)),
+        ] {
+            let req = fastn_core::http::Request::from_actix(
+                actix_web::test::TestRequest::get().to_http_request(),
+                Default::default(),
+            );
+            let response = super::handle_wasm(
+                req,
+                backend(name, packed),
+                &config.package,
+                config.root.clone(),
+            )
+            .await;
+            assert_eq!(
+                response.status(),
+                actix_web::http::StatusCode::INTERNAL_SERVER_ERROR,
+                "{}",
+                name
+            );
+        }
+    }
+
+    /// Serves http on a local port, `/to/<url>` redirects to `<url>` and every other path gets a
+    /// `200` with the path as body
+    fn server() -> u16 {
+        use std::io::{Read, Write};
+
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        std::thread::spawn(move || {
+            for stream in listener.incoming() {
+                let mut stream = stream.unwrap();
+                let mut buf = [0; 4096];
+                let n = stream.read(&mut buf).unwrap();
+                let request = String::from_utf8_lossy(&buf[..n]).to_string();
+                let path = request.split(' ').nth(1).unwrap_or_default().to_string();
+                let response = match path.strip_prefix("/to/") {
+                    Some(url) => format!(
+                        "HTTP/1.1 302 Found\r\nLocation: {}\r\nContent-Length: 0\r\n\
+                        Connection: close\r\n\r\n",
+                        url
+                    ),
+                    None => format!(
+                        "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                        path.len(),
+                        path
+                    ),
+                };
+                stream.write_all(response.as_bytes()).unwrap();
+            }
+        });
+        port
+    }
+
+    #[tokio::test]
+    async fn http_redirects() {
+        let port = server();
+        let allowed_hosts = vec!["127.0.0.1".to_string()];
+        let host = super::HostExports {
+            client: super::client(allowed_hosts.clone()).unwrap(),
+            capabilities: fastn_package::old_fastn::BackendCapabilities {
+                allowed_hosts,
+                ..Default::default()
+            },
+            limits: wasmtime::StoreLimitsBuilder::new().build(),
+            package_name: "www.amitu.com".to_string(),
+            package_root: Default::default(),
+            identities: Default::default(),
+        };
+        let get = |url: String| super::OutgoingRequest {
+            method: "GET".to_string(),
+            url,
+            headers: vec![],
+        };
+
+        let (response, body) = host
+            .http(
+                get(format!(
+                    "http://127.0.0.1:{}/to/http://127.0.0.1:{}/done",
+                    port, port
+                )),
+                vec![],
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status, 200);
+        assert_eq!(body, b"/done");
+
+        let err = host
+            .http(
+                get(format!(
+                    "http://127.0.0.1:{}/to/http://localhost:{}/done",
+                    port, port
+                )),
+                vec![],
+            )
+            .await
+            .unwrap_err();
+        assert!(matches!(err, super::WASMError::Capability(_)), "{}", err);
     }
-    fastn_core::time("WASM Execution: ").it(match inner(req, wasm_module, backend_headers).await {
-        Ok(resp) => resp,
-        Err(err) => fastn_core::server_error!("{}", err.to_string()),
-    })
 }
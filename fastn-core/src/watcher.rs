@@ -12,7 +12,7 @@ fn watcher() -> (
 ) {
     let (tx, mut rx) = tokio::sync::mpsc::channel::<WatcherSender>(32);
     let (g_tx, mut g_rx) = tokio::sync::mpsc::channel::<usize>(32);
-    let (f_tx, mut f_rx) = tokio::sync::mpsc::channel::<()>(32);
+    let (f_tx, mut f_rx) = tokio::sync::mpsc::channel::<Vec<std::path::PathBuf>>(32);
 
     if fastn_core::utils::is_test() {
         // we do not want to run the watcher in tests
@@ -36,8 +36,13 @@ fn watcher() -> (
                     polls.remove(&id);
                     println!("removed poll request");
                 }
-                Some(()) = f_rx.recv() => {
-                    // some file event has happened, lets inform all pending watchers
+                Some(paths) = f_rx.recv() => {
+                    // some file event has happened, lets update the package db and inform all
+                    // pending watchers
+                    for path in paths.iter() {
+                        fastn_core::package::initializer::file_changed(path).await;
+                    }
+                    fastn_core::render_cache::clear();
                     println!("file event, informing {} pending polls", polls.len());
                     for p in polls.values() {
                         if let Err(e) = p.send(()).await {
@@ -57,11 +62,14 @@ fn watcher() -> (
     (tx, g_tx)
 }
 
-fn create_watcher(f_tx: tokio::sync::mpsc::Sender<()>) -> notify::RecommendedWatcher {
+fn create_watcher(
+    f_tx: tokio::sync::mpsc::Sender<Vec<std::path::PathBuf>>,
+) -> notify::RecommendedWatcher {
     use notify::Watcher;
 
-    let mut watcher = notify::recommended_watcher(move |_res| {
-        if let Err(e) = f_tx.blocking_send(()) {
+    let mut watcher = notify::recommended_watcher(move |res: notify::Result<notify::Event>| {
+        let paths = res.map(|e| e.paths).unwrap_or_default();
+        if let Err(e) = f_tx.blocking_send(paths) {
             eprintln!("watcher: failed to send signal: {}", e);
         }
     })
@@ -77,6 +85,12 @@ fn create_watcher(f_tx: tokio::sync::mpsc::Sender<()>) -> notify::RecommendedWat
     watcher
 }
 
+/// Starts watching for file changes, which also invalidate `fastn_core::render_cache` and update
+/// the package db
+pub(crate) fn start() {
+    once_cell::sync::Lazy::force(&WATCHER);
+}
+
 fn next_id() -> usize {
     GLOBAL_POLL_COUNT.fetch_add(1, std::sync::atomic::Ordering::SeqCst)
 }
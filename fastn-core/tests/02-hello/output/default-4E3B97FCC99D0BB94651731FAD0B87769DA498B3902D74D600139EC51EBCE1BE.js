@@ -131,6 +131,133 @@ window.ftd = (function () {
     exports.is_empty = function (str) {
         return (!str || str.length === 0);
     };
+    exports.format_date = function (value, pattern) {
+        return format_datetime(value, pattern);
+    };
+    exports.format_date_locale = function (value, locale) {
+        return format_datetime(value, date_locale_pattern(locale, !is_plain_date(value)));
+    };
+    exports.format_duration = function (seconds) {
+        if (seconds === 0) {
+            return "0s";
+        }
+        let remaining = Math.abs(seconds);
+        let parts = [];
+        for (let [unit, size] of [["d", 86400], ["h", 3600], ["m", 60], ["s", 1]]) {
+            let count = Math.floor(remaining / size);
+            remaining %= size;
+            if (count > 0) {
+                parts.push(count + unit);
+            }
+        }
+        return (seconds < 0 ? "-" : "") + parts.join(" ");
+    };
+    exports.date_diff = function (from, to) {
+        let from_date = parse_datetime(from);
+        let to_date = parse_datetime(to);
+        if (from_date === null || to_date === null) {
+            return null;
+        }
+        return Math.trunc((to_date.getTime() - from_date.getTime()) / 1000);
+    };
+    exports.date_add = function (value, seconds) {
+        let d = parse_datetime(value);
+        if (d === null) {
+            return null;
+        }
+        let result = new Date(d.getTime() + seconds * 1000);
+        return is_plain_date(value)
+            ? result.toISOString().substring(0, 10)
+            : datetime_to_string(result);
+    };
+    exports.map_keys = function (map) {
+        return !map ? [] : Object.keys(map).sort();
+    };
+    exports.map_values = function (map) {
+        return !map ? [] : Object.keys(map).sort().map(key => map[key]);
+    };
+    exports.map_contains = function (map, key) {
+        return !!map && Object.prototype.hasOwnProperty.call(map, String(key));
+    };
+    exports.map_get = function (map, key) {
+        return window.ftd.map_contains(map, key) ? map[String(key)] : null;
+    };
+    exports.split = function (s, separator) {
+        return separator === "" ? Array.from(s) : s.split(separator);
+    };
+    exports.join = function (list, separator) {
+        return list_items(list).map(value_to_text).join(separator);
+    };
+    exports.replace = function (s, from, to) {
+        return from === "" ? s : s.split(from).join(to);
+    };
+    exports.trim = function (s) {
+        return s.trim();
+    };
+    exports.starts_with = function (s, prefix) {
+        return s.startsWith(prefix);
+    };
+    exports.ends_with = function (s, suffix) {
+        return s.endsWith(suffix);
+    };
+    exports.to_uppercase = function (s) {
+        return s.toUpperCase();
+    };
+    exports.to_lowercase = function (s) {
+        return s.toLowerCase();
+    };
+    exports.slice = function (value, start, end) {
+        end = typeof end === "number" ? end : undefined;
+        if (typeof value === "string") {
+            return Array.from(value).slice(start, end).join("");
+        }
+        return list_items(value).slice(start, end);
+    };
+    exports.reverse = function (value) {
+        if (typeof value === "string") {
+            return Array.from(value).reverse().join("");
+        }
+        return list_items(value).slice().reverse();
+    };
+    exports.sort = function (list) {
+        let items = list_items(list).slice();
+        if (items.every((v) => typeof v === "number")) {
+            return items.sort((a, b) => a - b);
+        }
+        return items.sort((a, b) => {
+            let [x, y] = [value_to_text(a), value_to_text(b)];
+            return x < y ? -1 : x > y ? 1 : 0;
+        });
+    };
+    exports.contains = function (value, item) {
+        if (typeof value === "string") {
+            return value.includes(value_to_text(item));
+        }
+        return list_items(value).some((v) => values_equal(v, item));
+    };
+    exports.index_of = function (value, item) {
+        if (typeof value === "string") {
+            let index = value.indexOf(value_to_text(item));
+            return index < 0 ? -1 : Array.from(value.substring(0, index)).length;
+        }
+        return list_items(value).findIndex((v) => values_equal(v, item));
+    };
+    exports.filter = function (list, item) {
+        return list_items(list).filter((v) => values_equal(v, item));
+    };
+    exports.sum = function (list) {
+        return list_items(list).reduce((sum, v) => sum + v, 0);
+    };
+    exports.format_number = function (value, decimals) {
+        decimals = typeof decimals === "number" ? Math.min(Math.max(Math.trunc(decimals), 0), 15) : 0;
+        let scale = Math.pow(10, decimals);
+        let scaled = Math.floor(Math.abs(value) * scale + 0.5);
+        let result = String(Math.floor(scaled / scale)).replace(/\B(?=(\d{3})+(?!\d))/g, ",");
+        if (decimals > 0) {
+            result += "." + String(scaled % scale).padStart(decimals, "0");
+        }
+        return value < 0 && scaled !== 0 ? "-" + result : result;
+    };
     exports.set_list = function (array, value, args, data, id) {
         args["CHANGE_VALUE"] = false;
         window.ftd.clear(array, args, data, id);
@@ -282,6 +409,11 @@ window.ftd = (function () {
         xhr.open(method_name, url);
         xhr.setRequestHeader("Accept", "application/json");
         xhr.setRequestHeader("Content-Type", "application/json");
+        // fastn form handlers expect the `fastn-csrf` cookie to be sent back as a header
+        let csrf_cookie = document.cookie.split("; ").find(c => c.startsWith("fastn-csrf="));
+        if (!!csrf_cookie) {
+            xhr.setRequestHeader("X-FASTN-CSRF", csrf_cookie.substring("fastn-csrf=".length));
+        }
         xhr.onreadystatechange = function () {
             if (xhr.readyState !== 4) {
                 // this means request is still underway
@@ -1050,6 +1182,45 @@ window.ftd.dependencies.eval_box_shadow = function (shadow, data) {
         return null;
     }
 };
+window.ftd.dependencies.eval_transform = function (transform, data) {
+    if (typeof transform === 'object' && !!transform) {
+        var functions = [];
+        if (transform["translate-x"] != null)
+            functions.push("translateX(" + transform["translate-x"] + ")");
+        if (transform["translate-y"] != null)
+            functions.push("translateY(" + transform["translate-y"] + ")");
+        if (transform.scale != null)
+            functions.push("scale(" + transform.scale + ")");
+        if (transform.rotate != null)
+            functions.push("rotate(" + transform.rotate + "deg)");
+        if (functions.length === 0)
+            return "none";
+        return functions.join(" ");
+    }
+    else {
+        return null;
+    }
+};
+window.ftd.dependencies.eval_transition = function (transition, data) {
+    if (typeof transition === 'object' && !!transition) {
+        // property, duration, easing, delay
+        return transition.property + " " + transition.duration + "ms " + transition.easing + " " + transition.delay + "ms";
+    }
+    else {
+        return null;
+    }
+};
+window.ftd.dependencies.eval_animation = function (animation, data) {
+    if (typeof animation === 'object' && !!animation) {
+        let iterations = animation.infinite ? "infinite" : animation.iterations;
+        let direction = animation.alternate ? "alternate" : "normal";
+        // name, duration, easing, delay, iterations, direction, fill-mode
+        return animation.keyframes.name + " " + animation.duration + "ms " + animation.easing + " " + animation.delay + "ms " + iterations + " " + direction + " both";
+    }
+    else {
+        return null;
+    }
+};
 window.ftd.utils.add_extra_in_id = function (node_id) {
     let element = document.querySelector(`[data-id=\"${node_id}\"]`);
     if (element) {
@@ -1090,6 +1261,124 @@ function updatedID(str, flag, suffix) {
         return str.replace(suffix, "");
     }
 }
+// Date helpers, these mirror `ftd::interpreter::things::date` so that dates
+// are parsed and formatted identically on the server and in the browser.
+const DATE_MONTHS = ["January", "February", "March", "April", "May", "June", "July",
+    "August", "September", "October", "November", "December"];
+const DATE_WEEKDAYS = ["Sunday", "Monday", "Tuesday", "Wednesday", "Thursday", "Friday",
+    "Saturday"];
+const DATE_REGEX = /^(\d{4})-(\d{1,2})-(\d{1,2})$/;
+const DATETIME_REGEX = /^(\d{4})-(\d{1,2})-(\d{1,2})[T ](\d{1,2}):(\d{1,2})(?::(\d{1,2})(\.\d+)?)?(Z|[+-]\d{2}:\d{2})?$/;
+function parse_datetime(value) {
+    value = value.trim();
+    let date = DATE_REGEX.exec(value);
+    if (!!date) {
+        return new Date(Date.UTC(parseInt(date[1]), parseInt(date[2]) - 1, parseInt(date[3])));
+    }
+    let m = DATETIME_REGEX.exec(value);
+    if (!m) {
+        return null;
+    }
+    let ms = Date.UTC(parseInt(m[1]), parseInt(m[2]) - 1, parseInt(m[3]), parseInt(m[4]),
+        parseInt(m[5]), !!m[6] ? parseInt(m[6]) : 0);
+    if (!!m[8] && m[8] !== "Z") {
+        let sign = m[8][0] === "-" ? -1 : 1;
+        let [hours, minutes] = m[8].substring(1).split(":");
+        ms -= sign * (parseInt(hours) * 60 + parseInt(minutes)) * 60000;
+    }
+    return new Date(ms);
+}
+function is_plain_date(value) {
+    return DATE_REGEX.test(value.trim());
+}
+function pad_date_part(n, no_pad) {
+    return no_pad || n >= 10 ? n.toString() : "0" + n;
+}
+function format_datetime(value, pattern) {
+    let d = parse_datetime(value);
+    if (d === null) {
+        return null;
+    }
+    let result = "";
+    for (let i = 0; i < pattern.length; i++) {
+        let c = pattern[i];
+        if (c !== "%") {
+            result += c;
+            continue;
+        }
+        let no_pad = pattern[i + 1] === "-";
+        if (no_pad) {
+            i++;
+        }
+        i++;
+        let hour12 = d.getUTCHours() % 12 === 0 ? 12 : d.getUTCHours() % 12;
+        switch (pattern[i]) {
+            case "Y": result += d.getUTCFullYear(); break;
+            case "y": result += pad_date_part(d.getUTCFullYear() % 100, false); break;
+            case "m": result += pad_date_part(d.getUTCMonth() + 1, no_pad); break;
+            case "d": result += pad_date_part(d.getUTCDate(), no_pad); break;
+            case "e": result += d.getUTCDate(); break;
+            case "H": result += pad_date_part(d.getUTCHours(), no_pad); break;
+            case "I": result += pad_date_part(hour12, no_pad); break;
+            case "M": result += pad_date_part(d.getUTCMinutes(), no_pad); break;
+            case "S": result += pad_date_part(d.getUTCSeconds(), no_pad); break;
+            case "p": result += d.getUTCHours() >= 12 ? "PM" : "AM"; break;
+            case "B": result += DATE_MONTHS[d.getUTCMonth()]; break;
+            case "b": result += DATE_MONTHS[d.getUTCMonth()].substring(0, 3); break;
+            case "A": result += DATE_WEEKDAYS[d.getUTCDay()]; break;
+            case "a": result += DATE_WEEKDAYS[d.getUTCDay()].substring(0, 3); break;
+            case "%": result += "%"; break;
+            default: return null;
+        }
+    }
+    return result;
+}
+function date_locale_pattern(locale, with_time) {
+    let normalised = locale.toLowerCase().replace("_", "-");
+    let language = normalised.split("-")[0];
+    if (["en-gb", "en-in", "en-au", "en-nz", "en-ie"].includes(normalised)) {
+        return with_time ? "%-d %b %Y %H:%M" : "%-d %b %Y";
+    }
+    if (language === "en") {
+        return with_time ? "%b %-d, %Y %-I:%M %p" : "%b %-d, %Y";
+    }
+    if (["de", "ru", "pl", "tr", "fi", "nb", "da", "cs"].includes(language)) {
+        return with_time ? "%d.%m.%Y %H:%M" : "%d.%m.%Y";
+    }
+    if (["fr", "es", "it", "pt", "hi"].includes(language)) {
+        return with_time ? "%d/%m/%Y %H:%M" : "%d/%m/%Y";
+    }
+    if (["ja", "zh", "ko"].includes(language)) {
+        return with_time ? "%Y/%m/%d %H:%M" : "%Y/%m/%d";
+    }
+    return with_time ? "%Y-%m-%d %H:%M" : "%Y-%m-%d";
+}
+function datetime_to_string(d) {
+    return d.toISOString().substring(0, 19) + "Z";
+}
+function list_items(value) {
+    if (Array.isArray(value)) {
+        return value;
+    }
+    return value === null || value === undefined ? [] : [value];
+}
+// Same as `String()` except that `null` is empty, this is how `ftd.join` and
+// `ftd.sort` write values at render time as well.
+function value_to_text(value) {
+    if (value === null || value === undefined) {
+        return "";
+    }
+    if (Array.isArray(value)) {
+        return value.map(value_to_text).join(",");
+    }
+    return String(value);
+}
+function values_equal(a, b) {
+    if (Array.isArray(a) && Array.isArray(b)) {
+        return a.length === b.length && a.every((v, i) => values_equal(v, b[i]));
+    }
+    return a === b;
+}
 
 
 FASTN_JS
\ No newline at end of file
@@ -0,0 +1,49 @@
+;; The backend of `input/amitu`, rebuilt with:
+;;
+;;     wat2wasm backend.wat -o ../input/amitu/backend.wasm
+;;
+;; Every request is answered with the head of the request as json, so tests can check what the
+;; host passes to `handle_request`.
+(module
+  (memory (export "memory") 1)
+
+  ;; head of every response, with its length prefix
+  (data (i32.const 0) "\3e\00\00\00{\"status\":200,\"headers\":[[\"content-type\",\"application/json\"]]}")
+
+  ;; memory from here on is handed out by `alloc`, and never freed
+  (global $next (mut i32) (i32.const 1024))
+
+  (func $alloc (export "alloc") (param $size i32) (result i32)
+    (local $ptr i32)
+    (local $end i32)
+    (local.set $ptr (global.get $next))
+    (local.set $end (i32.add (local.get $ptr) (local.get $size)))
+    ;; grow the memory by as many 64KiB pages as the allocation needs
+    (if (i32.gt_u (local.get $end) (i32.shl (memory.size) (i32.const 16)))
+      (then
+        (if (i32.eq
+              (memory.grow
+                (i32.sub
+                  (i32.shr_u (i32.add (local.get $end) (i32.const 65535)) (i32.const 16))
+                  (memory.size)))
+              (i32.const -1))
+          (then unreachable))))
+    (global.set $next (local.get $end))
+    (local.get $ptr))
+
+  (func (export "handle_request") (param $ptr i32) (param $len i32) (result i64)
+    (local $head i32)
+    (local $out i32)
+    (local $out_len i32)
+    (local.set $head (i32.load (local.get $ptr)))
+    ;; the 66 bytes of the response head, and the request head as body
+    (local.set $out_len (i32.add (i32.const 66) (local.get $head)))
+    (local.set $out (call $alloc (local.get $out_len)))
+    (memory.copy (local.get $out) (i32.const 0) (i32.const 66))
+    (memory.copy
+      (i32.add (local.get $out) (i32.const 66))
+      (i32.add (local.get $ptr) (i32.const 4))
+      (local.get $head))
+    (i64.or
+      (i64.shl (i64.extend_i32_u (local.get $out)) (i64.const 32))
+      (i64.extend_i32_u (local.get $out_len)))))
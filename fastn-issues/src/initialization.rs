@@ -10,6 +10,30 @@ pub enum InitializePackageError {
         #[from]
         source: InitializeDBError,
     },
+    #[error("static files error: {source}")]
+    StaticFilesError {
+        #[from]
+        source: StaticFilesError,
+    },
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum FileChangedError {
+    #[error("fastn.ftd error: {source}")]
+    FastnFTDError {
+        #[from]
+        source: FastnFTDError,
+    },
+    #[error("static files error: {source}")]
+    StaticFilesError {
+        #[from]
+        source: StaticFilesError,
+    },
+    #[error("cant update the db: {source}")]
+    UpdateDB {
+        #[from]
+        source: rusqlite::Error,
+    },
 }
 
 #[derive(thiserror::Error, Debug)]
@@ -29,6 +53,16 @@ pub enum FastnFTDError {
         #[from]
         source: StoreNameError,
     },
+    #[error("Cant get {variable} from FASTN.ftd: {source}")]
+    CantGetVariable {
+        variable: String,
+        source: ftd::ftd2021::p1::Error,
+    },
+    #[error("Cant store {table}: {source}")]
+    StorePackageData {
+        table: String,
+        source: rusqlite::Error,
+    },
 }
 
 #[derive(thiserror::Error, Debug)]
@@ -38,6 +72,53 @@ pub enum StoreNameError {
         #[from]
         source: GetNameError,
     },
+    #[error("Cant insert package name: {source}")]
+    InsertName {
+        #[from]
+        source: rusqlite::Error,
+    },
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum StaticFilesError {
+    #[error("Cant list files: {source}")]
+    ListFiles {
+        #[from]
+        source: ListFilesError,
+    },
+    #[error("Cant read static file: {source}")]
+    ReadFile {
+        #[from]
+        source: FileAsBytesError,
+    },
+    #[error("Cant store static file {name}: {source}")]
+    StoreStaticFile {
+        name: String,
+        source: rusqlite::Error,
+    },
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum ListFilesError {
+    #[error("cant read directory: {name}, {source}")]
+    CantReadDirectory {
+        name: String,
+        source: std::io::Error,
+    },
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum FileAsBytesError {
+    #[error("file not found: {name}, {source}")]
+    FileDoesNotExist {
+        name: String,
+        source: std::io::Error,
+    },
+    #[error("cant read file: {name}, {source}")]
+    CantReadFile {
+        name: String,
+        source: std::io::Error,
+    },
 }
 
 #[derive(thiserror::Error, Debug)]
@@ -94,4 +175,15 @@ pub enum InitializeDBError {
     OpenDBConnection { source: rusqlite::Error },
     #[error("cant create tables: {source}")]
     CreateTables { source: rusqlite::Error },
+    #[error("cant store the package: {source}")]
+    Transaction { source: rusqlite::Error },
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum QueryError {
+    #[error("cant query the package: {source}")]
+    Sqlite {
+        #[from]
+        source: rusqlite::Error,
+    },
 }
@@ -4,6 +4,7 @@ pub fn display_initialisation_error(e: &InitializePackageError) {
     match e {
         InitializePackageError::FastnFTDError { source } => display_fastn_ftd_error(source),
         InitializePackageError::InitializeDBError { source } => display_initialize_db_error(source),
+        InitializePackageError::StaticFilesError { source } => display_static_files_error(source),
     }
 }
 
@@ -19,6 +20,15 @@ fn display_fastn_ftd_error(e: &FastnFTDError) {
         FastnFTDError::StorePackageName { .. } => {
             todo!()
         }
+        FastnFTDError::CantGetVariable { variable, source } => {
+            eprintln!(
+                "FASTN.ftd: `{}` does not match what fastn expects: {}",
+                variable, source
+            );
+        }
+        FastnFTDError::StorePackageData { table, source } => {
+            eprintln!("Can't store the {} of FASTN.ftd: {}", table, source);
+        }
     }
 }
 
@@ -30,5 +40,22 @@ fn display_initialize_db_error(e: &InitializeDBError) {
         InitializeDBError::CreateTables { .. } => {
             todo!()
         }
+        InitializeDBError::Transaction { source } => {
+            eprintln!("Can't store the package, nothing was changed: {}", source);
+        }
+    }
+}
+
+fn display_static_files_error(e: &StaticFilesError) {
+    match e {
+        StaticFilesError::ListFiles { source } => {
+            eprintln!("Can't list the files of the package: {}", source);
+        }
+        StaticFilesError::ReadFile { source } => {
+            eprintln!("Can't read a file of the package: {}", source);
+        }
+        StaticFilesError::StoreStaticFile { name, source } => {
+            eprintln!("Can't store the static file {}: {}", name, source);
+        }
     }
 }
@@ -1,10 +1,49 @@
 DROP TABLE IF EXISTS main_package;
+DROP TABLE IF EXISTS translations;
+DROP TABLE IF EXISTS dependencies;
+DROP TABLE IF EXISTS sitemap;
+DROP TABLE IF EXISTS user_groups;
+DROP TABLE IF EXISTS user_group_identities;
 DROP TABLE IF EXISTS static_files;
 
 CREATE TABLE main_package (
+    name TEXT NOT NULL PRIMARY KEY,
+    language TEXT NULL,
+    translation_of TEXT NULL,
+    canonical_url TEXT NULL
+) WITHOUT ROWID;
+
+CREATE TABLE translations (
     name TEXT NOT NULL PRIMARY KEY
 ) WITHOUT ROWID;
 
+-- `name` is the package, `alias` is what follows `as` in `-- fastn.dependency: <name> as <alias>`
+CREATE TABLE dependencies (
+    name TEXT NOT NULL PRIMARY KEY,
+    alias TEXT NULL,
+    version TEXT NULL,
+    endpoint TEXT NULL,
+    mount_point TEXT NULL
+) WITHOUT ROWID;
+
+CREATE TABLE sitemap (
+    body TEXT NOT NULL
+);
+
+CREATE TABLE user_groups (
+    id TEXT NOT NULL PRIMARY KEY,
+    title TEXT NULL,
+    description TEXT NULL
+) WITHOUT ROWID;
+
+-- `key` is `email`, `github` etc, `excluded` for the `-email`, `-github` etc headers
+CREATE TABLE user_group_identities (
+    group_id TEXT NOT NULL,
+    key TEXT NOT NULL,
+    value TEXT NOT NULL,
+    excluded BOOLEAN NOT NULL
+);
+
 CREATE TABLE static_files (
     name TEXT NOT NULL PRIMARY KEY,
     content_type TEXT NOT NULL,
@@ -1,42 +1,354 @@
-/// re_initialise() is called when any file is changed in the package
+/// re_initialise() loads the whole package again, `file_changed()` updates only what depends on
+/// a changed file
 pub async fn re_initialise(
+    package: &fastn_package::Package,
     i: impl fastn_package::initializer::Initializer,
 ) -> Result<(), fastn_issues::initialization::InitializePackageError> {
     if let Some(v) = fastn_package::FTD_CACHE.get() {
         let mut v = v.write().await;
         v.clear();
     }
-    let conn = fastn_package::sqlite::initialize_db()?;
-    process_fastn_ftd(i, conn).await?;
-    todo!()
+    // everything is read before the db is touched, so a broken FASTN.ftd does not leave the db
+    // half filled
+    let fastn_doc = read_fastn_ftd(&i).await?;
+    let static_files = read_static_files(&i).await?;
+
+    let mut conn = package.connection();
+    let tx = conn
+        .transaction()
+        .map_err(|e| fastn_issues::initialization::InitializeDBError::Transaction { source: e })?;
+    fastn_package::sqlite::create_tables(&tx)
+        .map_err(|e| fastn_issues::initialization::InitializeDBError::CreateTables { source: e })?;
+    process_fastn_ftd(&tx, &fastn_doc)?;
+    for file in static_files.iter() {
+        store_static_file(&tx, file)?;
+    }
+    tx.commit()
+        .map_err(|e| fastn_issues::initialization::InitializeDBError::Transaction { source: e })?;
+    Ok(())
 }
 
-/// initialise() is called on application start
+/// initialise() is called on application start, the returned package is kept up to date with
+/// `file_changed()`
 pub async fn initialize(
     i: impl fastn_package::initializer::Initializer,
-) -> Result<(), fastn_issues::initialization::InitializePackageError> {
+) -> Result<fastn_package::Package, fastn_issues::initialization::InitializePackageError> {
     fastn_package::FTD_CACHE
         .get_or_init(|| async { tokio::sync::RwLock::new(std::collections::HashMap::new()) })
         .await;
-    re_initialise(i).await
+    let package = fastn_package::Package::new()?;
+    re_initialise(&package, i).await?;
+    Ok(package)
 }
 
-async fn process_fastn_ftd(
+/// file_changed() is called when a file of the package is created, modified or deleted, `path`
+/// is relative to the package root
+pub async fn file_changed(
+    package: &fastn_package::Package,
     i: impl fastn_package::initializer::Initializer,
-    conn: rusqlite::Connection,
-) -> Result<(), fastn_issues::initialization::FastnFTDError> {
+    path: &str,
+) -> Result<(), fastn_issues::initialization::FileChangedError> {
+    if let Some(v) = fastn_package::FTD_CACHE.get() {
+        let mut v = v.write().await;
+        v.remove(path);
+    }
+
+    if path.eq("FASTN.ftd") {
+        let fastn_doc = read_fastn_ftd(&i).await?;
+        let mut conn = package.connection();
+        let tx = conn.transaction()?;
+        clear_fastn_ftd(&tx)?;
+        process_fastn_ftd(&tx, &fastn_doc)?;
+        tx.commit()?;
+        return Ok(());
+    }
+
+    if !is_static(path) {
+        return Ok(());
+    }
+    let file = match i.file_as_bytes(path).await {
+        Ok(content) => Some(StaticFile::new(path, content.as_slice())),
+        Err(fastn_issues::initialization::FileAsBytesError::FileDoesNotExist { .. }) => None,
+        Err(e) => {
+            return Err(fastn_issues::initialization::StaticFilesError::from(e).into());
+        }
+    };
+    let conn = package.connection();
+    match file {
+        Some(file) => store_static_file(&conn, &file)?,
+        None => {
+            conn.execute("DELETE FROM static_files WHERE name = ?1", [path])?;
+        }
+    }
+    Ok(())
+}
+
+async fn read_fastn_ftd(
+    i: &impl fastn_package::initializer::Initializer,
+) -> Result<ftd::ftd2021::p2::Document, fastn_issues::initialization::FastnFTDError> {
     let content = i.file_as_string("FASTN.ftd").await?;
-    let fastn_doc = fastn_package::old_fastn::parse_old_fastn(content.as_str())?;
-    store_name(conn, fastn_doc).await?;
+    Ok(fastn_package::old_fastn::parse_old_fastn(content.as_str())?)
+}
 
-    todo!()
+fn process_fastn_ftd(
+    conn: &rusqlite::Connection,
+    fastn_doc: &ftd::ftd2021::p2::Document,
+) -> Result<(), fastn_issues::initialization::FastnFTDError> {
+    store_name(conn, fastn_doc)?;
+    store_dependencies(conn, fastn_doc)?;
+    store_sitemap(conn, fastn_doc)?;
+    store_user_groups(conn, fastn_doc)?;
+    Ok(())
 }
 
-async fn store_name(
-    _conn: rusqlite::Connection,
-    fastn_doc: ftd::ftd2021::p2::Document,
+fn clear_fastn_ftd(conn: &rusqlite::Connection) -> Result<(), rusqlite::Error> {
+    conn.execute_batch(
+        "DELETE FROM main_package;
+        DELETE FROM translations;
+        DELETE FROM dependencies;
+        DELETE FROM sitemap;
+        DELETE FROM user_groups;
+        DELETE FROM user_group_identities;",
+    )
+}
+
+fn store_name(
+    conn: &rusqlite::Connection,
+    fastn_doc: &ftd::ftd2021::p2::Document,
 ) -> Result<(), fastn_issues::initialization::StoreNameError> {
-    let _name = fastn_package::old_fastn::get_name(fastn_doc)?;
-    // TODO: insert package name to main_package table
-    todo!()
+    let package = fastn_package::old_fastn::get_package(fastn_doc)?;
+    conn.execute(
+        "INSERT INTO main_package (name, language, translation_of, canonical_url)
+        VALUES (?1, ?2, ?3, ?4)",
+        rusqlite::params![
+            package.name,
+            package.language,
+            package.translation_of,
+            package.canonical_url
+        ],
+    )?;
+    for translation in package.translations.iter() {
+        conn.execute(
+            "INSERT OR IGNORE INTO translations (name) VALUES (?1)",
+            [translation],
+        )?;
+    }
+    Ok(())
+}
+
+fn get<T: serde::de::DeserializeOwned>(
+    fastn_doc: &ftd::ftd2021::p2::Document,
+    variable: &str,
+) -> Result<T, fastn_issues::initialization::FastnFTDError> {
+    fastn_doc.get(variable).map_err(|e| {
+        fastn_issues::initialization::FastnFTDError::CantGetVariable {
+            variable: variable.to_string(),
+            source: e,
+        }
+    })
+}
+
+fn store_error(
+    table: &str,
+) -> impl FnOnce(rusqlite::Error) -> fastn_issues::initialization::FastnFTDError + '_ {
+    move |e| fastn_issues::initialization::FastnFTDError::StorePackageData {
+        table: table.to_string(),
+        source: e,
+    }
+}
+
+fn store_dependencies(
+    conn: &rusqlite::Connection,
+    fastn_doc: &ftd::ftd2021::p2::Document,
+) -> Result<(), fastn_issues::initialization::FastnFTDError> {
+    let dependencies: Vec<fastn_package::old_fastn::DependencyTemp> =
+        get(fastn_doc, fastn_package::FASTN_DEPENDENCY_VARIABLE)?;
+    for dependency in dependencies.iter() {
+        let (name, alias) = dependency.name_and_alias();
+        conn.execute(
+            "INSERT OR REPLACE INTO dependencies (name, alias, version, endpoint, mount_point)
+            VALUES (?1, ?2, ?3, ?4, ?5)",
+            rusqlite::params![
+                name,
+                alias,
+                dependency.version,
+                dependency.endpoint,
+                dependency.mount_point
+            ],
+        )
+        .map_err(store_error("dependencies"))?;
+    }
+    Ok(())
+}
+
+fn store_sitemap(
+    conn: &rusqlite::Connection,
+    fastn_doc: &ftd::ftd2021::p2::Document,
+) -> Result<(), fastn_issues::initialization::FastnFTDError> {
+    let sitemap: Option<fastn_package::old_fastn::SitemapTemp> =
+        get(fastn_doc, fastn_package::FASTN_SITEMAP_VARIABLE)?;
+    if let Some(sitemap) = sitemap {
+        conn.execute("INSERT INTO sitemap (body) VALUES (?1)", [sitemap.body])
+            .map_err(store_error("sitemap"))?;
+    }
+    Ok(())
+}
+
+fn store_user_groups(
+    conn: &rusqlite::Connection,
+    fastn_doc: &ftd::ftd2021::p2::Document,
+) -> Result<(), fastn_issues::initialization::FastnFTDError> {
+    let user_groups: Vec<fastn_package::old_fastn::UserGroupTemp> =
+        get(fastn_doc, fastn_package::FASTN_USER_GROUP_VARIABLE)?;
+    for group in user_groups.iter() {
+        conn.execute(
+            "INSERT OR REPLACE INTO user_groups (id, title, description) VALUES (?1, ?2, ?3)",
+            rusqlite::params![group.id, group.title, group.description],
+        )
+        .map_err(store_error("user_groups"))?;
+        for (key, values) in group.identities.iter() {
+            let (key, excluded) = match key.strip_prefix('-') {
+                Some(key) => (key, true),
+                None => (key.as_str(), false),
+            };
+            for value in values.iter() {
+                conn.execute(
+                    "INSERT INTO user_group_identities (group_id, key, value, excluded)
+                    VALUES (?1, ?2, ?3, ?4)",
+                    rusqlite::params![group.id, key, value, excluded],
+                )
+                .map_err(store_error("user_group_identities"))?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Documents are not stored, every other file is served as is
+fn is_static(path: &str) -> bool {
+    !path.ends_with(".ftd") && !path.ends_with(".md")
+}
+
+struct StaticFile {
+    name: String,
+    content_type: String,
+    content_hash: String,
+}
+
+impl StaticFile {
+    fn new(name: &str, content: &[u8]) -> StaticFile {
+        use sha2::Digest;
+
+        StaticFile {
+            name: name.to_string(),
+            content_type: mime_guess::from_path(name)
+                .first_or_octet_stream()
+                .to_string(),
+            content_hash: format!("{:x}", sha2::Sha256::digest(content)),
+        }
+    }
+}
+
+async fn read_static_files(
+    i: &impl fastn_package::initializer::Initializer,
+) -> Result<Vec<StaticFile>, fastn_issues::initialization::StaticFilesError> {
+    let mut files = vec![];
+    for name in i.files().await? {
+        if !is_static(name.as_str()) {
+            continue;
+        }
+        let content = i.file_as_bytes(name.as_str()).await?;
+        files.push(StaticFile::new(name.as_str(), content.as_slice()));
+    }
+    Ok(files)
+}
+
+fn store_static_file(
+    conn: &rusqlite::Connection,
+    file: &StaticFile,
+) -> Result<(), fastn_issues::initialization::StaticFilesError> {
+    conn.execute(
+        "INSERT OR REPLACE INTO static_files (name, content_type, content_hash)
+        VALUES (?1, ?2, ?3)",
+        rusqlite::params![file.name, file.content_type, file.content_hash],
+    )
+    .map_err(
+        |e| fastn_issues::initialization::StaticFilesError::StoreStaticFile {
+            name: file.name.to_string(),
+            source: e,
+        },
+    )?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    const FASTN_FTD: &str = r#"-- import: fastn
+
+-- fastn.package: example.com
+language: en
+translation: hi.example.com
+
+-- fastn.dependency: fastn-community.github.io/doc-site as ds
+
+-- fastn.sitemap:
+
+# Home: /
+
+-- fastn.user-group: admins
+title: Admins
+email: a@example.com
+-email: b@example.com
+"#;
+
+    fn rows(package: &fastn_package::Package, query: &str) -> Vec<Vec<Option<String>>> {
+        let conn = package.connection();
+        let mut stmt = conn.prepare(query).unwrap();
+        let columns = stmt.column_count();
+        let rows = stmt
+            .query_map([], |row| (0..columns).map(|i| row.get(i)).collect())
+            .unwrap()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        rows
+    }
+
+    #[test]
+    fn store_fastn_ftd() {
+        let mut i = fastn_package::initializer::test::TestInitializer::default();
+        i.add_file("FASTN.ftd", FASTN_FTD);
+        let package = futures::executor::block_on(super::initialize(i)).unwrap();
+        let s = |v: &str| Some(v.to_string());
+
+        assert_eq!(
+            rows(&package, "SELECT name, language FROM main_package"),
+            vec![vec![s("example.com"), s("en")]]
+        );
+        assert_eq!(
+            rows(&package, "SELECT name FROM translations"),
+            vec![vec![s("hi.example.com")]]
+        );
+        assert_eq!(
+            rows(&package, "SELECT name, alias FROM dependencies"),
+            vec![vec![s("fastn-community.github.io/doc-site"), s("ds")]]
+        );
+        assert!(rows(&package, "SELECT body FROM sitemap")[0][0]
+            .as_ref()
+            .unwrap()
+            .contains("# Home: /"));
+        assert_eq!(
+            rows(&package, "SELECT id, title FROM user_groups"),
+            vec![vec![s("admins"), s("Admins")]]
+        );
+        assert_eq!(
+            rows(
+                &package,
+                "SELECT key, value, CAST(excluded AS TEXT) FROM user_group_identities ORDER BY value"
+            ),
+            vec![
+                vec![s("email"), s("a@example.com"), s("0")],
+                vec![s("email"), s("b@example.com"), s("1")],
+            ]
+        );
+    }
 }
@@ -4,12 +4,20 @@ pub trait Initializer {
         &self,
         path: &str,
     ) -> Result<String, fastn_issues::initialization::FileAsStringError>;
+
+    async fn file_as_bytes(
+        &self,
+        path: &str,
+    ) -> Result<Vec<u8>, fastn_issues::initialization::FileAsBytesError>;
+
+    /// All the files of the package, relative to its root, without the ignored ones
+    async fn files(&self) -> Result<Vec<String>, fastn_issues::initialization::ListFilesError>;
 }
 
 pub mod test {
     pub use super::Initializer;
     pub use fastn_issues::initialization::{
-        FastnFTDError, FileAsStringError, InitializePackageError,
+        FastnFTDError, FileAsBytesError, FileAsStringError, InitializePackageError, ListFilesError,
     };
 
     #[derive(Default)]
@@ -21,6 +29,10 @@ pub mod test {
         pub fn add_file(&mut self, name: &str, content: &str) {
             self.files.insert(name.to_string(), content.to_string());
         }
+
+        pub fn remove_file(&mut self, name: &str) {
+            self.files.remove(name);
+        }
     }
 
     #[async_trait::async_trait]
@@ -37,5 +49,24 @@ pub mod test {
             })?;
             Ok(content.to_string())
         }
+
+        async fn file_as_bytes(
+            &self,
+            path: &str,
+        ) -> Result<Vec<u8>, fastn_issues::initialization::FileAsBytesError> {
+            let content = self.files.get(path).ok_or_else(|| {
+                fastn_issues::initialization::FileAsBytesError::FileDoesNotExist {
+                    name: path.to_string(),
+                    source: std::io::Error::from(std::io::ErrorKind::NotFound),
+                }
+            })?;
+            Ok(content.as_bytes().to_vec())
+        }
+
+        async fn files(&self) -> Result<Vec<String>, fastn_issues::initialization::ListFilesError> {
+            let mut files = self.files.keys().cloned().collect::<Vec<_>>();
+            files.sort();
+            Ok(files)
+        }
     }
 }
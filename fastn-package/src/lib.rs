@@ -3,11 +3,16 @@ extern crate self as fastn_package;
 mod initialize;
 pub mod initializer;
 pub mod old_fastn;
+pub mod queries;
 pub(crate) mod sqlite;
 
-pub use initialize::initialize;
+pub use initialize::{file_changed, initialize, re_initialise};
+pub use sqlite::Package;
 
 const FASTN_PACKAGE_VARIABLE: &str = "fastn#package";
+const FASTN_DEPENDENCY_VARIABLE: &str = "fastn#dependency";
+const FASTN_SITEMAP_VARIABLE: &str = "fastn#sitemap";
+const FASTN_USER_GROUP_VARIABLE: &str = "fastn#user-group";
 
 static FTD_CACHE: tokio::sync::OnceCell<
     tokio::sync::RwLock<std::collections::HashMap<String, ftd::ast::AST>>,
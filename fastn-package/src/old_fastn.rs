@@ -46,15 +46,62 @@ pub fn parse_old_fastn(
 }
 
 pub fn get_name(
-    doc: ftd::ftd2021::p2::Document,
+    doc: &ftd::ftd2021::p2::Document,
 ) -> Result<String, fastn_issues::initialization::GetNameError> {
+    Ok(get_package(doc)?.name)
+}
+
+pub fn get_package(
+    doc: &ftd::ftd2021::p2::Document,
+) -> Result<PackageTemp, fastn_issues::initialization::GetNameError> {
     let op: Option<PackageTemp> = doc.get(fastn_package::FASTN_PACKAGE_VARIABLE)?;
     match op {
-        Some(p) => Ok(p.name),
+        Some(p) => Ok(p),
         None => Err(fastn_issues::initialization::GetNameError::PackageIsNone),
     }
 }
 
+/// DependencyTemp is read from `fastn.dependency` in the FASTN.ftd file, `name` may be followed by
+/// `as <alias>`
+#[derive(serde::Deserialize, Debug, Clone)]
+pub struct DependencyTemp {
+    pub name: String,
+    pub version: Option<String>,
+    pub endpoint: Option<String>,
+    #[serde(rename = "mount-point")]
+    pub mount_point: Option<String>,
+}
+
+impl DependencyTemp {
+    /// The package name and the alias of the dependency
+    pub fn name_and_alias(&self) -> (&str, Option<&str>) {
+        match self.name.split_once(" as ") {
+            Some((name, alias)) => (name.trim(), Some(alias.trim())),
+            None => (self.name.trim(), None),
+        }
+    }
+}
+
+/// SitemapTemp is read from `fastn.sitemap` in the FASTN.ftd file, the body is parsed by
+/// `fastn_core::sitemap::Sitemap`
+#[derive(serde::Deserialize, Debug, Clone)]
+pub struct SitemapTemp {
+    #[serde(rename = "sitemap-body")]
+    pub body: String,
+}
+
+/// UserGroupTemp is read from `fastn.user-group` in the FASTN.ftd file, every header other than
+/// `id`, `title` and `description` is a list of identities, or of identities excluded from the
+/// group if it starts with `-`
+#[derive(serde::Deserialize, Debug, Clone)]
+pub struct UserGroupTemp {
+    pub id: String,
+    pub title: Option<String>,
+    pub description: Option<String>,
+    #[serde(flatten)]
+    pub identities: std::collections::BTreeMap<String, Vec<String>>,
+}
+
 /// Backend Header is a struct that is used to read and store the backend-header from the FASTN.ftd file
 #[derive(serde::Deserialize, Debug, Clone)]
 pub struct BackendHeader {
@@ -64,6 +111,167 @@ pub struct BackendHeader {
     pub header_value: String,
 }
 
+/// BackendCapabilities is read from `fastn.backend-capabilities` in the FASTN.ftd file and limits
+/// what the WASM backend of the package may do while handling a request
+#[derive(serde::Deserialize, Debug, Clone)]
+pub struct BackendCapabilities {
+    /// Hosts the backend may send HTTP requests to
+    #[serde(rename = "allowed-hosts")]
+    pub allowed_hosts: Vec<String>,
+    /// SQLite databases, relative to the package root, the backend may query
+    pub databases: Vec<String>,
+    /// Instructions the backend may execute per request
+    pub fuel: i64,
+    /// Seconds a request may take
+    pub timeout: i64,
+    /// Memory in MiB the backend may grow to
+    pub memory: i64,
+}
+
+impl Default for BackendCapabilities {
+    fn default() -> Self {
+        BackendCapabilities {
+            allowed_hosts: vec![],
+            databases: vec![],
+            fuel: 1_000_000_000,
+            timeout: 10,
+            memory: 64,
+        }
+    }
+}
+
+/// ProxyRule is read from `fastn.proxy` in the FASTN.ftd file and controls how requests whose
+/// path starts with `prefix` are forwarded to the upstream endpoint
+#[derive(serde::Deserialize, Debug, Clone)]
+pub struct ProxyRule {
+    pub prefix: String,
+    /// Upstream to forward to instead of the `endpoint` of the package
+    pub endpoint: Option<String>,
+    /// Replaces `prefix` in the forwarded path
+    pub rewrite: Option<String>,
+    /// If not empty, only these client headers are forwarded
+    #[serde(rename = "allow-headers")]
+    pub allow_headers: Vec<String>,
+    /// Client headers that are never forwarded
+    #[serde(rename = "deny-headers")]
+    pub deny_headers: Vec<String>,
+    /// Forward the identities of the logged in user as signed headers
+    #[serde(rename = "forward-identity")]
+    pub forward_identity: bool,
+    /// Forward the user groups the user belongs to as signed headers
+    #[serde(rename = "forward-groups")]
+    pub forward_groups: bool,
+    /// Seconds to wait for the upstream
+    pub timeout: Option<i64>,
+    /// Stream the upstream response body instead of buffering it
+    pub stream: bool,
+}
+
+/// The rest of `path` if it is `route` or a path under it: `/api/users` is under `/api/` but
+/// `/apis` is not
+fn strip_route<'a>(route: &str, path: &'a str) -> Option<&'a str> {
+    let route = route.trim_matches('/');
+    let path = path.trim_start_matches('/');
+    if route.is_empty() {
+        return Some(path);
+    }
+    match path.strip_prefix(route) {
+        Some(rest) if rest.is_empty() || rest.starts_with('/') => Some(rest),
+        _ => None,
+    }
+}
+
+impl ProxyRule {
+    pub fn matches(&self, path: &str) -> bool {
+        strip_route(self.prefix.as_str(), path).is_some()
+    }
+
+    /// Applies `rewrite` to `path`, which is returned as is if it is not under `prefix`
+    pub fn rewrite_path(&self, path: &str) -> String {
+        let (rewrite, rest) = match (
+            self.rewrite.as_ref(),
+            strip_route(self.prefix.as_str(), path),
+        ) {
+            (Some(rewrite), Some(rest)) => (rewrite, rest),
+            _ => return path.to_string(),
+        };
+        format!(
+            "/{}",
+            format!("{}{}", rewrite.trim_matches('/'), rest).trim_start_matches('/')
+        )
+    }
+
+    pub fn allows_header(&self, name: &str) -> bool {
+        (self.allow_headers.is_empty()
+            || self
+                .allow_headers
+                .iter()
+                .any(|h| h.trim().eq_ignore_ascii_case(name)))
+            && !self
+                .deny_headers
+                .iter()
+                .any(|h| h.trim().eq_ignore_ascii_case(name))
+    }
+}
+
+/// Form is read from `fastn.form` in the FASTN.ftd file and declares a route which accepts form
+/// submissions, stores them in a SQLite `table` or passes them to a `wasm` handler
+#[derive(serde::Deserialize, Debug, Clone)]
+pub struct Form {
+    pub route: String,
+    /// SQLite database, relative to the package root, containing `table`
+    pub database: Option<String>,
+    pub table: Option<String>,
+    /// WASM module, relative to the package root, handling the submission
+    pub wasm: Option<String>,
+    /// Fields that are accepted, others are dropped
+    #[serde(rename = "field")]
+    pub fields: Vec<String>,
+    pub required: Vec<String>,
+    /// `<field>=<regex>` the value of the field has to match
+    #[serde(rename = "pattern")]
+    pub patterns: Vec<String>,
+    /// `<field>=<number of characters>`
+    #[serde(rename = "max-length")]
+    pub max_lengths: Vec<String>,
+    /// Page to send the user to after a successful submission
+    pub redirect: Option<String>,
+    /// Reload the page after a successful submission
+    pub reload: bool,
+}
+
+impl Form {
+    pub fn matches(&self, path: &str) -> bool {
+        self.route.trim_matches('/').eq(path.trim_matches('/'))
+    }
+}
+
+/// Limit is read from `fastn.limit` in the FASTN.ftd file and limits the rate of requests, and
+/// the size of their body, for paths starting with `route`
+#[derive(serde::Deserialize, Debug, Clone)]
+pub struct Limit {
+    pub route: String,
+    /// Requests allowed in `window`, unlimited if not set
+    pub requests: Option<i64>,
+    /// Seconds
+    pub window: i64,
+    /// `ip`, or `identity` to count the requests of logged in users by their identity
+    pub per: String,
+    /// Bytes
+    #[serde(rename = "max-body")]
+    pub max_body: Option<i64>,
+}
+
+impl Limit {
+    pub fn matches(&self, path: &str) -> bool {
+        strip_route(self.route.as_str(), path).is_some()
+    }
+
+    pub fn per_identity(&self) -> bool {
+        self.per.eq("identity")
+    }
+}
+
 /// PackageTemp is a struct that is used for mapping the `fastn.package` data in FASTN.ftd file. It is
 /// not used elsewhere in program, it is immediately converted to `fastn_core::Package` struct during
 /// deserialization process
@@ -96,3 +304,79 @@ pub struct PackageTemp {
     #[serde(rename = "icon")]
     pub icon: Option<ftd::ImageSrc>,
 }
+
+#[cfg(test)]
+mod tests {
+    fn rule(prefix: &str, rewrite: Option<&str>) -> super::ProxyRule {
+        super::ProxyRule {
+            prefix: prefix.to_string(),
+            endpoint: None,
+            rewrite: rewrite.map(ToString::to_string),
+            allow_headers: vec![],
+            deny_headers: vec![],
+            forward_identity: false,
+            forward_groups: false,
+            timeout: None,
+            stream: false,
+        }
+    }
+
+    #[test]
+    fn proxy_rule_matches() {
+        let api = rule("/api/", None);
+        assert!(api.matches("/api"));
+        assert!(api.matches("/api/"));
+        assert!(api.matches("api/users/1"));
+        assert!(!api.matches("/apis"));
+        assert!(!api.matches("/apiary/"));
+        assert!(!api.matches("/"));
+        assert!(rule("/", None).matches("/anything/"));
+    }
+
+    #[test]
+    fn limit_matches() {
+        let limit = super::Limit {
+            route: "/api/".to_string(),
+            requests: None,
+            window: 60,
+            per: "ip".to_string(),
+            max_body: None,
+        };
+        assert!(limit.matches("/api/"));
+        assert!(limit.matches("/api/upload/"));
+        assert!(!limit.matches("/apis/"));
+    }
+
+    #[test]
+    fn proxy_rule_rewrite_path() {
+        let v2 = rule("/api/", Some("/v2/"));
+        assert_eq!(v2.rewrite_path("/api/users/"), "/v2/users/");
+        assert_eq!(v2.rewrite_path("/api/"), "/v2/");
+        assert_eq!(v2.rewrite_path("/api"), "/v2");
+        assert_eq!(v2.rewrite_path("/apis/"), "/apis/");
+
+        let root = rule("/api/", Some("/"));
+        assert_eq!(root.rewrite_path("/api/users/"), "/users/");
+        assert_eq!(root.rewrite_path("/api/"), "/");
+
+        assert_eq!(
+            rule("/api/", None).rewrite_path("/api/users/"),
+            "/api/users/"
+        );
+    }
+
+    #[test]
+    fn proxy_rule_allows_header() {
+        let mut r = rule("/api/", None);
+        assert!(r.allows_header("cookie"));
+
+        r.deny_headers = vec!["Cookie".to_string()];
+        assert!(!r.allows_header("cookie"));
+        assert!(r.allows_header("accept"));
+
+        r.allow_headers = vec!["accept".to_string(), " Cookie ".to_string()];
+        assert!(r.allows_header("Accept"));
+        assert!(!r.allows_header("cookie"));
+        assert!(!r.allows_header("authorization"));
+    }
+}
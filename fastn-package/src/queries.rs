@@ -0,0 +1,38 @@
+//! Reads of a package loaded by `fastn_package::initialize()`, kept up to date by
+//! `fastn_package::file_changed()`.
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct StaticFile {
+    pub name: String,
+    pub content_type: String,
+    /// Hex encoded sha256 of the content
+    pub content_hash: Option<String>,
+}
+
+pub fn name(
+    package: &fastn_package::Package,
+) -> Result<String, fastn_issues::initialization::QueryError> {
+    Ok(package
+        .connection()
+        .query_row("SELECT name FROM main_package", [], |row| row.get(0))?)
+}
+
+/// `name` is relative to the package root
+pub fn static_file(
+    package: &fastn_package::Package,
+    name: &str,
+) -> Result<Option<StaticFile>, fastn_issues::initialization::QueryError> {
+    let conn = package.connection();
+    let mut stmt =
+        conn.prepare("SELECT content_type, content_hash FROM static_files WHERE name = ?1")?;
+    let mut rows = stmt.query([name])?;
+    let file = match rows.next()? {
+        Some(row) => Some(StaticFile {
+            name: name.to_string(),
+            content_type: row.get(0)?,
+            content_hash: row.get(1)?,
+        }),
+        None => None,
+    };
+    Ok(file)
+}
@@ -1,19 +1,33 @@
-const DB_URL: &str = "file::memory:?cache=shared";
-
-fn rw_flags() -> rusqlite::OpenFlags {
-    rusqlite::OpenFlags::SQLITE_OPEN_READ_WRITE | rusqlite::OpenFlags::SQLITE_OPEN_URI
+/// A package loaded by `fastn_package::initialize()`. Its data is in an in-memory database owned
+/// by it, so every package, and every test, has a database of its own.
+#[derive(Debug)]
+pub struct Package {
+    conn: std::sync::Mutex<rusqlite::Connection>,
 }
 
-// fn ro_flags() -> rusqlite::OpenFlags {
-//     rusqlite::OpenFlags::SQLITE_OPEN_READ_ONLY | rusqlite::OpenFlags::SQLITE_OPEN_URI
-// }
+impl Package {
+    /// A package with empty tables
+    pub(crate) fn new() -> Result<Package, fastn_issues::initialization::InitializeDBError> {
+        let conn = rusqlite::Connection::open_in_memory().map_err(|e| {
+            fastn_issues::initialization::InitializeDBError::OpenDBConnection { source: e }
+        })?;
+        create_tables(&conn).map_err(|e| {
+            fastn_issues::initialization::InitializeDBError::CreateTables { source: e }
+        })?;
+        Ok(Package {
+            conn: std::sync::Mutex::new(conn),
+        })
+    }
+
+    /// The locked connection to the database of the package
+    pub(crate) fn connection(&self) -> std::sync::MutexGuard<'_, rusqlite::Connection> {
+        // a panic while holding the lock can not leave the connection in a bad state, sqlite rolls
+        // back unfinished transactions
+        self.conn.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
 
-pub(crate) fn initialize_db(
-) -> Result<rusqlite::Connection, fastn_issues::initialization::InitializeDBError> {
-    let conn = rusqlite::Connection::open_with_flags(DB_URL, rw_flags()).map_err(|e| {
-        fastn_issues::initialization::InitializeDBError::OpenDBConnection { source: e }
-    })?;
+/// Creates empty tables, dropping the existing ones
+pub(crate) fn create_tables(conn: &rusqlite::Connection) -> Result<(), rusqlite::Error> {
     conn.execute_batch(include_str!("../create-db.sql"))
-        .map_err(|e| fastn_issues::initialization::InitializeDBError::CreateTables { source: e })?;
-    Ok(conn)
 }
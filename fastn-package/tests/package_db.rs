@@ -0,0 +1,145 @@
+const FASTN_FTD: &str = r#"-- import: fastn
+
+-- fastn.package: example.com
+language: en
+translation: hi.example.com
+
+-- fastn.dependency: fastn-community.github.io/doc-site as ds
+
+-- fastn.sitemap:
+
+# Home: /
+
+-- fastn.user-group: admins
+title: Admins
+email: a@example.com
+-email: b@example.com
+"#;
+
+fn initializer(files: &[(&str, &str)]) -> fastn_package::initializer::test::TestInitializer {
+    let mut i = fastn_package::initializer::test::TestInitializer::default();
+    for (name, content) in files {
+        i.add_file(name, content);
+    }
+    i
+}
+
+async fn package() -> fastn_package::Package {
+    fastn_package::initialize(initializer(&[
+        ("FASTN.ftd", FASTN_FTD),
+        ("index.ftd", "-- ftd.text: hello"),
+        ("logo.svg", "<svg/>"),
+    ]))
+    .await
+    .unwrap()
+}
+
+#[test]
+fn package_data() {
+    futures::executor::block_on(async {
+        let package = package().await;
+
+        assert_eq!(
+            fastn_package::queries::name(&package).unwrap(),
+            "example.com"
+        );
+        let logo = fastn_package::queries::static_file(&package, "logo.svg")
+            .unwrap()
+            .unwrap();
+        assert_eq!(logo.content_type, "image/svg+xml");
+        assert!(logo.content_hash.is_some());
+        // documents are not static files
+        assert!(fastn_package::queries::static_file(&package, "index.ftd")
+            .unwrap()
+            .is_none());
+    });
+}
+
+#[test]
+fn fastn_ftd_changed() {
+    futures::executor::block_on(async {
+        let package = package().await;
+
+        fastn_package::file_changed(
+            &package,
+            initializer(&[(
+                "FASTN.ftd",
+                FASTN_FTD.replace("example.com", "example.org").as_str(),
+            )]),
+            "FASTN.ftd",
+        )
+        .await
+        .unwrap();
+        assert_eq!(
+            fastn_package::queries::name(&package).unwrap(),
+            "example.org"
+        );
+
+        // a broken FASTN.ftd leaves the package as it was
+        assert!(fastn_package::file_changed(
+            &package,
+            initializer(&[("FASTN.ftd", "-- fastn.package:")]),
+            "FASTN.ftd"
+        )
+        .await
+        .is_err());
+        assert_eq!(
+            fastn_package::queries::name(&package).unwrap(),
+            "example.org"
+        );
+    });
+}
+
+#[test]
+fn static_file_changed() {
+    futures::executor::block_on(async {
+        let package = package().await;
+        let hash = |package| {
+            fastn_package::queries::static_file(package, "logo.svg")
+                .unwrap()
+                .map(|f| f.content_hash.unwrap())
+        };
+        let before = hash(&package).unwrap();
+
+        fastn_package::file_changed(
+            &package,
+            initializer(&[("logo.svg", "<svg></svg>")]),
+            "logo.svg",
+        )
+        .await
+        .unwrap();
+        assert_ne!(hash(&package).unwrap(), before);
+
+        fastn_package::file_changed(&package, initializer(&[]), "logo.svg")
+            .await
+            .unwrap();
+        assert!(hash(&package).is_none());
+    });
+}
+
+#[test]
+fn packages_are_separate() {
+    futures::executor::block_on(async {
+        let example = package().await;
+        let other = fastn_package::initialize(initializer(&[(
+            "FASTN.ftd",
+            "-- import: fastn\n\n-- fastn.package: other.com\n",
+        )]))
+        .await
+        .unwrap();
+
+        assert_eq!(
+            fastn_package::queries::name(&example).unwrap(),
+            "example.com"
+        );
+        assert_eq!(fastn_package::queries::name(&other).unwrap(), "other.com");
+        assert!(fastn_package::queries::static_file(&other, "logo.svg")
+            .unwrap()
+            .is_none());
+
+        fastn_package::re_initialise(&other, initializer(&[("FASTN.ftd", FASTN_FTD)]))
+            .await
+            .unwrap();
+        assert_eq!(fastn_package::queries::name(&other).unwrap(), "example.com");
+    });
+}
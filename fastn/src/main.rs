@@ -78,6 +78,12 @@ async fn fastn_core_commands(matches: &clap::ArgMatches) -> fastn_core::Result<(
         Some((fastn_core::commands::sync_status::COMMAND, matches)) => {
             return fastn_core::commands::sync_status::handle_command(matches).await;
         }
+        Some((fastn_core::commands::translate::COMMAND, matches)) => {
+            return fastn_core::commands::translate::handle_command(matches).await;
+        }
+        Some((fastn_core::commands::design_tokens::COMMAND, matches)) => {
+            return fastn_core::commands::design_tokens::handle_command(matches).await;
+        }
         _ => {}
     }
 
@@ -112,6 +118,45 @@ async fn fastn_core_commands(matches: &clap::ArgMatches) -> fastn_core::Result<(
             fastn_core::utils::enable_parse_caching(true);
         }
 
+        let tls = match (serve.value_of_("tls-cert"), serve.value_of_("tls-key")) {
+            (Some(cert), Some(key)) => Some(fastn_core::Tls::Files {
+                cert: cert.into(),
+                key: key.into(),
+            }),
+            _ if serve.get_flag("tls-self-signed") => Some(fastn_core::Tls::SelfSigned),
+            _ => None,
+        };
+        let rate_limit = serve
+            .value_of_("rate-limit")
+            .map(|r| match r.parse::<u32>() {
+                Ok(v) => v,
+                Err(_) => {
+                    eprintln!("Provided rate limit {} is not a valid number.", r.red());
+                    std::process::exit(1);
+                }
+            });
+        let max_body_size = match serve
+            .value_of_("max-body-size")
+            .unwrap_or("262144")
+            .parse::<usize>()
+        {
+            Ok(v) => v,
+            Err(_) => {
+                eprintln!("Provided max body size is not a valid number.");
+                std::process::exit(1);
+            }
+        };
+        let access_log = match serve.value_of_("access-log").unwrap_or("combined") {
+            "off" => None,
+            format => match format.parse::<fastn_core::AccessLogFormat>() {
+                Ok(format) => Some(format),
+                Err(e) => {
+                    eprintln!("{}", e.to_string().red());
+                    std::process::exit(1);
+                }
+            },
+        };
+
         return fastn_core::listen(
             bind.as_str(),
             port,
@@ -121,6 +166,10 @@ async fn fastn_core_commands(matches: &clap::ArgMatches) -> fastn_core::Result<(
             inline_js,
             external_css,
             inline_css,
+            tls,
+            access_log,
+            rate_limit,
+            max_body_size,
         )
         .await;
     }
@@ -181,14 +230,33 @@ async fn fastn_core_commands(matches: &clap::ArgMatches) -> fastn_core::Result<(
             .add_external_css(external_css)
             .add_inline_css(inline_css);
 
-        return fastn_core::build(
+        let base_url = build.value_of_("base").unwrap_or("/");
+        let multilingual = build.get_flag("multilingual");
+        if multilingual {
+            config.multilingual_base_url = Some(base_url.to_string());
+        }
+
+        fastn_core::build(
             &mut config,
             build.value_of_("file"), // TODO: handle more than one files
-            build.value_of_("base").unwrap_or("/"),
+            base_url,
             build.get_flag("ignore-failed"),
             build.get_flag("test"),
+            build.get_flag("compress"),
         )
-        .await;
+        .await?;
+
+        if multilingual {
+            fastn_core::build_translations(
+                &config,
+                base_url,
+                build.get_flag("ignore-failed"),
+                build.get_flag("test"),
+                build.get_flag("compress"),
+            )
+            .await?;
+        }
+        return Ok(());
     }
 
     if let Some(mark_resolve) = matches.subcommand_matches("mark-resolved") {
@@ -231,8 +299,17 @@ async fn fastn_core_commands(matches: &clap::ArgMatches) -> fastn_core::Result<(
         // TODO: handle multiple files
         return fastn_core::status(&config, status.value_of_("file")).await;
     }
-    if matches.subcommand_matches("translation-status").is_some() {
-        return fastn_core::translation_status(&config).await;
+    if let Some(translation_status) = matches.subcommand_matches("translation-status") {
+        let fail_above = translation_status
+            .value_of_("fail-above")
+            .map(|v| v.parse::<usize>())
+            .transpose()?;
+        return fastn_core::translation_status(
+            &config,
+            translation_status.value_of_("format").eq(&Some("json")),
+            fail_above,
+        )
+        .await;
     }
     if let Some(diff) = matches.subcommand_matches("diff") {
         let all = diff.get_flag("all");
@@ -294,6 +371,8 @@ fn app(version: &'static str) -> clap::Command {
                 .arg(clap::arg!(-b --base [BASE] "The base path.").default_value("/"))
                 .arg(clap::arg!(--"ignore-failed" "Ignore failed files."))
                 .arg(clap::arg!(--"test" "Use for test"))
+                .arg(clap::arg!(--"compress" "Also write brotli (.br) and gzip (.gz) compressed copies of text files"))
+                .arg(clap::arg!(--"multilingual" "Also build the translations of the package into <base>/<language>/"))
                 .arg(clap::arg!(--"external-js" <URL> "Script added in ftd files")
                     .action(clap::ArgAction::Append))
                 .arg(clap::arg!(--"js" <URL> "Script text added in ftd files")
@@ -397,8 +476,14 @@ fn app(version: &'static str) -> clap::Command {
         .subcommand(
             clap::Command::new("translation-status")
                 .about("Show the translation status of files in this fastn package")
+                .arg(clap::arg!(--format <FORMAT> "Output format")
+                    .value_parser(["text", "json"])
+                    .default_value("text"))
+                .arg(clap::arg!(--"fail-above" <COUNT> "Exit with an error if more than these many documents are missing or out-dated"))
                 .hide(true) // hidden since the feature is not being released yet.
         )
+        .subcommand(fastn_core::commands::translate::command())
+        .subcommand(fastn_core::commands::design_tokens::command())
         .subcommand(
             clap::Command::new("diff")
                 .about("Show un-synced changes to files in this fastn package")
@@ -451,6 +536,15 @@ mod sub_command {
             .arg(clap::arg!(--port <PORT> "The port to listen on [default: first available port starting 8000]"))
             .arg(clap::arg!(--bind <ADDRESS> "The address to bind to").default_value("127.0.0.1"))
             .arg(clap::arg!(--"cached-parse" "Use cached parser"))
+            .arg(clap::arg!(--"tls-cert" <PATH> "PEM encoded certificate chain, serves over https (and http/2)").requires("tls-key"))
+            .arg(clap::arg!(--"tls-key" <PATH> "PEM encoded private key of --tls-cert").requires("tls-cert"))
+            .arg(clap::arg!(--"tls-self-signed" "Serve over https with a self-signed certificate, for development only")
+                .conflicts_with("tls-cert"))
+            .arg(clap::arg!(--"rate-limit" <REQUESTS> "Requests per minute allowed from an ip, for routes without a `fastn.limit`"))
+            .arg(clap::arg!(--"max-body-size" <BYTES> "Largest request body accepted, for routes without a `fastn.limit`").default_value("262144"))
+            .arg(clap::arg!(--"access-log" <FORMAT> "Format of the access log written to stdout")
+                .value_parser(["combined", "json", "off"])
+                .default_value("combined"))
             .arg(clap::arg!(--edition <EDITION> "The FTD edition"))
             .arg(clap::arg!(--"external-js" <URL> "Script added in ftd files")
                 .action(clap::ArgAction::Append))
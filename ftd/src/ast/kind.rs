@@ -386,6 +386,39 @@ impl VariableValue {
         }
     }
 
+    /// Entries of a `map<K, V>` literal, each header of the variable is one
+    /// entry, e.g. `alice: 10` under `-- map<string, integer> scores:`.
+    pub(crate) fn into_map(self, doc_name: &str) -> ftd::ast::Result<Vec<(String, VariableValue)>> {
+        match self {
+            VariableValue::Optional { value, .. } if value.is_none() => Ok(vec![]),
+            VariableValue::Record {
+                caption,
+                headers,
+                body,
+                values,
+                ..
+            } if caption.is_none() && body.is_none() && values.is_empty() => {
+                let mut entries = vec![];
+                for header in headers.0 {
+                    if header.condition.is_some() {
+                        return ftd::ast::parse_error(
+                            format!("Map entry `{}` can't have a condition", header.key),
+                            doc_name,
+                            header.line_number,
+                        );
+                    }
+                    entries.push((header.key, header.value));
+                }
+                Ok(entries)
+            }
+            t => ftd::ast::parse_error(
+                format!("Expected map, found: `{:?}`", t),
+                doc_name,
+                t.line_number(),
+            ),
+        }
+    }
+
     pub(crate) fn is_record(&self) -> bool {
         matches!(self, VariableValue::Record { .. })
     }
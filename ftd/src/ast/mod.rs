@@ -20,7 +20,8 @@ pub use component::{
 pub use function::Function;
 pub use import::{Export, Exposing, Import};
 pub use kind::{
-    Condition, HeaderValues, ValueSource, VariableKind, VariableModifier, VariableValue, NULL,
+    Condition, HeaderValue, HeaderValues, ValueSource, VariableKind, VariableModifier,
+    VariableValue, NULL,
 };
 pub use main::AST;
 pub use or_type::{OrType, OrTypeVariant};
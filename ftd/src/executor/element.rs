@@ -3,6 +3,7 @@ pub enum Element {
     Row(Row),
     Column(Column),
     Container(ContainerElement),
+    Grid(Grid),
     Document(Document),
     Text(Text),
     Integer(Text),
@@ -26,6 +27,7 @@ impl Element {
             Element::Row(r) => Some(&r.common),
             Element::Column(c) => Some(&c.common),
             Element::Container(e) => Some(&e.common),
+            Element::Grid(g) => Some(&g.common),
             Element::Text(t) => Some(&t.common),
             Element::Integer(i) => Some(&i.common),
             Element::Boolean(b) => Some(&b.common),
@@ -48,6 +50,7 @@ impl Element {
         match self {
             Element::Row(r) => Some(&mut r.container.children),
             Element::Column(c) => Some(&mut c.container.children),
+            Element::Grid(g) => Some(&mut g.children),
             Element::Document(d) => Some(&mut d.children),
             Element::RawElement(r) => Some(&mut r.children),
             _ => None,
@@ -63,6 +66,7 @@ impl Element {
             Element::Row(r) => r.common.line_number,
             Element::Column(c) => c.common.line_number,
             Element::Container(e) => e.common.line_number,
+            Element::Grid(g) => g.common.line_number,
             Element::Document(d) => d.line_number,
             Element::Text(t) => t.common.line_number,
             Element::Integer(i) => i.common.line_number,
@@ -136,6 +140,21 @@ pub struct ContainerElement {
     pub display: ftd::executor::Value<Option<ftd::executor::Display>>,
 }
 
+/// `ftd.grid`, a CSS grid. `columns` is a shorthand for `template-columns`
+/// with that many equal columns, `template-columns` wins if both are given.
+#[derive(serde::Deserialize, Debug, Default, PartialEq, Clone, serde::Serialize)]
+pub struct Grid {
+    pub common: Common,
+    pub children: Vec<ftd::executor::Element>,
+    pub columns: ftd::executor::Value<Option<ftd::executor::ResponsiveColumns>>,
+    pub template_columns: ftd::executor::Value<Option<String>>,
+    pub template_rows: ftd::executor::Value<Option<String>>,
+    pub areas: ftd::executor::Value<Option<String>>,
+    pub gap: ftd::executor::Value<Option<ftd::executor::Length>>,
+    pub row_gap: ftd::executor::Value<Option<ftd::executor::Length>>,
+    pub column_gap: ftd::executor::Value<Option<ftd::executor::Length>>,
+}
+
 #[derive(serde::Deserialize, Debug, Default, PartialEq, Clone, serde::Serialize)]
 pub struct HTMLData {
     pub title: ftd::executor::Value<Option<String>>,
@@ -729,6 +748,9 @@ pub struct Common {
     pub background: ftd::executor::Value<Option<ftd::executor::Background>>,
     pub color: ftd::executor::Value<Option<ftd::executor::Color>>,
     pub align_self: ftd::executor::Value<Option<ftd::executor::AlignSelf>>,
+    pub grid_column: ftd::executor::Value<Option<String>>,
+    pub grid_row: ftd::executor::Value<Option<String>>,
+    pub grid_area: ftd::executor::Value<Option<String>>,
     pub data_id: String,
     pub line_number: usize,
     pub condition: Option<ftd::interpreter::Expression>,
@@ -748,6 +770,9 @@ pub struct Common {
     pub border_style_top: ftd::executor::Value<Option<ftd::executor::BorderStyle>>,
     pub border_style_bottom: ftd::executor::Value<Option<ftd::executor::BorderStyle>>,
     pub shadow: ftd::executor::Value<Option<ftd::executor::Shadow>>,
+    pub transform: ftd::executor::Value<Option<ftd::executor::Transform>>,
+    pub transition: ftd::executor::Value<Option<ftd::executor::Transition>>,
+    pub animation: ftd::executor::Value<Option<ftd::executor::Animation>>,
     pub device: Option<ftd::executor::Device>,
 }
 
@@ -1316,6 +1341,97 @@ pub fn container_element_from_properties(
     })
 }
 
+#[allow(clippy::too_many_arguments)]
+pub fn grid_from_properties(
+    properties: &[ftd::interpreter::Property],
+    events: &[ftd::interpreter::Event],
+    arguments: &[ftd::interpreter::Argument],
+    condition: &Option<ftd::interpreter::Expression>,
+    doc: &mut ftd::executor::TDoc,
+    local_container: &[usize],
+    line_number: usize,
+    children: Vec<Element>,
+    inherited_variables: &ftd::VecMap<(String, Vec<usize>)>,
+    device: Option<ftd::executor::Device>,
+) -> ftd::executor::Result<Grid> {
+    let common = common_from_properties(
+        properties,
+        events,
+        arguments,
+        condition,
+        doc,
+        local_container,
+        line_number,
+        inherited_variables,
+        "ftd#grid",
+        device,
+    )?;
+    Ok(Grid {
+        common,
+        children,
+        columns: ftd::executor::ResponsiveColumns::optional_responsive_columns(
+            properties,
+            arguments,
+            doc,
+            line_number,
+            "columns",
+            inherited_variables,
+            "ftd#grid",
+        )?,
+        template_columns: ftd::executor::value::optional_string(
+            "template-columns",
+            "ftd#grid",
+            properties,
+            arguments,
+            doc,
+            line_number,
+        )?,
+        template_rows: ftd::executor::value::optional_string(
+            "template-rows",
+            "ftd#grid",
+            properties,
+            arguments,
+            doc,
+            line_number,
+        )?,
+        areas: ftd::executor::value::optional_string(
+            "areas",
+            "ftd#grid",
+            properties,
+            arguments,
+            doc,
+            line_number,
+        )?,
+        gap: ftd::executor::Length::optional_length(
+            properties,
+            arguments,
+            doc,
+            line_number,
+            "gap",
+            inherited_variables,
+            "ftd#grid",
+        )?,
+        row_gap: ftd::executor::Length::optional_length(
+            properties,
+            arguments,
+            doc,
+            line_number,
+            "row-gap",
+            inherited_variables,
+            "ftd#grid",
+        )?,
+        column_gap: ftd::executor::Length::optional_length(
+            properties,
+            arguments,
+            doc,
+            line_number,
+            "column-gap",
+            inherited_variables,
+            "ftd#grid",
+        )?,
+    })
+}
+
 #[allow(clippy::too_many_arguments)]
 pub fn rive_from_properties(
     properties: &[ftd::interpreter::Property],
@@ -2121,6 +2237,30 @@ pub fn common_from_properties(
             inherited_variables,
             component_name,
         )?,
+        grid_column: ftd::executor::value::optional_string(
+            "grid-column",
+            component_name,
+            properties,
+            arguments,
+            doc,
+            line_number,
+        )?,
+        grid_row: ftd::executor::value::optional_string(
+            "grid-row",
+            component_name,
+            properties,
+            arguments,
+            doc,
+            line_number,
+        )?,
+        grid_area: ftd::executor::value::optional_string(
+            "grid-area",
+            component_name,
+            properties,
+            arguments,
+            doc,
+            line_number,
+        )?,
         overflow: ftd::executor::Overflow::optional_overflow(
             properties,
             arguments,
@@ -2183,6 +2323,34 @@ pub fn common_from_properties(
             inherited_variables,
             component_name,
         )?,
+
+        transform: ftd::executor::Transform::optional_transform(
+            properties,
+            arguments,
+            doc,
+            line_number,
+            "transform",
+            inherited_variables,
+            component_name,
+        )?,
+        transition: ftd::executor::Transition::optional_transition(
+            properties,
+            arguments,
+            doc,
+            line_number,
+            "transition",
+            inherited_variables,
+            component_name,
+        )?,
+        animation: ftd::executor::Animation::optional_animation(
+            properties,
+            arguments,
+            doc,
+            line_number,
+            "animation",
+            inherited_variables,
+            component_name,
+        )?,
     })
 }
 
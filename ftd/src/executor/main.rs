@@ -23,6 +23,7 @@ pub struct RT {
     pub js: std::collections::HashSet<String>,
     pub css: std::collections::HashSet<String>,
     pub rive_data: Vec<ftd::executor::RiveData>,
+    pub keyframes: Vec<ftd::executor::Keyframes>,
 }
 
 impl Default for RT {
@@ -38,6 +39,7 @@ impl Default for RT {
             js: Default::default(),
             css: Default::default(),
             rive_data: vec![],
+            keyframes: vec![],
         }
     }
 }
@@ -94,6 +96,17 @@ impl<'a> ExecuteDoc<'a> {
         let mut main = ftd::executor::element::default_column();
         main.container.children.extend(children);
 
+        let keyframes = ExecuteDoc::get_keyframes(&ftd::executor::TDoc {
+            name: document.name.as_str(),
+            aliases: &document.aliases,
+            bag: &mut document.data,
+            dummy_instructions: &mut dummy_instructions,
+            element_constructor: &mut element_constructor,
+            js: &mut js,
+            css: &mut css,
+            rive_data: &mut rive_data,
+        })?;
+
         Ok(RT {
             name: document.name.to_string(),
             aliases: document.aliases,
@@ -105,8 +118,30 @@ impl<'a> ExecuteDoc<'a> {
             js,
             css,
             rive_data,
+            keyframes,
         })
     }
+
+    /// Collects every `ftd.keyframes` variable in the bag, so that their
+    /// `@keyframes` rules can be emitted once for the whole document.
+    fn get_keyframes(
+        doc: &ftd::executor::TDoc,
+    ) -> ftd::executor::Result<Vec<ftd::executor::Keyframes>> {
+        let mut keyframes = vec![];
+        for thing in doc.bag.values() {
+            if let ftd::interpreter::Thing::Variable(v) = thing {
+                if v.kind.kind.get_record_name() != Some(ftd::interpreter::FTD_KEYFRAMES) {
+                    continue;
+                }
+                keyframes.push(ftd::executor::Keyframes::from_value(
+                    v.value.to_owned(),
+                    doc,
+                    v.line_number,
+                )?);
+            }
+        }
+        Ok(keyframes)
+    }
     #[tracing::instrument(skip_all)]
     fn execute(&mut self) -> ftd::executor::Result<Vec<ftd::executor::Element>> {
         let mut doc = ftd::executor::TDoc {
@@ -373,7 +408,7 @@ impl<'a> ExecuteDoc<'a> {
         )
         .0;
 
-        let children_length = iteration.children(&doc.itdoc())?.0.len();
+        let children_keys = iteration.children_keys(&doc.itdoc())?;
         let reference_name =
             iteration
                 .on
@@ -387,7 +422,7 @@ impl<'a> ExecuteDoc<'a> {
                     line_number: iteration.line_number,
                 })?;
         let mut elements = vec![];
-        for index in 0..children_length {
+        for (index, key) in children_keys.iter().enumerate() {
             let local_container = {
                 let mut local_container = parent_container.to_vec();
                 local_container.push(index + start_index);
@@ -397,6 +432,7 @@ impl<'a> ExecuteDoc<'a> {
                 instruction,
                 doc,
                 index,
+                key,
                 iteration.alias.as_str(),
                 reference_name,
                 inherited_variables,
@@ -608,6 +644,7 @@ impl<'a> ExecuteDoc<'a> {
                     ftd::executor::Element::Row(r) => &mut r.container.children,
                     ftd::executor::Element::Column(r) => &mut r.container.children,
                     ftd::executor::Element::Container(e) => &mut e.children,
+                    ftd::executor::Element::Grid(g) => &mut g.children,
                     ftd::executor::Element::Document(r) => &mut r.children,
                     t => unreachable!("{:?}", t),
                 };
@@ -771,8 +808,8 @@ impl<'a> ExecuteDoc<'a> {
         use itertools::Itertools;
 
         match component_definition.name.as_str() {
-            "ftd#row" | "ftd#column" | "ftd#container" | "ftd#document" | "ftd#desktop"
-            | "ftd#mobile" => {
+            "ftd#row" | "ftd#column" | "ftd#container" | "ftd#grid" | "ftd#document"
+            | "ftd#desktop" | "ftd#mobile" => {
                 doc.insert_local_variables(
                     component_definition.name.as_str(),
                     instruction.properties.as_slice(),
@@ -920,6 +957,20 @@ impl<'a> ExecuteDoc<'a> {
                     device,
                 )?,
             ),
+            "ftd#grid" => {
+                ftd::executor::Element::Grid(ftd::executor::element::grid_from_properties(
+                    instruction.properties.as_slice(),
+                    instruction.events.as_slice(),
+                    component_definition.arguments.as_slice(),
+                    instruction.condition.as_ref(),
+                    doc,
+                    local_container,
+                    instruction.line_number,
+                    vec![],
+                    inherited_variables,
+                    device,
+                )?)
+            }
             "ftd#document" => {
                 if !instruction.events.is_empty() {
                     return ftd::executor::utils::parse_error(
@@ -18,18 +18,19 @@ pub type FieldWithValue = (ftd::interpreter::Field, Option<ftd::ast::VariableVal
 
 pub use dummy::{DummyElement, ElementConstructor};
 pub use element::{
-    CheckBox, Code, Column, Common, Container, ContainerElement, Document, Element, Event,
+    CheckBox, Code, Column, Common, Container, ContainerElement, Document, Element, Event, Grid,
     HTMLData, Iframe, Image, ImageSrc, IterativeElement, RawElement, RawImage, Rive, Row, Text,
     TextInput, WebComponent,
 };
 pub use main::{Device, ExecuteDoc, RT};
 pub use rive::RiveData;
 pub use styles::{
-    AlignSelf, Alignment, Anchor, Background, BackgroundImage, BackgroundPosition,
+    AlignSelf, Alignment, Anchor, Animation, Background, BackgroundImage, BackgroundPosition,
     BackgroundRepeat, BackgroundSize, BorderStyle, BreakpointWidth, Color, ColorValue, Cursor,
-    Display, FontSize, Length, LineClamp, LinearGradient, LinearGradientColor,
-    LinearGradientDirection, Loading, Overflow, Region, Resize, Resizing, ResponsiveType, Shadow,
-    Spacing, TextAlign, TextInputType, TextStyle, TextTransform, TextWeight, WhiteSpace,
+    Display, FontSize, Keyframe, Keyframes, Length, LineClamp, LinearGradient, LinearGradientColor,
+    LinearGradientDirection, Loading, Overflow, Region, Resize, Resizing, ResponsiveColumns,
+    ResponsiveType, Shadow, Spacing, TextAlign, TextInputType, TextStyle, TextTransform,
+    TextWeight, Transform, Transition, WhiteSpace,
 };
 pub(crate) use tdoc::TDoc;
 pub(crate) use value::Value;
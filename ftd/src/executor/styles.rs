@@ -452,6 +452,113 @@ impl BreakpointWidth {
     }
 }
 
+#[derive(serde::Deserialize, Debug, Default, PartialEq, Clone, serde::Serialize)]
+pub struct ResponsiveColumns {
+    pub desktop: i64,
+    pub mobile: i64,
+}
+
+impl ResponsiveColumns {
+    fn from_optional_values(
+        or_type_value: Option<ftd::Map<ftd::interpreter::PropertyValue>>,
+        doc: &ftd::executor::TDoc,
+        line_number: usize,
+    ) -> ftd::executor::Result<Option<ResponsiveColumns>> {
+        if let Some(value) = or_type_value {
+            Ok(Some(ResponsiveColumns::from_values(
+                value,
+                doc,
+                line_number,
+            )?))
+        } else {
+            Ok(None)
+        }
+    }
+
+    pub(crate) fn optional_responsive_columns(
+        properties: &[ftd::interpreter::Property],
+        arguments: &[ftd::interpreter::Argument],
+        doc: &ftd::executor::TDoc,
+        line_number: usize,
+        key: &str,
+        inherited_variables: &ftd::VecMap<(String, Vec<usize>)>,
+        component_name: &str,
+    ) -> ftd::executor::Result<ftd::executor::Value<Option<ResponsiveColumns>>> {
+        let record_values = ftd::executor::value::optional_record_inherited(
+            key,
+            component_name,
+            properties,
+            arguments,
+            doc,
+            line_number,
+            ftd::interpreter::FTD_RESPONSIVE_COLUMNS,
+            inherited_variables,
+        )?;
+
+        Ok(ftd::executor::Value::new(
+            ResponsiveColumns::from_optional_values(record_values.value, doc, line_number)?,
+            record_values.line_number,
+            record_values.properties,
+        ))
+    }
+
+    fn from_values(
+        values: ftd::Map<ftd::interpreter::PropertyValue>,
+        doc: &ftd::executor::TDoc,
+        line_number: usize,
+    ) -> ftd::executor::Result<ResponsiveColumns> {
+        let desktop = values
+            .get("desktop")
+            .ok_or(ftd::executor::Error::ParseError {
+                message: format!(
+                    "`desktop` field in {} not found",
+                    ftd::interpreter::FTD_RESPONSIVE_COLUMNS
+                ),
+                doc_id: doc.name.to_string(),
+                line_number,
+            })?
+            .clone()
+            .resolve(&doc.itdoc(), line_number)?
+            .integer(doc.name, line_number)?;
+
+        let mobile = if let Some(value) = values.get("mobile") {
+            value
+                .clone()
+                .resolve(&doc.itdoc(), line_number)?
+                .integer(doc.name, line_number)?
+        } else {
+            desktop
+        };
+
+        if desktop < 1 || mobile < 1 {
+            return ftd::executor::utils::parse_error(
+                format!(
+                    "Expected at least one column in {}, found: desktop {}, mobile {}",
+                    ftd::interpreter::FTD_RESPONSIVE_COLUMNS,
+                    desktop,
+                    mobile
+                ),
+                doc.name,
+                line_number,
+            );
+        }
+
+        Ok(ResponsiveColumns { desktop, mobile })
+    }
+
+    pub fn to_css_string(&self, device: &Option<ftd::executor::Device>) -> String {
+        let columns = match device {
+            Some(ftd::executor::Device::Mobile) => self.mobile,
+            _ => self.desktop,
+        };
+        format!("repeat({}, minmax(0, 1fr))", columns)
+    }
+
+    pub fn template_columns_pattern() -> (String, bool) {
+        ("repeat({0}, minmax(0, 1fr))".to_string(), false)
+    }
+}
+
 #[derive(serde::Deserialize, Default, Debug, PartialEq, Clone, serde::Serialize)]
 pub enum Alignment {
     #[default]
@@ -1894,6 +2001,493 @@ impl Shadow {
     }
 }
 
+fn record_field<'a>(
+    values: &'a ftd::Map<ftd::interpreter::PropertyValue>,
+    field_name: &str,
+    record_name: &str,
+    doc: &ftd::executor::TDoc,
+    line_number: usize,
+) -> ftd::executor::Result<&'a ftd::interpreter::PropertyValue> {
+    values
+        .get(field_name)
+        .ok_or_else(|| ftd::executor::Error::ParseError {
+            message: format!("`{}` field in {} not found", field_name, record_name),
+            doc_id: doc.name.to_string(),
+            line_number,
+        })
+}
+
+fn record_fields_of(
+    value: ftd::interpreter::PropertyValue,
+    record_name: &str,
+    doc: &ftd::executor::TDoc,
+    line_number: usize,
+) -> ftd::executor::Result<Option<ftd::Map<ftd::interpreter::PropertyValue>>> {
+    let value = value.resolve(&doc.itdoc(), line_number)?;
+    match value.inner() {
+        Some(ftd::interpreter::Value::Record { name, fields }) if name.eq(record_name) => {
+            Ok(Some(fields))
+        }
+        None => Ok(None),
+        t => ftd::executor::utils::parse_error(
+            format!(
+                "Expected value of type record `{}`, found: {:?}",
+                record_name, t
+            ),
+            doc.name,
+            line_number,
+        ),
+    }
+}
+
+fn optional_decimal(
+    value: &ftd::interpreter::PropertyValue,
+    doc: &ftd::executor::TDoc,
+    line_number: usize,
+) -> ftd::executor::Result<Option<f64>> {
+    match value.clone().resolve(&doc.itdoc(), line_number)?.inner() {
+        Some(value) => Ok(Some(value.decimal(doc.name, line_number)?)),
+        None => Ok(None),
+    }
+}
+
+#[derive(serde::Deserialize, Debug, Default, PartialEq, Clone, serde::Serialize)]
+pub struct Transform {
+    pub translate_x: Option<Length>,
+    pub translate_y: Option<Length>,
+    pub scale: Option<f64>,
+    pub rotate: Option<f64>,
+}
+
+impl Transform {
+    fn from_values(
+        values: ftd::Map<ftd::interpreter::PropertyValue>,
+        doc: &ftd::executor::TDoc,
+        line_number: usize,
+    ) -> ftd::executor::Result<ftd::executor::Transform> {
+        let record_name = "ftd.transform";
+        Ok(ftd::executor::Transform {
+            translate_x: Length::from_optional_value(
+                Some(record_field(&values, "translate-x", record_name, doc, line_number)?.clone()),
+                doc,
+                line_number,
+            )?,
+            translate_y: Length::from_optional_value(
+                Some(record_field(&values, "translate-y", record_name, doc, line_number)?.clone()),
+                doc,
+                line_number,
+            )?,
+            scale: optional_decimal(
+                record_field(&values, "scale", record_name, doc, line_number)?,
+                doc,
+                line_number,
+            )?,
+            rotate: optional_decimal(
+                record_field(&values, "rotate", record_name, doc, line_number)?,
+                doc,
+                line_number,
+            )?,
+        })
+    }
+
+    fn from_optional_values(
+        or_type_value: Option<ftd::Map<ftd::interpreter::PropertyValue>>,
+        doc: &ftd::executor::TDoc,
+        line_number: usize,
+    ) -> ftd::executor::Result<Option<ftd::executor::Transform>> {
+        if let Some(value) = or_type_value {
+            Ok(Some(ftd::executor::Transform::from_values(
+                value,
+                doc,
+                line_number,
+            )?))
+        } else {
+            Ok(None)
+        }
+    }
+
+    pub(crate) fn optional_transform(
+        properties: &[ftd::interpreter::Property],
+        arguments: &[ftd::interpreter::Argument],
+        doc: &ftd::executor::TDoc,
+        line_number: usize,
+        key: &str,
+        inherited_variables: &ftd::VecMap<(String, Vec<usize>)>,
+        component_name: &str,
+    ) -> ftd::executor::Result<ftd::executor::Value<Option<ftd::executor::Transform>>> {
+        let record_values = ftd::executor::value::optional_record_inherited(
+            key,
+            component_name,
+            properties,
+            arguments,
+            doc,
+            line_number,
+            ftd::interpreter::FTD_TRANSFORM,
+            inherited_variables,
+        )?;
+
+        Ok(ftd::executor::Value::new(
+            ftd::executor::Transform::from_optional_values(record_values.value, doc, line_number)?,
+            record_values.line_number,
+            record_values.properties,
+        ))
+    }
+
+    pub fn to_css_string(&self, device: &Option<ftd::executor::Device>) -> String {
+        let mut functions = vec![];
+        if let Some(ref translate_x) = self.translate_x {
+            functions.push(format!("translateX({})", translate_x.to_css_string(device)));
+        }
+        if let Some(ref translate_y) = self.translate_y {
+            functions.push(format!("translateY({})", translate_y.to_css_string(device)));
+        }
+        if let Some(scale) = self.scale {
+            functions.push(format!("scale({})", scale));
+        }
+        if let Some(rotate) = self.rotate {
+            functions.push(format!("rotate({}deg)", rotate));
+        }
+        if functions.is_empty() {
+            "none".to_string()
+        } else {
+            functions.join(" ")
+        }
+    }
+
+    pub fn transform_pattern() -> (String, bool) {
+        (
+            "window.ftd.dependencies.eval_transform({0}, data)".to_string(),
+            true,
+        )
+    }
+}
+
+#[derive(serde::Deserialize, Debug, Default, PartialEq, Clone, serde::Serialize)]
+pub struct Transition {
+    pub property: String,
+    pub duration: i64,
+    pub easing: String,
+    pub delay: i64,
+}
+
+impl Transition {
+    fn from_values(
+        values: ftd::Map<ftd::interpreter::PropertyValue>,
+        doc: &ftd::executor::TDoc,
+        line_number: usize,
+    ) -> ftd::executor::Result<ftd::executor::Transition> {
+        let record_name = "ftd.transition";
+        let get_value = |field_name: &str| -> ftd::executor::Result<ftd::interpreter::Value> {
+            Ok(
+                record_field(&values, field_name, record_name, doc, line_number)?
+                    .clone()
+                    .resolve(&doc.itdoc(), line_number)?,
+            )
+        };
+
+        Ok(ftd::executor::Transition {
+            property: get_value("property")?.string(doc.name, line_number)?,
+            duration: get_value("duration")?.integer(doc.name, line_number)?,
+            easing: get_value("easing")?.string(doc.name, line_number)?,
+            delay: get_value("delay")?.integer(doc.name, line_number)?,
+        })
+    }
+
+    fn from_optional_values(
+        or_type_value: Option<ftd::Map<ftd::interpreter::PropertyValue>>,
+        doc: &ftd::executor::TDoc,
+        line_number: usize,
+    ) -> ftd::executor::Result<Option<ftd::executor::Transition>> {
+        if let Some(value) = or_type_value {
+            Ok(Some(ftd::executor::Transition::from_values(
+                value,
+                doc,
+                line_number,
+            )?))
+        } else {
+            Ok(None)
+        }
+    }
+
+    pub(crate) fn optional_transition(
+        properties: &[ftd::interpreter::Property],
+        arguments: &[ftd::interpreter::Argument],
+        doc: &ftd::executor::TDoc,
+        line_number: usize,
+        key: &str,
+        inherited_variables: &ftd::VecMap<(String, Vec<usize>)>,
+        component_name: &str,
+    ) -> ftd::executor::Result<ftd::executor::Value<Option<ftd::executor::Transition>>> {
+        let record_values = ftd::executor::value::optional_record_inherited(
+            key,
+            component_name,
+            properties,
+            arguments,
+            doc,
+            line_number,
+            ftd::interpreter::FTD_TRANSITION,
+            inherited_variables,
+        )?;
+
+        Ok(ftd::executor::Value::new(
+            ftd::executor::Transition::from_optional_values(record_values.value, doc, line_number)?,
+            record_values.line_number,
+            record_values.properties,
+        ))
+    }
+
+    pub fn to_css_string(&self) -> String {
+        format!(
+            "{} {}ms {} {}ms",
+            self.property, self.duration, self.easing, self.delay
+        )
+    }
+
+    pub fn transition_pattern() -> (String, bool) {
+        (
+            "window.ftd.dependencies.eval_transition({0}, data)".to_string(),
+            true,
+        )
+    }
+}
+
+#[derive(serde::Deserialize, Debug, Default, PartialEq, Clone, serde::Serialize)]
+pub struct Keyframe {
+    pub at: i64,
+    pub opacity: Option<f64>,
+    pub transform: Option<Transform>,
+}
+
+impl Keyframe {
+    fn from_values(
+        values: ftd::Map<ftd::interpreter::PropertyValue>,
+        doc: &ftd::executor::TDoc,
+        line_number: usize,
+    ) -> ftd::executor::Result<ftd::executor::Keyframe> {
+        let record_name = "ftd.keyframe";
+        let transform = match record_fields_of(
+            record_field(&values, "transform", record_name, doc, line_number)?.clone(),
+            ftd::interpreter::FTD_TRANSFORM,
+            doc,
+            line_number,
+        )? {
+            Some(fields) => Some(Transform::from_values(fields, doc, line_number)?),
+            None => None,
+        };
+
+        Ok(ftd::executor::Keyframe {
+            at: record_field(&values, "at", record_name, doc, line_number)?
+                .clone()
+                .resolve(&doc.itdoc(), line_number)?
+                .integer(doc.name, line_number)?,
+            opacity: optional_decimal(
+                record_field(&values, "opacity", record_name, doc, line_number)?,
+                doc,
+                line_number,
+            )?,
+            transform,
+        })
+    }
+
+    pub fn to_css_string(&self, device: &Option<ftd::executor::Device>) -> String {
+        let mut declarations = vec![];
+        if let Some(opacity) = self.opacity {
+            declarations.push(format!("opacity: {};", opacity));
+        }
+        if let Some(ref transform) = self.transform {
+            declarations.push(format!("transform: {};", transform.to_css_string(device)));
+        }
+        format!("{}% {{ {} }}", self.at, declarations.join(" "))
+    }
+}
+
+#[derive(serde::Deserialize, Debug, Default, PartialEq, Clone, serde::Serialize)]
+pub struct Keyframes {
+    pub name: String,
+    pub frames: Vec<Keyframe>,
+}
+
+impl Keyframes {
+    pub(crate) fn from_value(
+        value: ftd::interpreter::PropertyValue,
+        doc: &ftd::executor::TDoc,
+        line_number: usize,
+    ) -> ftd::executor::Result<ftd::executor::Keyframes> {
+        match record_fields_of(value, ftd::interpreter::FTD_KEYFRAMES, doc, line_number)? {
+            Some(fields) => Keyframes::from_values(fields, doc, line_number),
+            None => ftd::executor::utils::parse_error(
+                format!(
+                    "Expected value of type record `{}`, found: None",
+                    ftd::interpreter::FTD_KEYFRAMES
+                ),
+                doc.name,
+                line_number,
+            ),
+        }
+    }
+
+    fn from_values(
+        values: ftd::Map<ftd::interpreter::PropertyValue>,
+        doc: &ftd::executor::TDoc,
+        line_number: usize,
+    ) -> ftd::executor::Result<ftd::executor::Keyframes> {
+        let record_name = "ftd.keyframes";
+        let name = record_field(&values, "name", record_name, doc, line_number)?
+            .clone()
+            .resolve(&doc.itdoc(), line_number)?
+            .string(doc.name, line_number)?;
+
+        let frames_value = record_field(&values, "frames", record_name, doc, line_number)?
+            .clone()
+            .resolve(&doc.itdoc(), line_number)?;
+        let frames = match frames_value {
+            ftd::interpreter::Value::List { data, .. } => {
+                let mut frames = vec![];
+                for item in data {
+                    let line_number = item.line_number();
+                    match record_fields_of(item, ftd::interpreter::FTD_KEYFRAME, doc, line_number)?
+                    {
+                        Some(fields) => {
+                            frames.push(Keyframe::from_values(fields, doc, line_number)?)
+                        }
+                        None => continue,
+                    }
+                }
+                frames
+            }
+            t => {
+                return ftd::executor::utils::parse_error(
+                    format!("Expected list of `ftd.keyframe`, found: {:?}", t),
+                    doc.name,
+                    line_number,
+                )
+            }
+        };
+
+        Ok(ftd::executor::Keyframes { name, frames })
+    }
+
+    pub fn to_css_string(&self, device: &Option<ftd::executor::Device>) -> String {
+        format!(
+            "@keyframes {} {{ {} }}",
+            self.name,
+            self.frames
+                .iter()
+                .map(|frame| frame.to_css_string(device))
+                .join(" ")
+        )
+    }
+}
+
+#[derive(serde::Deserialize, Debug, Default, PartialEq, Clone, serde::Serialize)]
+pub struct Animation {
+    pub keyframes: Keyframes,
+    pub duration: i64,
+    pub easing: String,
+    pub delay: i64,
+    pub iterations: i64,
+    pub infinite: bool,
+    pub alternate: bool,
+}
+
+impl Animation {
+    fn from_values(
+        values: ftd::Map<ftd::interpreter::PropertyValue>,
+        doc: &ftd::executor::TDoc,
+        line_number: usize,
+    ) -> ftd::executor::Result<ftd::executor::Animation> {
+        let record_name = "ftd.animation";
+        let get_value = |field_name: &str| -> ftd::executor::Result<ftd::interpreter::Value> {
+            Ok(
+                record_field(&values, field_name, record_name, doc, line_number)?
+                    .clone()
+                    .resolve(&doc.itdoc(), line_number)?,
+            )
+        };
+
+        Ok(ftd::executor::Animation {
+            keyframes: Keyframes::from_value(
+                record_field(&values, "keyframes", record_name, doc, line_number)?.clone(),
+                doc,
+                line_number,
+            )?,
+            duration: get_value("duration")?.integer(doc.name, line_number)?,
+            easing: get_value("easing")?.string(doc.name, line_number)?,
+            delay: get_value("delay")?.integer(doc.name, line_number)?,
+            iterations: get_value("iterations")?.integer(doc.name, line_number)?,
+            infinite: get_value("infinite")?.bool(doc.name, line_number)?,
+            alternate: get_value("alternate")?.bool(doc.name, line_number)?,
+        })
+    }
+
+    fn from_optional_values(
+        or_type_value: Option<ftd::Map<ftd::interpreter::PropertyValue>>,
+        doc: &ftd::executor::TDoc,
+        line_number: usize,
+    ) -> ftd::executor::Result<Option<ftd::executor::Animation>> {
+        if let Some(value) = or_type_value {
+            Ok(Some(ftd::executor::Animation::from_values(
+                value,
+                doc,
+                line_number,
+            )?))
+        } else {
+            Ok(None)
+        }
+    }
+
+    pub(crate) fn optional_animation(
+        properties: &[ftd::interpreter::Property],
+        arguments: &[ftd::interpreter::Argument],
+        doc: &ftd::executor::TDoc,
+        line_number: usize,
+        key: &str,
+        inherited_variables: &ftd::VecMap<(String, Vec<usize>)>,
+        component_name: &str,
+    ) -> ftd::executor::Result<ftd::executor::Value<Option<ftd::executor::Animation>>> {
+        let record_values = ftd::executor::value::optional_record_inherited(
+            key,
+            component_name,
+            properties,
+            arguments,
+            doc,
+            line_number,
+            ftd::interpreter::FTD_ANIMATION,
+            inherited_variables,
+        )?;
+
+        Ok(ftd::executor::Value::new(
+            ftd::executor::Animation::from_optional_values(record_values.value, doc, line_number)?,
+            record_values.line_number,
+            record_values.properties,
+        ))
+    }
+
+    pub fn to_css_string(&self) -> String {
+        let iterations = if self.infinite {
+            "infinite".to_string()
+        } else {
+            self.iterations.to_string()
+        };
+        let direction = if self.alternate {
+            "alternate"
+        } else {
+            "normal"
+        };
+        format!(
+            "{} {}ms {} {}ms {} {} both",
+            self.keyframes.name, self.duration, self.easing, self.delay, iterations, direction
+        )
+    }
+
+    pub fn animation_pattern() -> (String, bool) {
+        (
+            "window.ftd.dependencies.eval_animation({0}, data)".to_string(),
+            true,
+        )
+    }
+}
+
 #[derive(serde::Deserialize, Debug, Default, PartialEq, Clone, serde::Serialize)]
 pub struct Color {
     pub light: ftd::executor::Value<ColorValue>,
@@ -62,7 +62,8 @@ impl<'a> DummyHtmlGenerator<'a> {
                         function dummy_data(list, all_data, index) {{
                             let new_data = {{
                                 \"{alias}\": list[index],
-                                \"LOOP__COUNTER\": index
+                                \"LOOP__COUNTER\": index,
+                                \"LOOP__KEY\": index.toString()
                             }};
                             let data = {{...new_data, ...all_data}};
                             var args={{}};
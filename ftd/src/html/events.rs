@@ -71,6 +71,12 @@ impl ftd::html::Action {
             ftd::interpreter::Value::Integer { value } => serde_json::json!(value),
             ftd::interpreter::Value::Decimal { value } => serde_json::json!(value),
             ftd::interpreter::Value::Boolean { value } => serde_json::json!(value),
+            ftd::interpreter::Value::Date { .. }
+            | ftd::interpreter::Value::DateTime { .. }
+            | ftd::interpreter::Value::Duration { .. }
+            | ftd::interpreter::Value::Map { .. } => {
+                value.to_serde_value().unwrap_or(serde_json::Value::Null)
+            }
             ftd::interpreter::Value::Optional { data, .. } => {
                 if let Some(data) = data.as_ref() {
                     ftd::html::Action::from_value(data)
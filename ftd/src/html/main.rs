@@ -13,6 +13,7 @@ pub struct HtmlUI {
     pub js: String,
     pub css: String,
     pub rive_data: String,
+    pub element_css: String,
 }
 
 pub struct HTMLData {
@@ -102,6 +103,7 @@ impl HtmlUI {
                 id,
                 &tdoc,
             )?,
+            element_css: ftd::html::utils::get_keyframes_css(node_data.keyframes.as_slice()),
         })
     }
 }
@@ -181,6 +181,7 @@ pub(crate) fn is_device_dependent(
 ) -> ftd::html::Result<bool> {
     let value = value.clone().resolve(doc, value.line_number())?;
     if value.is_record(ftd::interpreter::FTD_RESPONSIVE_TYPE)
+        || value.is_record(ftd::interpreter::FTD_RESPONSIVE_COLUMNS)
         || value.is_or_type_variant(ftd::interpreter::FTD_LENGTH_RESPONSIVE)
     {
         return Ok(true);
@@ -349,6 +350,16 @@ impl ftd::interpreter::Value {
             ftd::interpreter::Value::Integer { value } => Some(value.to_string()),
             ftd::interpreter::Value::Decimal { value } => Some(value.to_string()),
             ftd::interpreter::Value::Boolean { value } => Some(value.to_string()),
+            ftd::interpreter::Value::Date { .. } | ftd::interpreter::Value::DateTime { .. } => {
+                self.to_string().map(|v| {
+                    if string_needs_no_quotes {
+                        v
+                    } else {
+                        format!("\"{}\"", v)
+                    }
+                })
+            }
+            ftd::interpreter::Value::Duration { value } => Some(value.to_string()),
             ftd::interpreter::Value::List { data, .. } => {
                 let mut values = vec![];
                 for value in data {
@@ -894,6 +905,15 @@ pub fn get_css_html(external_css: &[String]) -> String {
     result
 }
 
+pub fn get_keyframes_css(keyframes: &[ftd::executor::Keyframes]) -> String {
+    use itertools::Itertools;
+
+    keyframes
+        .iter()
+        .map(|keyframes| keyframes.to_css_string(&None))
+        .join("\n")
+}
+
 pub fn get_meta_data(html_data: &ftd::html::HTMLData) -> String {
     let mut result = vec![];
     if let Some(ref title) = html_data.og_title {
@@ -22,6 +22,9 @@ pub const FTD_LENGTH_RESPONSIVE: &str = "ftd#length.responsive";
 pub const FTD_RESPONSIVE_LENGTH: &str = "ftd#responsive-length";
 pub const FTD_RESPONSIVE_LENGTH_DESKTOP: &str = "ftd#responsive-length.desktop";
 
+pub const FTD_RESPONSIVE_COLUMNS: &str = "ftd#responsive-columns";
+pub const FTD_RESPONSIVE_COLUMNS_DESKTOP: &str = "ftd#responsive-columns.desktop";
+
 pub const FTD_ALIGN: &str = "ftd#align";
 pub const FTD_ALIGN_TOP_LEFT: &str = "ftd#align.top-left";
 pub const FTD_ALIGN_TOP_CENTER: &str = "ftd#align.top-center";
@@ -135,6 +138,12 @@ pub const FTD_TEXT_ALIGN_JUSTIFY: &str = "ftd#text-align.justify";
 pub const FTD_SHADOW: &str = "ftd#shadow";
 pub const FTD_SHADOW_COLOR: &str = "ftd#shadow.color";
 
+pub const FTD_TRANSFORM: &str = "ftd#transform";
+pub const FTD_TRANSITION: &str = "ftd#transition";
+pub const FTD_KEYFRAME: &str = "ftd#keyframe";
+pub const FTD_KEYFRAMES: &str = "ftd#keyframes";
+pub const FTD_ANIMATION: &str = "ftd#animation";
+
 // FTD overflow(todo docs link)
 pub const FTD_OVERFLOW: &str = "ftd#overflow";
 pub const FTD_OVERFLOW_SCROLL: &str = "ftd#overflow.scroll";
@@ -259,6 +268,7 @@ pub const FTD_SPECIAL_VALUE: &str = "$VALUE";
 pub const FTD_SPECIAL_CHECKED: &str = "$CHECKED";
 pub const FTD_INHERITED: &str = "inherited";
 pub const FTD_LOOP_COUNTER: &str = "LOOP.COUNTER";
+pub const FTD_LOOP_KEY: &str = "LOOP.KEY";
 pub const FTD_DEFAULT_TYPES: &str = "default-types";
 pub const FTD_DEFAULT_COLORS: &str = "default-colors";
 pub const FTD_NONE: &str = "none";
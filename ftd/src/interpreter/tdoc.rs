@@ -284,6 +284,27 @@ impl<'a> TDoc<'a> {
                     }
                     Ok(value)
                 }
+                ftd::interpreter::Value::Map { data, kind, .. } => {
+                    let value = data
+                        .get(p1.as_str())
+                        .ok_or(ftd::interpreter::Error::ParseError {
+                            message: format!("Can't find key `{}` in map of kind `{:?}`", p1, kind),
+                            doc_id: doc.name.to_string(),
+                            line_number,
+                        })?
+                        .clone()
+                        .resolve_with_inherited(doc, line_number, inherited_variables)?;
+                    if let Some(p2) = p2 {
+                        return resolve_(
+                            p2.as_str(),
+                            &value,
+                            line_number,
+                            doc,
+                            inherited_variables,
+                        );
+                    }
+                    Ok(value)
+                }
                 t => ftd::interpreter::utils::e2(
                     format!("Expected record found `{:?}`", t).as_str(),
                     doc.name,
@@ -640,7 +661,8 @@ impl<'a> TDoc<'a> {
                         Ok(ftd::interpreter::StateWithThing::new_thing(field_kind))
                     }
                 }
-                ftd::interpreter::Kind::List { kind } => {
+                ftd::interpreter::Kind::List { kind }
+                | ftd::interpreter::Kind::Map { value: kind, .. } => {
                     if let Some(remaining) = remaining {
                         get_kind_(*kind, &remaining, doc, line_number)
                     } else {
@@ -858,6 +880,7 @@ impl<'a> TDoc<'a> {
                     let fields = match value.resolve(doc, line_number)?.inner() {
                         Some(ftd::interpreter::Value::Record { fields, .. }) => fields,
                         Some(ftd::interpreter::Value::Object { values }) => values,
+                        Some(ftd::interpreter::Value::Map { data, .. }) => data,
                         Some(ftd::interpreter::Value::List { data, .. }) => data
                             .into_iter()
                             .enumerate()
@@ -1279,6 +1302,7 @@ impl<'a> TDoc<'a> {
                     let fields = match value.resolve(doc, line_number)?.inner() {
                         Some(ftd::interpreter::Value::Record { fields, .. }) => fields,
                         Some(ftd::interpreter::Value::Object { values }) => values,
+                        Some(ftd::interpreter::Value::Map { data, .. }) => data,
                         Some(ftd::interpreter::Value::List { data, .. }) => data
                             .into_iter()
                             .enumerate()
@@ -1846,6 +1870,13 @@ impl<'a> TDoc<'a> {
                         })?,
                 }
             }
+            ftd::interpreter::Kind::Date
+            | ftd::interpreter::Kind::DateTime
+            | ftd::interpreter::Kind::Duration
+                if row.first().is_some() =>
+            {
+                self.as_json_(line_number, row.first().unwrap(), kind.to_owned())?
+            }
             t => unimplemented!(
                 "{:?} not yet implemented, line number: {}, doc: {}",
                 t,
@@ -1916,6 +1947,52 @@ impl<'a> TDoc<'a> {
                     }
                 })?,
             },
+            // SQLite has no date type, dates are stored either as text or as unix
+            // timestamps, and JSON APIs do the same, so we accept both.
+            ftd::interpreter::Kind::Date | ftd::interpreter::Kind::DateTime => match json {
+                serde_json::Value::Number(n) if n.is_i64() => {
+                    let datetime = ftd::interpreter::things::date::datetime_from_timestamp(
+                        n.as_i64().unwrap(),
+                    )
+                    .ok_or_else(|| ftd::interpreter::Error::ParseError {
+                        message: format!("Can't parse to {}, found: {}", kind.get_name(), json),
+                        doc_id: self.name.to_string(),
+                        line_number,
+                    })?;
+                    if kind.is_date() {
+                        ftd::interpreter::Value::Date {
+                            value: datetime.date_naive(),
+                        }
+                    } else {
+                        ftd::interpreter::Value::DateTime { value: datetime }
+                    }
+                }
+                serde_json::Value::String(s) => {
+                    ftd::interpreter::Value::from_date_literal(s, &kind, self.name, line_number)?
+                }
+                _ => {
+                    return ftd::interpreter::utils::e2(
+                        format!("Can't parse to {}, found: {}", kind.get_name(), json),
+                        self.name,
+                        line_number,
+                    )
+                }
+            },
+            ftd::interpreter::Kind::Duration => match json {
+                serde_json::Value::Number(n) if n.is_i64() => ftd::interpreter::Value::Duration {
+                    value: n.as_i64().unwrap(),
+                },
+                serde_json::Value::String(s) => {
+                    ftd::interpreter::Value::from_date_literal(s, &kind, self.name, line_number)?
+                }
+                _ => {
+                    return ftd::interpreter::utils::e2(
+                        format!("Can't parse to duration, found: {}", json),
+                        self.name,
+                        line_number,
+                    )
+                }
+            },
             ftd::interpreter::Kind::Record { name, .. } => {
                 let rec_fields = self.get_record(&name, line_number)?.fields;
                 let mut fields: ftd::Map<ftd::interpreter::PropertyValue> = Default::default();
@@ -1925,6 +2002,9 @@ impl<'a> TDoc<'a> {
                             Some(v) => v.to_owned(),
                             None if field.kind.is_optional() => serde_json::Value::Null,
                             None if field.kind.is_list() => serde_json::Value::Array(vec![]),
+                            None if field.kind.is_map() => {
+                                serde_json::Value::Object(Default::default())
+                            }
                             None => {
                                 return ftd::interpreter::utils::e2(
                                     format!("key not found: {}", field.name.as_str()),
@@ -1993,6 +2073,45 @@ impl<'a> TDoc<'a> {
                     kind: kind.to_owned().into_kind_data(),
                 }
             }
+            ftd::interpreter::Kind::Map {
+                key: key_kind,
+                value: kind,
+            } => {
+                let mut data: ftd::Map<ftd::interpreter::PropertyValue> = Default::default();
+                if let serde_json::Value::Object(o) = json {
+                    for (key, item) in o {
+                        ftd::interpreter::utils::validate_map_key(
+                            key.as_str(),
+                            &key_kind,
+                            self.name,
+                            line_number,
+                        )?;
+                        data.insert(
+                            key.to_string(),
+                            ftd::interpreter::PropertyValue::Value {
+                                value: self.as_json_(
+                                    line_number,
+                                    item,
+                                    kind.as_ref().to_owned(),
+                                )?,
+                                is_mutable: false,
+                                line_number,
+                            },
+                        );
+                    }
+                } else {
+                    return ftd::interpreter::utils::e2(
+                        format!("expected object of map type, found: {}", json),
+                        self.name,
+                        line_number,
+                    );
+                }
+                ftd::interpreter::Value::Map {
+                    data,
+                    key_kind: key_kind.as_ref().to_owned(),
+                    kind: kind.as_ref().to_owned().into_kind_data(),
+                }
+            }
             ftd::interpreter::Kind::Optional { kind, .. } => {
                 let kind = kind.as_ref().to_owned();
                 match json {
@@ -163,6 +163,46 @@ fn filename_with_second_last_extension_replaced_with_json(
     ))
 }
 
+#[test]
+fn map_key_with_dot_test() {
+    let source = indoc::indoc!(
+        "
+        -- map<string, integer> scores:
+        alice.smith: 10
+
+        -- ftd.text: $LOOP.KEY
+        $loop$: $scores as $obj
+        "
+    );
+    match interpret_helper("foo", source) {
+        Err(ftd::interpreter::Error::ParseError { message, .. }) => {
+            assert_eq!(message, "Map key can not contain `.`, found: `alice.smith`")
+        }
+        t => panic!("Expected error, found: {:?}", t.map(|_| ())),
+    }
+}
+
+#[test]
+fn date_add_out_of_range_test() {
+    use ftd::evalexpr::*;
+    let mut context = ftd::interpreter::default::default_context().unwrap();
+    assert_eq!(
+        eval_with_context_mut("ftd.date_add(\"2023-01-31\", 86400)", &mut context),
+        Ok(Value::from("2023-02-01"))
+    );
+    for seconds in ["9223372036854775", "-9223372036854775807"] {
+        assert!(eval_with_context_mut(
+            format!("ftd.date_add(\"2023-01-31\", {})", seconds).as_str(),
+            &mut context
+        )
+        .is_err());
+    }
+    assert_eq!(
+        ftd::interpreter::things::date::parse_duration("99999999999999w"),
+        None
+    );
+}
+
 #[test]
 fn evalexpr_test() {
     use ftd::evalexpr::*;
@@ -1118,7 +1118,8 @@ impl Loop {
     ) -> ftd::interpreter::Result<ftd::interpreter::Kind> {
         let kind = self.on.kind();
         match kind {
-            ftd::interpreter::Kind::List { kind } => Ok(kind.as_ref().to_owned()),
+            ftd::interpreter::Kind::List { kind }
+            | ftd::interpreter::Kind::Map { value: kind, .. } => Ok(kind.as_ref().to_owned()),
             t => ftd::interpreter::utils::e2(
                 format!("Expected list kind, found: {:?}", t),
                 doc_id,
@@ -1188,14 +1189,36 @@ impl Loop {
         ftd::interpreter::KindData,
     )> {
         let value = self.on.clone().resolve(doc, self.line_number)?;
-        if let ftd::interpreter::Value::List { data, kind } = value {
-            Ok((data, kind))
-        } else {
-            ftd::interpreter::utils::e2(
+        match value {
+            ftd::interpreter::Value::List { data, kind } => Ok((data, kind)),
+            ftd::interpreter::Value::Map { data, kind, .. } => {
+                Ok((data.into_values().collect(), kind))
+            }
+            _ => ftd::interpreter::utils::e2(
                 format!("Expected list type data, found: {:?}", self.on),
                 doc.name,
                 self.line_number,
-            )
+            ),
+        }
+    }
+
+    /// The name each loop element is referred by, relative to the loop object:
+    /// the index for a list and the key for a map.
+    pub fn children_keys(
+        &self,
+        doc: &ftd::interpreter::TDoc,
+    ) -> ftd::interpreter::Result<Vec<String>> {
+        let value = self.on.clone().resolve(doc, self.line_number)?;
+        match value {
+            ftd::interpreter::Value::List { data, .. } => {
+                Ok((0..data.len()).map(|index| index.to_string()).collect())
+            }
+            ftd::interpreter::Value::Map { data, .. } => Ok(data.into_keys().collect()),
+            _ => ftd::interpreter::utils::e2(
+                format!("Expected list type data, found: {:?}", self.on),
+                doc.name,
+                self.line_number,
+            ),
         }
     }
 }
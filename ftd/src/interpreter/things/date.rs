@@ -0,0 +1,303 @@
+/// Canonical format of a `date` value, also used when a date is passed to
+/// `ftd::evalexpr` or serialized to the browser. ISO 8601 dates compare
+/// correctly as plain strings, so `<`, `>`, `==` etc. work without any special
+/// casing in the expression evaluator.
+pub const DATE_FORMAT: &str = "%Y-%m-%d";
+
+/// Canonical format of a `datetime` value. All datetime values are normalised
+/// to UTC, so two datetimes can be compared as strings as well.
+pub const DATETIME_FORMAT: &str = "%Y-%m-%dT%H:%M:%SZ";
+
+const NAIVE_DATETIME_FORMATS: [&str; 4] = [
+    "%Y-%m-%dT%H:%M:%S%.f",
+    "%Y-%m-%d %H:%M:%S%.f",
+    "%Y-%m-%dT%H:%M",
+    "%Y-%m-%d %H:%M",
+];
+
+const MONTHS: [&str; 12] = [
+    "January",
+    "February",
+    "March",
+    "April",
+    "May",
+    "June",
+    "July",
+    "August",
+    "September",
+    "October",
+    "November",
+    "December",
+];
+
+const WEEKDAYS: [&str; 7] = [
+    "Monday",
+    "Tuesday",
+    "Wednesday",
+    "Thursday",
+    "Friday",
+    "Saturday",
+    "Sunday",
+];
+
+/// Parses a `date` literal. Accepts `YYYY-MM-DD` as well as any datetime
+/// literal accepted by `parse_datetime`, in which case the (UTC) date part is
+/// used.
+pub fn parse_date(s: &str) -> Option<chrono::NaiveDate> {
+    let s = s.trim();
+    if let Ok(date) = chrono::NaiveDate::parse_from_str(s, DATE_FORMAT) {
+        return Some(date);
+    }
+    parse_datetime(s).map(|v| v.date_naive())
+}
+
+/// Parses a `datetime` literal. Accepts RFC 3339 (`2023-04-05T10:00:00+05:30`),
+/// datetimes without offset (`2023-04-05 10:00:00`, as produced by SQLite's
+/// `datetime()`), which are treated as UTC, and plain dates, which are treated
+/// as midnight UTC.
+pub fn parse_datetime(s: &str) -> Option<chrono::DateTime<chrono::Utc>> {
+    use chrono::TimeZone;
+
+    let s = s.trim();
+    if let Ok(v) = chrono::DateTime::parse_from_rfc3339(s) {
+        return Some(v.with_timezone(&chrono::Utc));
+    }
+    for format in NAIVE_DATETIME_FORMATS {
+        if let Ok(v) = chrono::NaiveDateTime::parse_from_str(s, format) {
+            return Some(chrono::Utc.from_utc_datetime(&v));
+        }
+    }
+    chrono::NaiveDate::parse_from_str(s, DATE_FORMAT)
+        .ok()
+        .and_then(|v| v.and_hms_opt(0, 0, 0))
+        .map(|v| chrono::Utc.from_utc_datetime(&v))
+}
+
+/// Converts a unix timestamp (in seconds) to a datetime, this is how SQLite
+/// and most JSON APIs store time as integers.
+pub fn datetime_from_timestamp(seconds: i64) -> Option<chrono::DateTime<chrono::Utc>> {
+    use chrono::TimeZone;
+
+    chrono::Utc.timestamp_opt(seconds, 0).single()
+}
+
+/// Parses a `duration` literal and returns the number of seconds. Accepts a
+/// plain integer (seconds), ISO 8601 durations (`PT1H30M`, `P2DT3H`) and the
+/// short form `1w 2d 3h 4m 5s` (spaces optional, `-` prefix for negative).
+pub fn parse_duration(s: &str) -> Option<i64> {
+    let s = s.trim();
+    if s.is_empty() {
+        return None;
+    }
+    if let Ok(v) = s.parse::<i64>() {
+        return Some(v);
+    }
+    let (negative, s) = match s.strip_prefix('-') {
+        Some(s) => (true, s.trim_start()),
+        None => (false, s),
+    };
+    let seconds = match s.strip_prefix('P') {
+        Some(iso) => parse_iso_duration(iso)?,
+        None => parse_short_duration(s)?,
+    };
+    Some(if negative { -seconds } else { seconds })
+}
+
+fn parse_iso_duration(s: &str) -> Option<i64> {
+    let (date_part, time_part) = match s.split_once('T') {
+        Some((d, t)) => (d, Some(t)),
+        None => (s, None),
+    };
+    let mut seconds = sum_units(date_part, |unit| match unit {
+        'W' => Some(7 * 86400),
+        'D' => Some(86400),
+        _ => None,
+    })?;
+    if let Some(time_part) = time_part {
+        if time_part.is_empty() {
+            return None;
+        }
+        seconds = seconds.checked_add(sum_units(time_part, |unit| match unit {
+            'H' => Some(3600),
+            'M' => Some(60),
+            'S' => Some(1),
+            _ => None,
+        })?)?;
+    }
+    Some(seconds)
+}
+
+fn parse_short_duration(s: &str) -> Option<i64> {
+    let s: String = s.chars().filter(|c| !c.is_whitespace()).collect();
+    sum_units(s.as_str(), |unit| match unit {
+        'w' => Some(7 * 86400),
+        'd' => Some(86400),
+        'h' => Some(3600),
+        'm' => Some(60),
+        's' => Some(1),
+        _ => None,
+    })
+}
+
+fn sum_units(s: &str, unit_in_seconds: impl Fn(char) -> Option<i64>) -> Option<i64> {
+    let mut total = 0i64;
+    let mut number = String::new();
+    for c in s.chars() {
+        if c.is_ascii_digit() {
+            number.push(c);
+            continue;
+        }
+        if number.is_empty() {
+            return None;
+        }
+        // an overflowing duration is as invalid as a malformed one
+        total = total.checked_add(
+            number
+                .parse::<i64>()
+                .ok()?
+                .checked_mul(unit_in_seconds(c)?)?,
+        )?;
+        number.clear();
+    }
+    if !number.is_empty() {
+        return None;
+    }
+    Some(total)
+}
+
+pub fn date_to_string(date: &chrono::NaiveDate) -> String {
+    date.format(DATE_FORMAT).to_string()
+}
+
+pub fn datetime_to_string(datetime: &chrono::DateTime<chrono::Utc>) -> String {
+    datetime.format(DATETIME_FORMAT).to_string()
+}
+
+/// Renders seconds in the short duration form, e.g. `1d 2h 30m`. This is the
+/// inverse of `parse_duration` and is also what `ftd.format_duration` returns.
+pub fn duration_to_string(seconds: i64) -> String {
+    if seconds == 0 {
+        return "0s".to_string();
+    }
+    let mut remaining = seconds.unsigned_abs();
+    let mut parts = vec![];
+    for (unit, size) in [("d", 86400), ("h", 3600), ("m", 60), ("s", 1)] {
+        let count = remaining / size;
+        remaining %= size;
+        if count > 0 {
+            parts.push(format!("{}{}", count, unit));
+        }
+    }
+    let duration = parts.join(" ");
+    if seconds < 0 {
+        format!("-{}", duration)
+    } else {
+        duration
+    }
+}
+
+/// Formats a date or datetime (given in any form accepted by `parse_datetime`)
+/// using a strftime like pattern. Only the following specifiers are supported,
+/// and the same set is implemented by `window.ftd.format_date` in the browser so
+/// server and client rendering agree: `%Y %y %m %d %e %H %I %M %S %p %B %b %A
+/// %a %%`. A `-` after `%` drops the zero padding, e.g. `%-d`.
+pub fn format(value: &str, pattern: &str) -> Option<String> {
+    use chrono::{Datelike, Timelike};
+
+    let datetime = parse_datetime(value)?;
+    let mut result = String::new();
+    let mut chars = pattern.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c != '%' {
+            result.push(c);
+            continue;
+        }
+        let no_pad = chars.peek().eq(&Some(&'-'));
+        if no_pad {
+            chars.next();
+        }
+        let number = |n: u32| {
+            if no_pad {
+                n.to_string()
+            } else {
+                format!("{:02}", n)
+            }
+        };
+        match chars.next()? {
+            'Y' => result.push_str(datetime.year().to_string().as_str()),
+            'y' => result.push_str(format!("{:02}", datetime.year() % 100).as_str()),
+            'm' => result.push_str(number(datetime.month()).as_str()),
+            'd' => result.push_str(number(datetime.day()).as_str()),
+            'e' => result.push_str(datetime.day().to_string().as_str()),
+            'H' => result.push_str(number(datetime.hour()).as_str()),
+            'I' => result.push_str(number(datetime.hour12().1).as_str()),
+            'M' => result.push_str(number(datetime.minute()).as_str()),
+            'S' => result.push_str(number(datetime.second()).as_str()),
+            'p' => result.push_str(if datetime.hour12().0 { "PM" } else { "AM" }),
+            'B' => result.push_str(MONTHS[datetime.month0() as usize]),
+            'b' => result.push_str(&MONTHS[datetime.month0() as usize][..3]),
+            'A' => result.push_str(WEEKDAYS[datetime.weekday().num_days_from_monday() as usize]),
+            'a' => {
+                result.push_str(&WEEKDAYS[datetime.weekday().num_days_from_monday() as usize][..3])
+            }
+            '%' => result.push('%'),
+            _ => return None,
+        }
+    }
+    Some(result)
+}
+
+/// The date pattern conventionally used by `locale`. The table is deliberately
+/// small and numeric for non English locales so that the output does not depend
+/// on locale data available in the browser.
+pub fn locale_pattern(locale: &str, with_time: bool) -> &'static str {
+    let language = locale
+        .split(['-', '_'])
+        .next()
+        .unwrap_or_default()
+        .to_lowercase();
+    match (
+        locale.to_lowercase().replace('_', "-").as_str(),
+        language.as_str(),
+    ) {
+        ("en-gb" | "en-in" | "en-au" | "en-nz" | "en-ie", _) if with_time => "%-d %b %Y %H:%M",
+        ("en-gb" | "en-in" | "en-au" | "en-nz" | "en-ie", _) => "%-d %b %Y",
+        (_, "en") if with_time => "%b %-d, %Y %-I:%M %p",
+        (_, "en") => "%b %-d, %Y",
+        (_, "de" | "ru" | "pl" | "tr" | "fi" | "nb" | "da" | "cs") if with_time => "%d.%m.%Y %H:%M",
+        (_, "de" | "ru" | "pl" | "tr" | "fi" | "nb" | "da" | "cs") => "%d.%m.%Y",
+        (_, "fr" | "es" | "it" | "pt" | "hi") if with_time => "%d/%m/%Y %H:%M",
+        (_, "fr" | "es" | "it" | "pt" | "hi") => "%d/%m/%Y",
+        (_, "ja" | "zh" | "ko") if with_time => "%Y/%m/%d %H:%M",
+        (_, "ja" | "zh" | "ko") => "%Y/%m/%d",
+        _ if with_time => "%Y-%m-%d %H:%M",
+        _ => DATE_FORMAT,
+    }
+}
+
+/// Formats a date or datetime according to the conventions of `locale`. A value
+/// that carries a time part (anything that is not a plain `YYYY-MM-DD`) is
+/// formatted with the time as well.
+pub fn format_locale(value: &str, locale: &str) -> Option<String> {
+    let with_time = chrono::NaiveDate::parse_from_str(value.trim(), DATE_FORMAT).is_err();
+    format(value, locale_pattern(locale, with_time))
+}
+
+/// Number of seconds from `from` to `to`, both dates or datetimes.
+pub fn diff(from: &str, to: &str) -> Option<i64> {
+    Some((parse_datetime(to)? - parse_datetime(from)?).num_seconds())
+}
+
+/// Adds `seconds` to a date or datetime, a date stays a date (so adding `1d` to
+/// `2023-01-31` gives `2023-02-01`). `None` if the result is out of the range
+/// of dates.
+pub fn add(value: &str, seconds: i64) -> Option<String> {
+    // `chrono::Duration::seconds` panics for durations that do not fit in milliseconds
+    let duration = chrono::Duration::milliseconds(seconds.checked_mul(1000)?);
+    let datetime = parse_datetime(value)?.checked_add_signed(duration)?;
+    if chrono::NaiveDate::parse_from_str(value.trim(), DATE_FORMAT).is_ok() {
+        Some(date_to_string(&datetime.date_naive()))
+    } else {
+        Some(datetime_to_string(&datetime))
+    }
+}
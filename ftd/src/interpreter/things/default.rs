@@ -29,6 +29,22 @@ enable light mode in the application.
 4. `enable_system_mode` - This function takes no arguments and returns an empty value. It is used to
 enable system mode in the application, which means the application will use the system's default
 color scheme.
+
+5. `ftd.format_date`, `ftd.format_date_locale`, `ftd.format_duration`, `ftd.date_diff` and
+`ftd.date_add` - These functions work on `date`, `datetime` and `duration` values, which reach the
+expression as ISO 8601 strings and number of seconds respectively. They have a counterpart with the
+same name in `window.ftd` so that they behave the same in the browser.
+
+6. `ftd.map_keys`, `ftd.map_values`, `ftd.map_contains` and `ftd.map_get` - These functions work on
+`map<K, V>` values, which reach the expression as a tuple of `(key, value)` tuples, ordered by key.
+Keys are always strings, as they are for the JavaScript objects the maps become in the browser.
+
+7. `ftd.split`, `ftd.join`, `ftd.replace`, `ftd.trim`, `ftd.starts_with`, `ftd.ends_with`,
+`ftd.to_uppercase`, `ftd.to_lowercase`, `ftd.slice`, `ftd.reverse`, `ftd.sort`, `ftd.contains`,
+`ftd.index_of`, `ftd.filter`, `ftd.sum` and `ftd.format_number` - String, list and number
+functions. `ftd.slice`, `ftd.reverse`, `ftd.contains` and `ftd.index_of` take a string or a list,
+strings are counted in characters. Each one has a counterpart in `window.ftd` which gives the same
+result, so an expression can be evaluated either at render time or in the browser.
 **/
 pub fn default_functions() -> ftd::Map<ftd::evalexpr::Function> {
     use ftd::evalexpr::*;
@@ -114,6 +130,311 @@ pub fn default_functions() -> ftd::Map<ftd::evalexpr::Function> {
                 }
             }),
         ),
+        (
+            "ftd.format_date".to_string(),
+            Function::new(|argument| {
+                let arguments = argument.as_fixed_len_tuple(2)?;
+                let value = arguments[0].as_string()?;
+                let pattern = arguments[1].as_string()?;
+                ftd::interpreter::things::date::format(value.as_str(), pattern.as_str())
+                    .map(Value::String)
+                    .ok_or_else(|| {
+                        EvalexprError::CustomMessage(format!(
+                            "Can't format `{}` using `{}`",
+                            value, pattern
+                        ))
+                    })
+            }),
+        ),
+        (
+            "ftd.format_date_locale".to_string(),
+            Function::new(|argument| {
+                let arguments = argument.as_fixed_len_tuple(2)?;
+                let value = arguments[0].as_string()?;
+                let locale = arguments[1].as_string()?;
+                ftd::interpreter::things::date::format_locale(value.as_str(), locale.as_str())
+                    .map(Value::String)
+                    .ok_or_else(|| {
+                        EvalexprError::CustomMessage(format!("Can't format `{}` as date", value))
+                    })
+            }),
+        ),
+        (
+            "ftd.format_duration".to_string(),
+            Function::new(|argument| {
+                Ok(Value::String(
+                    ftd::interpreter::things::date::duration_to_string(argument.as_int()?),
+                ))
+            }),
+        ),
+        (
+            "ftd.date_diff".to_string(),
+            Function::new(|argument| {
+                let arguments = argument.as_fixed_len_tuple(2)?;
+                let from = arguments[0].as_string()?;
+                let to = arguments[1].as_string()?;
+                ftd::interpreter::things::date::diff(from.as_str(), to.as_str())
+                    .map(Value::Int)
+                    .ok_or_else(|| {
+                        EvalexprError::CustomMessage(format!(
+                            "Can't find difference between `{}` and `{}`",
+                            from, to
+                        ))
+                    })
+            }),
+        ),
+        (
+            "ftd.date_add".to_string(),
+            Function::new(|argument| {
+                let arguments = argument.as_fixed_len_tuple(2)?;
+                let value = arguments[0].as_string()?;
+                let seconds = arguments[1].as_int()?;
+                ftd::interpreter::things::date::add(value.as_str(), seconds)
+                    .map(Value::String)
+                    .ok_or_else(|| {
+                        EvalexprError::CustomMessage(format!("Can't add to `{}`", value))
+                    })
+            }),
+        ),
+        (
+            "ftd.map_keys".to_string(),
+            Function::new(|argument| {
+                Ok(Value::Tuple(
+                    map_entries(argument)?
+                        .into_iter()
+                        .map(|(key, _)| Value::String(key))
+                        .collect(),
+                ))
+            }),
+        ),
+        (
+            "ftd.map_values".to_string(),
+            Function::new(|argument| {
+                Ok(Value::Tuple(
+                    map_entries(argument)?
+                        .into_iter()
+                        .map(|(_, value)| value)
+                        .collect(),
+                ))
+            }),
+        ),
+        (
+            "ftd.map_contains".to_string(),
+            Function::new(|argument| {
+                let arguments = argument.as_fixed_len_tuple(2)?;
+                let key = map_key(&arguments[1]);
+                Ok(Value::Boolean(
+                    map_entries(&arguments[0])?.iter().any(|(k, _)| k.eq(&key)),
+                ))
+            }),
+        ),
+        (
+            "ftd.map_get".to_string(),
+            Function::new(|argument| {
+                let arguments = argument.as_fixed_len_tuple(2)?;
+                let key = map_key(&arguments[1]);
+                Ok(map_entries(&arguments[0])?
+                    .into_iter()
+                    .find(|(k, _)| k.eq(&key))
+                    .map(|(_, value)| value)
+                    .unwrap_or(Value::Empty))
+            }),
+        ),
+        (
+            "ftd.split".to_string(),
+            Function::new(|argument| {
+                let arguments = argument.as_fixed_len_tuple(2)?;
+                let s = arguments[0].as_string()?;
+                let separator = arguments[1].as_string()?;
+                Ok(Value::Tuple(if separator.is_empty() {
+                    s.chars().map(|c| Value::String(c.to_string())).collect()
+                } else {
+                    s.split(separator.as_str())
+                        .map(|v| Value::String(v.to_string()))
+                        .collect()
+                }))
+            }),
+        ),
+        (
+            "ftd.join".to_string(),
+            Function::new(|argument| {
+                let arguments = argument.as_fixed_len_tuple(2)?;
+                let separator = arguments[1].as_string()?;
+                Ok(Value::String(
+                    list_items(&arguments[0])
+                        .iter()
+                        .map(value_to_text)
+                        .collect::<Vec<String>>()
+                        .join(separator.as_str()),
+                ))
+            }),
+        ),
+        (
+            "ftd.replace".to_string(),
+            Function::new(|argument| {
+                let arguments = argument.as_fixed_len_tuple(3)?;
+                let s = arguments[0].as_string()?;
+                let from = arguments[1].as_string()?;
+                let to = arguments[2].as_string()?;
+                if from.is_empty() {
+                    return Ok(Value::String(s));
+                }
+                Ok(Value::String(s.replace(from.as_str(), to.as_str())))
+            }),
+        ),
+        (
+            "ftd.trim".to_string(),
+            Function::new(|argument| Ok(Value::String(argument.as_string()?.trim().to_string()))),
+        ),
+        (
+            "ftd.starts_with".to_string(),
+            Function::new(|argument| {
+                let arguments = argument.as_fixed_len_tuple(2)?;
+                let s = arguments[0].as_string()?;
+                let prefix = arguments[1].as_string()?;
+                Ok(Value::Boolean(s.starts_with(prefix.as_str())))
+            }),
+        ),
+        (
+            "ftd.ends_with".to_string(),
+            Function::new(|argument| {
+                let arguments = argument.as_fixed_len_tuple(2)?;
+                let s = arguments[0].as_string()?;
+                let suffix = arguments[1].as_string()?;
+                Ok(Value::Boolean(s.ends_with(suffix.as_str())))
+            }),
+        ),
+        (
+            "ftd.to_uppercase".to_string(),
+            Function::new(|argument| Ok(Value::String(argument.as_string()?.to_uppercase()))),
+        ),
+        (
+            "ftd.to_lowercase".to_string(),
+            Function::new(|argument| Ok(Value::String(argument.as_string()?.to_lowercase()))),
+        ),
+        (
+            "ftd.slice".to_string(),
+            Function::new(|argument| {
+                let arguments = argument.as_tuple()?;
+                if arguments.len() != 2 && arguments.len() != 3 {
+                    return Err(EvalexprError::WrongFunctionArgumentAmount {
+                        expected: 3,
+                        actual: arguments.len(),
+                    });
+                }
+                let start = arguments[1].as_int()?;
+                let end = match arguments.get(2) {
+                    Some(end) => Some(end.as_int()?),
+                    None => None,
+                };
+                if let Ok(s) = arguments[0].as_string() {
+                    let chars = s.chars().collect::<Vec<char>>();
+                    let (start, end) = slice_range(chars.len(), start, end);
+                    return Ok(Value::String(chars[start..end].iter().collect()));
+                }
+                let items = list_items(&arguments[0]);
+                let (start, end) = slice_range(items.len(), start, end);
+                Ok(Value::Tuple(items[start..end].to_vec()))
+            }),
+        ),
+        (
+            "ftd.reverse".to_string(),
+            Function::new(|argument| {
+                if let Ok(s) = argument.as_string() {
+                    return Ok(Value::String(s.chars().rev().collect()));
+                }
+                let mut items = list_items(argument);
+                items.reverse();
+                Ok(Value::Tuple(items))
+            }),
+        ),
+        (
+            "ftd.sort".to_string(),
+            Function::new(|argument| {
+                let mut items = list_items(argument);
+                if items.iter().all(|v| v.is_number()) {
+                    items.sort_by(|a, b| {
+                        let (a, b) = (a.as_number().unwrap(), b.as_number().unwrap());
+                        a.partial_cmp(&b).unwrap_or(std::cmp::Ordering::Equal)
+                    });
+                } else {
+                    items.sort_by_key(value_to_text);
+                }
+                Ok(Value::Tuple(items))
+            }),
+        ),
+        (
+            "ftd.contains".to_string(),
+            Function::new(|argument| {
+                let arguments = argument.as_fixed_len_tuple(2)?;
+                if let Ok(s) = arguments[0].as_string() {
+                    return Ok(Value::Boolean(
+                        s.contains(value_to_text(&arguments[1]).as_str()),
+                    ));
+                }
+                Ok(Value::Boolean(
+                    list_items(&arguments[0])
+                        .iter()
+                        .any(|v| values_equal(v, &arguments[1])),
+                ))
+            }),
+        ),
+        (
+            "ftd.index_of".to_string(),
+            Function::new(|argument| {
+                let arguments = argument.as_fixed_len_tuple(2)?;
+                let index = if let Ok(s) = arguments[0].as_string() {
+                    s.find(value_to_text(&arguments[1]).as_str())
+                        .map(|index| s[..index].chars().count())
+                } else {
+                    list_items(&arguments[0])
+                        .iter()
+                        .position(|v| values_equal(v, &arguments[1]))
+                };
+                Ok(Value::Int(index.map(|v| v as i64).unwrap_or(-1)))
+            }),
+        ),
+        (
+            "ftd.filter".to_string(),
+            Function::new(|argument| {
+                let arguments = argument.as_fixed_len_tuple(2)?;
+                Ok(Value::Tuple(
+                    list_items(&arguments[0])
+                        .into_iter()
+                        .filter(|v| values_equal(v, &arguments[1]))
+                        .collect(),
+                ))
+            }),
+        ),
+        (
+            "ftd.sum".to_string(),
+            Function::new(|argument| {
+                let items = list_items(argument);
+                if items.iter().all(|v| v.is_int()) {
+                    let mut sum = 0;
+                    for item in items {
+                        sum += item.as_int()?;
+                    }
+                    return Ok(Value::Int(sum));
+                }
+                let mut sum = 0.0;
+                for item in items {
+                    sum += item.as_number()?;
+                }
+                Ok(Value::Float(sum))
+            }),
+        ),
+        (
+            "ftd.format_number".to_string(),
+            Function::new(|argument| {
+                let (value, decimals) = if let Ok(arguments) = argument.as_fixed_len_tuple(2) {
+                    (arguments[0].as_number()?, arguments[1].as_int()?)
+                } else {
+                    (argument.as_number()?, 0)
+                };
+                Ok(Value::String(format_number(value, decimals)))
+            }),
+        ),
         (
             "enable_dark_mode".to_string(),
             Function::new(|_| Ok(Value::Empty)),
@@ -130,6 +451,108 @@ pub fn default_functions() -> ftd::Map<ftd::evalexpr::Function> {
     .collect()
 }
 
+fn map_entries(
+    value: &ftd::evalexpr::Value,
+) -> ftd::evalexpr::EvalexprResult<Vec<(String, ftd::evalexpr::Value)>> {
+    if value.as_empty().is_ok() {
+        return Ok(vec![]);
+    }
+    let mut entries = vec![];
+    for entry in value.as_tuple()? {
+        let mut entry = entry.as_fixed_len_tuple(2)?.into_iter();
+        let key = map_key(&entry.next().unwrap());
+        entries.push((key, entry.next().unwrap()));
+    }
+    Ok(entries)
+}
+
+fn map_key(value: &ftd::evalexpr::Value) -> String {
+    match value {
+        ftd::evalexpr::Value::String(s) => s.to_string(),
+        t => t.to_string(),
+    }
+}
+
+fn list_items(value: &ftd::evalexpr::Value) -> Vec<ftd::evalexpr::Value> {
+    match value {
+        ftd::evalexpr::Value::Tuple(items) => items.to_owned(),
+        ftd::evalexpr::Value::Empty => vec![],
+        t => vec![t.to_owned()],
+    }
+}
+
+/// Text of a value the way JavaScript's `String()` writes it, `NULL` is empty
+/// and a list is comma separated, so `ftd.join` and `ftd.sort` agree with the
+/// browser.
+fn value_to_text(value: &ftd::evalexpr::Value) -> String {
+    match value {
+        ftd::evalexpr::Value::String(s) => s.to_string(),
+        ftd::evalexpr::Value::Int(v) => v.to_string(),
+        ftd::evalexpr::Value::Float(v) => v.to_string(),
+        ftd::evalexpr::Value::Boolean(v) => v.to_string(),
+        ftd::evalexpr::Value::Tuple(items) => items
+            .iter()
+            .map(value_to_text)
+            .collect::<Vec<String>>()
+            .join(","),
+        ftd::evalexpr::Value::Empty => "".to_string(),
+    }
+}
+
+/// Integers and decimals are equal if they are the same number, as in
+/// JavaScript.
+fn values_equal(a: &ftd::evalexpr::Value, b: &ftd::evalexpr::Value) -> bool {
+    match (a.as_number(), b.as_number()) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => a == b,
+    }
+}
+
+/// Same as the `start` and `end` of JavaScript's `slice()`, negative values
+/// count from the end.
+fn slice_range(len: usize, start: i64, end: Option<i64>) -> (usize, usize) {
+    let position = |index: i64| {
+        if index < 0 {
+            (len as i64 + index).max(0) as usize
+        } else {
+            (index as usize).min(len)
+        }
+    };
+    let start = position(start);
+    let end = end.map(position).unwrap_or(len);
+    (start, end.max(start))
+}
+
+/// Formats `value` with `decimals` digits after the point and `,` between
+/// thousands, e.g. `1,234.50`. Rounds half away from zero, using the same float
+/// operations as `window.ftd.format_number` so that both give the same digits.
+fn format_number(value: f64, decimals: i64) -> String {
+    let decimals = decimals.clamp(0, 15) as u32;
+    let scale = 10u64.pow(decimals);
+    let scaled = (value.abs() * scale as f64 + 0.5).floor() as u64;
+    let integer = (scaled / scale).to_string();
+    let mut grouped = integer
+        .as_bytes()
+        .rchunks(3)
+        .rev()
+        .map(|digits| String::from_utf8_lossy(digits).to_string())
+        .collect::<Vec<String>>()
+        .join(",");
+    if decimals > 0 {
+        grouped = format!(
+            "{}.{:0width$}",
+            grouped,
+            scaled % scale,
+            width = decimals as usize
+        );
+    }
+    if value < 0.0 && scaled != 0 {
+        format!("-{}", grouped)
+    } else {
+        grouped
+    }
+}
+
 pub fn default_context() -> ftd::interpreter::Result<ftd::evalexpr::HashMapContext> {
     let mut context = ftd::evalexpr::HashMapContext::new();
     for (key, function) in default_functions() {
@@ -181,6 +604,10 @@ pub fn default_bag() -> ftd::Map<ftd::interpreter::Thing> {
             "ftd#column".to_string(),
             ftd::interpreter::Thing::Component(column_function()),
         ),
+        (
+            "ftd#grid".to_string(),
+            ftd::interpreter::Thing::Component(grid_function()),
+        ),
         (
             "ftd#document".to_string(),
             ftd::interpreter::Thing::Component(document_function()),
@@ -961,6 +1388,255 @@ pub fn default_bag() -> ftd::Map<ftd::interpreter::Thing> {
                 line_number: 0,
             }),
         ),
+        (
+            ftd::interpreter::FTD_TRANSFORM.to_string(),
+            ftd::interpreter::Thing::Record(ftd::interpreter::Record {
+                name: ftd::interpreter::FTD_TRANSFORM.to_string(),
+                fields: std::iter::IntoIterator::into_iter([
+                    ftd::interpreter::Field {
+                        name: "translate-x".to_string(),
+                        kind: ftd::interpreter::Kind::or_type(ftd::interpreter::FTD_LENGTH)
+                            .into_optional()
+                            .into_kind_data(),
+                        mutable: false,
+                        value: None,
+                        access_modifier: Default::default(),
+                        line_number: 0,
+                    },
+                    ftd::interpreter::Field {
+                        name: "translate-y".to_string(),
+                        kind: ftd::interpreter::Kind::or_type(ftd::interpreter::FTD_LENGTH)
+                            .into_optional()
+                            .into_kind_data(),
+                        mutable: false,
+                        value: None,
+                        access_modifier: Default::default(),
+                        line_number: 0,
+                    },
+                    ftd::interpreter::Field {
+                        name: "scale".to_string(),
+                        kind: ftd::interpreter::Kind::decimal().into_optional().into_kind_data(),
+                        mutable: false,
+                        value: None,
+                        access_modifier: Default::default(),
+                        line_number: 0,
+                    },
+                    ftd::interpreter::Field {
+                        name: "rotate".to_string(),
+                        kind: ftd::interpreter::Kind::decimal().into_optional().into_kind_data(),
+                        mutable: false,
+                        value: None,
+                        access_modifier: Default::default(),
+                        line_number: 0,
+                    },
+                ])
+                .collect(),
+                line_number: 0,
+            }),
+        ),
+        (
+            ftd::interpreter::FTD_TRANSITION.to_string(),
+            ftd::interpreter::Thing::Record(ftd::interpreter::Record {
+                name: ftd::interpreter::FTD_TRANSITION.to_string(),
+                fields: std::iter::IntoIterator::into_iter([
+                    ftd::interpreter::Field {
+                        name: "property".to_string(),
+                        kind: ftd::interpreter::Kind::string().into_kind_data(),
+                        mutable: false,
+                        value: Some(
+                            ftd::interpreter::Value::new_string("all")
+                                .into_property_value(false, 0),
+                        ),
+                        access_modifier: Default::default(),
+                        line_number: 0,
+                    },
+                    ftd::interpreter::Field {
+                        name: "duration".to_string(),
+                        kind: ftd::interpreter::Kind::integer().into_kind_data(),
+                        mutable: false,
+                        value: Some(
+                            ftd::interpreter::Value::Integer { value: 300 }
+                                .into_property_value(false, 0),
+                        ),
+                        access_modifier: Default::default(),
+                        line_number: 0,
+                    },
+                    ftd::interpreter::Field {
+                        name: "easing".to_string(),
+                        kind: ftd::interpreter::Kind::string().into_kind_data(),
+                        mutable: false,
+                        value: Some(
+                            ftd::interpreter::Value::new_string("ease")
+                                .into_property_value(false, 0),
+                        ),
+                        access_modifier: Default::default(),
+                        line_number: 0,
+                    },
+                    ftd::interpreter::Field {
+                        name: "delay".to_string(),
+                        kind: ftd::interpreter::Kind::integer().into_kind_data(),
+                        mutable: false,
+                        value: Some(
+                            ftd::interpreter::Value::Integer { value: 0 }
+                                .into_property_value(false, 0),
+                        ),
+                        access_modifier: Default::default(),
+                        line_number: 0,
+                    },
+                ])
+                .collect(),
+                line_number: 0,
+            }),
+        ),
+        (
+            ftd::interpreter::FTD_KEYFRAME.to_string(),
+            ftd::interpreter::Thing::Record(ftd::interpreter::Record {
+                name: ftd::interpreter::FTD_KEYFRAME.to_string(),
+                fields: std::iter::IntoIterator::into_iter([
+                    ftd::interpreter::Field {
+                        name: "at".to_string(),
+                        kind: ftd::interpreter::Kind::integer().into_kind_data().caption(),
+                        mutable: false,
+                        value: None,
+                        access_modifier: Default::default(),
+                        line_number: 0,
+                    },
+                    ftd::interpreter::Field {
+                        name: "opacity".to_string(),
+                        kind: ftd::interpreter::Kind::decimal().into_optional().into_kind_data(),
+                        mutable: false,
+                        value: None,
+                        access_modifier: Default::default(),
+                        line_number: 0,
+                    },
+                    ftd::interpreter::Field {
+                        name: "transform".to_string(),
+                        kind: ftd::interpreter::Kind::record(ftd::interpreter::FTD_TRANSFORM)
+                            .into_optional()
+                            .into_kind_data(),
+                        mutable: false,
+                        value: None,
+                        access_modifier: Default::default(),
+                        line_number: 0,
+                    },
+                ])
+                .collect(),
+                line_number: 0,
+            }),
+        ),
+        (
+            ftd::interpreter::FTD_KEYFRAMES.to_string(),
+            ftd::interpreter::Thing::Record(ftd::interpreter::Record {
+                name: ftd::interpreter::FTD_KEYFRAMES.to_string(),
+                fields: std::iter::IntoIterator::into_iter([
+                    ftd::interpreter::Field {
+                        name: "name".to_string(),
+                        kind: ftd::interpreter::Kind::string().into_kind_data().caption(),
+                        mutable: false,
+                        value: None,
+                        access_modifier: Default::default(),
+                        line_number: 0,
+                    },
+                    ftd::interpreter::Field {
+                        name: "frames".to_string(),
+                        kind: ftd::interpreter::Kind::record(ftd::interpreter::FTD_KEYFRAME)
+                            .into_list()
+                            .into_kind_data(),
+                        mutable: false,
+                        value: None,
+                        access_modifier: Default::default(),
+                        line_number: 0,
+                    },
+                ])
+                .collect(),
+                line_number: 0,
+            }),
+        ),
+        (
+            ftd::interpreter::FTD_ANIMATION.to_string(),
+            ftd::interpreter::Thing::Record(ftd::interpreter::Record {
+                name: ftd::interpreter::FTD_ANIMATION.to_string(),
+                fields: std::iter::IntoIterator::into_iter([
+                    ftd::interpreter::Field {
+                        name: "keyframes".to_string(),
+                        kind: ftd::interpreter::Kind::record(ftd::interpreter::FTD_KEYFRAMES)
+                            .into_kind_data(),
+                        mutable: false,
+                        value: None,
+                        access_modifier: Default::default(),
+                        line_number: 0,
+                    },
+                    ftd::interpreter::Field {
+                        name: "duration".to_string(),
+                        kind: ftd::interpreter::Kind::integer().into_kind_data(),
+                        mutable: false,
+                        value: Some(
+                            ftd::interpreter::Value::Integer { value: 1000 }
+                                .into_property_value(false, 0),
+                        ),
+                        access_modifier: Default::default(),
+                        line_number: 0,
+                    },
+                    ftd::interpreter::Field {
+                        name: "easing".to_string(),
+                        kind: ftd::interpreter::Kind::string().into_kind_data(),
+                        mutable: false,
+                        value: Some(
+                            ftd::interpreter::Value::new_string("ease")
+                                .into_property_value(false, 0),
+                        ),
+                        access_modifier: Default::default(),
+                        line_number: 0,
+                    },
+                    ftd::interpreter::Field {
+                        name: "delay".to_string(),
+                        kind: ftd::interpreter::Kind::integer().into_kind_data(),
+                        mutable: false,
+                        value: Some(
+                            ftd::interpreter::Value::Integer { value: 0 }
+                                .into_property_value(false, 0),
+                        ),
+                        access_modifier: Default::default(),
+                        line_number: 0,
+                    },
+                    ftd::interpreter::Field {
+                        name: "iterations".to_string(),
+                        kind: ftd::interpreter::Kind::integer().into_kind_data(),
+                        mutable: false,
+                        value: Some(
+                            ftd::interpreter::Value::Integer { value: 1 }
+                                .into_property_value(false, 0),
+                        ),
+                        access_modifier: Default::default(),
+                        line_number: 0,
+                    },
+                    ftd::interpreter::Field {
+                        name: "infinite".to_string(),
+                        kind: ftd::interpreter::Kind::boolean().into_kind_data(),
+                        mutable: false,
+                        value: Some(
+                            ftd::interpreter::Value::Boolean { value: false }
+                                .into_property_value(false, 0),
+                        ),
+                        access_modifier: Default::default(),
+                        line_number: 0,
+                    },
+                    ftd::interpreter::Field {
+                        name: "alternate".to_string(),
+                        kind: ftd::interpreter::Kind::boolean().into_kind_data(),
+                        mutable: false,
+                        value: Some(
+                            ftd::interpreter::Value::Boolean { value: false }
+                                .into_property_value(false, 0),
+                        ),
+                        access_modifier: Default::default(),
+                        line_number: 0,
+                    },
+                ])
+                .collect(),
+                line_number: 0,
+            }),
+        ),
         (
             ftd::interpreter::FTD_SHADOW.to_string(),
             ftd::interpreter::Thing::Record(ftd::interpreter::Record {
@@ -2712,6 +3388,42 @@ pub fn default_bag() -> ftd::Map<ftd::interpreter::Thing> {
                 line_number: 0,
             }),
         ),
+        (
+            ftd::interpreter::FTD_RESPONSIVE_COLUMNS.to_string(),
+            ftd::interpreter::Thing::Record(ftd::interpreter::Record {
+                name: ftd::interpreter::FTD_RESPONSIVE_COLUMNS.to_string(),
+                fields: std::iter::IntoIterator::into_iter([
+                    ftd::interpreter::Field {
+                        name: "desktop".to_string(),
+                        kind: ftd::interpreter::Kind::integer()
+                            .into_kind_data()
+                            .caption(),
+                        mutable: false,
+                        value: None,
+                        access_modifier: Default::default(),
+                        line_number: 0,
+                    },
+                    ftd::interpreter::Field {
+                        name: "mobile".to_string(),
+                        kind: ftd::interpreter::Kind::integer().into_kind_data(),
+                        mutable: false,
+                        access_modifier: Default::default(),
+                        value: Some(ftd::interpreter::PropertyValue::Reference {
+                            name: ftd::interpreter::FTD_RESPONSIVE_COLUMNS_DESKTOP.to_string(),
+                            kind: ftd::interpreter::Kind::integer().into_kind_data(),
+                            source: ftd::interpreter::PropertyValueSource::Local(
+                                ftd::interpreter::FTD_RESPONSIVE_COLUMNS.to_string(),
+                            ),
+                            is_mutable: false,
+                            line_number: 0,
+                        }),
+                        line_number: 0,
+                    },
+                ])
+                .collect(),
+                line_number: 0,
+            }),
+        ),
         (
             ftd::interpreter::FTD_FONT_SIZE.to_string(),
             ftd::interpreter::Thing::OrType(ftd::interpreter::OrType {
@@ -9596,6 +10308,66 @@ pub fn container_function() -> ftd::interpreter::ComponentDefinition {
     }
 }
 
+pub fn grid_function() -> ftd::interpreter::ComponentDefinition {
+    ftd::interpreter::ComponentDefinition {
+        name: "ftd#grid".to_string(),
+        arguments: [
+            container_root_arguments(),
+            common_arguments(),
+            vec![
+                ftd::interpreter::Argument::default(
+                    "columns",
+                    ftd::interpreter::Kind::record(ftd::interpreter::FTD_RESPONSIVE_COLUMNS)
+                        .into_optional()
+                        .into_kind_data(),
+                ),
+                ftd::interpreter::Argument::default(
+                    "template-columns",
+                    ftd::interpreter::Kind::string()
+                        .into_optional()
+                        .into_kind_data(),
+                ),
+                ftd::interpreter::Argument::default(
+                    "template-rows",
+                    ftd::interpreter::Kind::string()
+                        .into_optional()
+                        .into_kind_data(),
+                ),
+                ftd::interpreter::Argument::default(
+                    "areas",
+                    ftd::interpreter::Kind::string()
+                        .into_optional()
+                        .into_kind_data(),
+                ),
+                ftd::interpreter::Argument::default(
+                    "gap",
+                    ftd::interpreter::Kind::or_type(ftd::interpreter::FTD_LENGTH)
+                        .into_optional()
+                        .into_kind_data(),
+                ),
+                ftd::interpreter::Argument::default(
+                    "row-gap",
+                    ftd::interpreter::Kind::or_type(ftd::interpreter::FTD_LENGTH)
+                        .into_optional()
+                        .into_kind_data(),
+                ),
+                ftd::interpreter::Argument::default(
+                    "column-gap",
+                    ftd::interpreter::Kind::or_type(ftd::interpreter::FTD_LENGTH)
+                        .into_optional()
+                        .into_kind_data(),
+                ),
+            ],
+        ]
+        .concat()
+        .into_iter()
+        .collect(),
+        definition: ftd::interpreter::Component::from_name("ftd.kernel"),
+        css: None,
+        line_number: 0,
+    }
+}
+
 pub fn desktop_function() -> ftd::interpreter::ComponentDefinition {
     ftd::interpreter::ComponentDefinition {
         name: "ftd#desktop".to_string(),
@@ -9923,6 +10695,24 @@ fn common_arguments() -> Vec<ftd::interpreter::Argument> {
                 .into_optional()
                 .into_kind_data(),
         ),
+        ftd::interpreter::Argument::default(
+            "transform",
+            ftd::interpreter::Kind::record(ftd::interpreter::FTD_TRANSFORM)
+                .into_optional()
+                .into_kind_data(),
+        ),
+        ftd::interpreter::Argument::default(
+            "transition",
+            ftd::interpreter::Kind::record(ftd::interpreter::FTD_TRANSITION)
+                .into_optional()
+                .into_kind_data(),
+        ),
+        ftd::interpreter::Argument::default(
+            "animation",
+            ftd::interpreter::Kind::record(ftd::interpreter::FTD_ANIMATION)
+                .into_optional()
+                .into_kind_data(),
+        ),
         ftd::interpreter::Argument::default(
             "sticky",
             ftd::interpreter::Kind::boolean()
@@ -10097,6 +10887,24 @@ fn common_arguments() -> Vec<ftd::interpreter::Argument> {
                 .into_optional()
                 .into_kind_data(),
         ),
+        ftd::interpreter::Argument::default(
+            "grid-column",
+            ftd::interpreter::Kind::string()
+                .into_optional()
+                .into_kind_data(),
+        ),
+        ftd::interpreter::Argument::default(
+            "grid-row",
+            ftd::interpreter::Kind::string()
+                .into_optional()
+                .into_kind_data(),
+        ),
+        ftd::interpreter::Argument::default(
+            "grid-area",
+            ftd::interpreter::Kind::string()
+                .into_optional()
+                .into_kind_data(),
+        ),
         ftd::interpreter::Argument::default(
             "background",
             ftd::interpreter::Kind::or_type(ftd::interpreter::FTD_BACKGROUND)
@@ -221,6 +221,16 @@ impl ftd::evalexpr::ExprNode {
                         identifier: value.to_string(),
                     }
                 }
+            } else if format!("${}", ftd::interpreter::FTD_LOOP_KEY).eq(identifier) {
+                if let Some(ftd::interpreter::PropertyValue::Value {
+                    value: ftd::interpreter::Value::String { text },
+                    ..
+                }) = references.get(identifier)
+                {
+                    operator = ftd::evalexpr::Operator::VariableIdentifierRead {
+                        identifier: serde_json::Value::String(text.to_string()).to_string(),
+                    }
+                }
             } else if let Some(ftd::interpreter::PropertyValue::Reference { name, .. }) =
                 references.get(identifier)
             {
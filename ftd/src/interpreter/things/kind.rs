@@ -5,6 +5,9 @@ pub enum Kind {
     Integer,
     Decimal,
     Boolean,
+    Date,
+    DateTime,
+    Duration,
     Record {
         name: String,
     }, // the full name of the record (full document name.record name)
@@ -16,6 +19,10 @@ pub enum Kind {
     List {
         kind: Box<Kind>,
     },
+    Map {
+        key: Box<Kind>,
+        value: Box<Kind>,
+    },
     Optional {
         kind: Box<Kind>,
     },
@@ -38,8 +45,12 @@ impl Kind {
             Kind::Integer { .. } => "integer".to_string(),
             Kind::Boolean { .. } => "boolean".to_string(),
             Kind::Decimal { .. } => "decimal".to_string(),
+            Kind::Date => "date".to_string(),
+            Kind::DateTime => "datetime".to_string(),
+            Kind::Duration => "duration".to_string(),
             Kind::Constant { .. } => "constant".to_string(),
             Kind::List { .. } => "list".to_string(),
+            Kind::Map { .. } => "map".to_string(),
             Kind::Object { .. } => "object".to_string(),
             Kind::OrType { name, .. } => name.clone(),
             Kind::Optional { .. } => "optional".to_string(),
@@ -61,6 +72,9 @@ impl Kind {
             (Self::Optional { kind, .. }, _) => kind.is_same_as(other),
             (_, Self::Optional { kind: other, .. }) => self.is_same_as(other),
             (Self::List { kind: k1 }, Self::List { kind: k2 }) => k1.is_same_as(k2),
+            (Self::Map { key: k1, value: v1 }, Self::Map { key: k2, value: v2 }) => {
+                k1.is_same_as(k2) && v1.is_same_as(v2)
+            }
             _ => self.eq(other),
         }
     }
@@ -81,6 +95,18 @@ impl Kind {
         Kind::Boolean
     }
 
+    pub fn date() -> Kind {
+        Kind::Date
+    }
+
+    pub fn datetime() -> Kind {
+        Kind::DateTime
+    }
+
+    pub fn duration() -> Kind {
+        Kind::Duration
+    }
+
     pub fn module() -> Kind {
         Kind::Module
     }
@@ -153,6 +179,13 @@ impl Kind {
         }
     }
 
+    pub fn into_map(self, key: Kind) -> Kind {
+        Kind::Map {
+            key: Box::new(key),
+            value: Box::new(self),
+        }
+    }
+
     pub fn into_optional(self) -> Kind {
         Kind::Optional {
             kind: Box::new(self),
@@ -198,6 +231,10 @@ impl Kind {
         matches!(self, Kind::List { .. })
     }
 
+    pub fn is_map(&self) -> bool {
+        matches!(self, Kind::Map { .. })
+    }
+
     pub fn is_subsection_ui(&self) -> bool {
         matches!(
             self,
@@ -280,6 +317,18 @@ impl Kind {
         matches!(self, Kind::Void { .. })
     }
 
+    pub fn is_date(&self) -> bool {
+        matches!(self, Kind::Date)
+    }
+
+    pub fn is_datetime(&self) -> bool {
+        matches!(self, Kind::DateTime)
+    }
+
+    pub fn is_duration(&self) -> bool {
+        matches!(self, Kind::Duration)
+    }
+
     pub(crate) fn list_type(
         &self,
         doc_name: &str,
@@ -295,6 +344,21 @@ impl Kind {
         }
     }
 
+    pub(crate) fn map_type(
+        &self,
+        doc_name: &str,
+        line_number: usize,
+    ) -> ftd::interpreter::Result<(Kind, Kind)> {
+        match &self {
+            Kind::Map { key, value } => Ok((key.as_ref().clone(), value.as_ref().clone())),
+            t => ftd::interpreter::utils::e2(
+                format!("Expected Map, found: `{:?}`", t),
+                doc_name,
+                line_number,
+            ),
+        }
+    }
+
     pub fn get_or_type(&self) -> Option<(String, Option<String>, Option<String>)> {
         match self {
             Kind::OrType {
@@ -364,9 +428,23 @@ impl KindData {
     ) -> ftd::interpreter::Result<()> {
         let ast_kind = var_kind.kind;
         match ast_kind.as_ref() {
-            "string" | "object" | "integer" | "decimal" | "boolean" | "void" | "ftd.ui"
-            | "children" => Ok(()),
+            "string" | "object" | "integer" | "decimal" | "boolean" | "date" | "datetime"
+            | "duration" | "void" | "ftd.ui" | "children" => Ok(()),
             k if known_kinds.contains_key(k) => Ok(()),
+            k if k.starts_with(MAP_KIND_PREFIX) => {
+                let (key, value) = split_map_kind(k).ok_or_else(|| {
+                    ftd::interpreter::utils::invalid_kind_error(k, doc.name, line_number)
+                })?;
+                for kind in [key, value] {
+                    KindData::scan_ast_kind(
+                        ftd::ast::VariableKind::get_kind(kind, doc.name, line_number)?,
+                        known_kinds,
+                        doc,
+                        line_number,
+                    )?;
+                }
+                Ok(())
+            }
             k => doc.scan_thing(k, line_number),
         }
     }
@@ -408,6 +486,9 @@ impl KindData {
             "integer" => Kind::integer(),
             "decimal" => Kind::decimal(),
             "boolean" => Kind::boolean(),
+            "date" => Kind::date(),
+            "datetime" => Kind::datetime(),
+            "duration" => Kind::duration(),
             "void" => Kind::void(),
             "ftd.ui" => Kind::ui(),
             "module" => Kind::module(),
@@ -424,6 +505,33 @@ impl KindData {
                 }
             }
             k if known_kinds.contains_key(k) => known_kinds.get(k).unwrap().to_owned(),
+            k if k.starts_with(MAP_KIND_PREFIX) => {
+                let (key, value) = split_map_kind(k).ok_or_else(|| {
+                    ftd::interpreter::utils::invalid_kind_error(k, doc.name, line_number)
+                })?;
+                let key = try_ok_state!(KindData::from_ast_kind(
+                    ftd::ast::VariableKind::get_kind(key, doc.name, line_number)?,
+                    known_kinds,
+                    doc,
+                    line_number,
+                )?)
+                .kind;
+                if !key.is_string() && !key.is_integer() {
+                    return ftd::interpreter::utils::e2(
+                        format!("Map key must be `string` or `integer`, found: `{:?}`", key),
+                        doc.name,
+                        line_number,
+                    );
+                }
+                let value = try_ok_state!(KindData::from_ast_kind(
+                    ftd::ast::VariableKind::get_kind(value, doc.name, line_number)?,
+                    known_kinds,
+                    doc,
+                    line_number,
+                )?)
+                .kind;
+                value.into_map(key)
+            }
             k => match try_ok_state!(doc.search_thing(k, line_number)?) {
                 ftd::interpreter::Thing::Record(r) => Kind::record(r.name.as_str()),
                 ftd::interpreter::Thing::Component(_) => Kind::ui(),
@@ -483,6 +591,10 @@ impl KindData {
         self.kind.is_list()
     }
 
+    pub fn is_map(&self) -> bool {
+        self.kind.is_map()
+    }
+
     pub fn is_or_type(&self) -> bool {
         self.kind.is_or_type()
     }
@@ -556,6 +668,27 @@ impl KindData {
     }
 }
 
+pub const MAP_KIND_PREFIX: &str = "map<";
+
+/// Splits `map<K, V>` into `K` and `V`. The value kind can itself be a map or
+/// carry a modifier, e.g. `map<string, map<string, integer>>` or
+/// `map<string, string list>`.
+pub(crate) fn split_map_kind(s: &str) -> Option<(&str, &str)> {
+    let inner = s.trim().strip_prefix(MAP_KIND_PREFIX)?.strip_suffix('>')?;
+    let mut depth = 0;
+    for (index, c) in inner.char_indices() {
+        match c {
+            '<' => depth += 1,
+            '>' => depth -= 1,
+            ',' if depth == 0 => {
+                return Some((inner[..index].trim(), inner[index + 1..].trim()));
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
 pub fn check_for_caption_and_body(s: &mut String) -> (bool, bool) {
     use itertools::Itertools;
 
@@ -1,4 +1,5 @@
 pub(crate) mod component;
+pub(crate) mod date;
 pub mod default;
 pub(crate) mod expression;
 pub(crate) mod function;
@@ -787,6 +787,21 @@ impl PropertyValue {
                         line_number: value.line_number(),
                     })
                 }
+                ftd::interpreter::Kind::Date
+                | ftd::interpreter::Kind::DateTime
+                | ftd::interpreter::Kind::Duration => {
+                    let line_number = value.line_number();
+                    ftd::interpreter::StateWithThing::new_thing(PropertyValue::Value {
+                        value: Value::from_date_literal(
+                            value.string(doc.name)?.as_str(),
+                            &expected_kind.kind,
+                            doc.name,
+                            line_number,
+                        )?,
+                        is_mutable,
+                        line_number,
+                    })
+                }
                 ftd::interpreter::Kind::List { kind } => {
                     let line_number = value.line_number();
                     let value_list = value.into_list(doc.name, kind)?;
@@ -834,6 +849,54 @@ impl PropertyValue {
                         line_number,
                     })
                 }
+                ftd::interpreter::Kind::Map {
+                    key: key_kind,
+                    value: kind,
+                } => {
+                    let line_number = value.line_number();
+                    let mut values: ftd::Map<PropertyValue> = Default::default();
+                    for (key, value) in value.into_map(doc.name)? {
+                        ftd::interpreter::utils::validate_map_key(
+                            key.as_str(),
+                            key_kind,
+                            doc.name,
+                            value.line_number(),
+                        )?;
+                        // `-- scores.alice:` section headers come as a single item list
+                        let value = match value {
+                            ftd::ast::VariableValue::List { mut value, .. }
+                                if !kind.is_list() && value.len() == 1 =>
+                            {
+                                value.remove(0).value
+                            }
+                            t => t,
+                        };
+                        values.insert(
+                            key,
+                            try_ok_state!(PropertyValue::from_ast_value_with_argument(
+                                value,
+                                doc,
+                                is_mutable,
+                                Some(&ftd::interpreter::KindData {
+                                    kind: kind.as_ref().clone(),
+                                    caption: expected_kind.caption,
+                                    body: expected_kind.body,
+                                }),
+                                definition_name_with_arguments,
+                                loop_object_name_and_kind,
+                            )?),
+                        );
+                    }
+                    ftd::interpreter::StateWithThing::new_thing(PropertyValue::Value {
+                        value: ftd::interpreter::Value::Map {
+                            data: values,
+                            key_kind: key_kind.as_ref().clone(),
+                            kind: kind.as_ref().clone().into_kind_data(),
+                        },
+                        is_mutable,
+                        line_number,
+                    })
+                }
                 ftd::interpreter::Kind::Record { name }
                     if value.is_record() || value.is_string() =>
                 {
@@ -1318,6 +1381,16 @@ pub enum Value {
     Boolean {
         value: bool,
     },
+    Date {
+        value: chrono::NaiveDate,
+    },
+    DateTime {
+        value: chrono::DateTime<chrono::Utc>,
+    },
+    /// Length of time, in seconds.
+    Duration {
+        value: i64,
+    },
     Object {
         values: ftd::Map<PropertyValue>,
     },
@@ -1335,6 +1408,13 @@ pub enum Value {
         data: Vec<PropertyValue>,
         kind: ftd::interpreter::KindData,
     },
+    /// Keys are stored as text (integer keys in their decimal form), so entries
+    /// are ordered by the key text, the same order a JSON object has.
+    Map {
+        data: ftd::Map<PropertyValue>,
+        key_kind: ftd::interpreter::Kind,
+        kind: ftd::interpreter::KindData,
+    },
     Optional {
         data: Box<Option<Value>>,
         kind: ftd::interpreter::KindData,
@@ -1369,12 +1449,11 @@ impl Value {
     }
 
     pub fn is_empty(&self) -> bool {
-        if let Self::List { data, .. } = self {
-            if data.is_empty() {
-                return true;
-            }
+        match self {
+            Self::List { data, .. } => data.is_empty(),
+            Self::Map { data, .. } => data.is_empty(),
+            _ => false,
         }
-        false
     }
 
     pub fn is_record(&self, rec_name: &str) -> bool {
@@ -1486,7 +1565,8 @@ impl Value {
                 is_static
             }
             ftd::interpreter::Value::Record { fields, .. }
-            | ftd::interpreter::Value::Object { values: fields, .. } => {
+            | ftd::interpreter::Value::Object { values: fields, .. }
+            | ftd::interpreter::Value::Map { data: fields, .. } => {
                 let mut is_static = true;
                 for d in fields.values() {
                     if !d.is_static(doc) {
@@ -1506,9 +1586,13 @@ impl Value {
             Value::Integer { .. } => ftd::interpreter::Kind::integer(),
             Value::Decimal { .. } => ftd::interpreter::Kind::decimal(),
             Value::Boolean { .. } => ftd::interpreter::Kind::boolean(),
+            Value::Date { .. } => ftd::interpreter::Kind::date(),
+            Value::DateTime { .. } => ftd::interpreter::Kind::datetime(),
+            Value::Duration { .. } => ftd::interpreter::Kind::duration(),
             Value::Object { .. } => ftd::interpreter::Kind::object(),
             Value::Record { name, .. } => ftd::interpreter::Kind::record(name),
             Value::List { kind, .. } => kind.kind.clone().into_list(),
+            Value::Map { key_kind, kind, .. } => kind.kind.clone().into_map(key_kind.clone()),
             Value::Optional { kind, .. } => ftd::interpreter::Kind::Optional {
                 kind: Box::new(kind.kind.clone()),
             },
@@ -1533,6 +1617,9 @@ impl Value {
             Value::Integer { value } => ftd::evalexpr::Value::Int(*value),
             Value::Decimal { value } => ftd::evalexpr::Value::Float(*value),
             Value::Boolean { value } => ftd::evalexpr::Value::Boolean(*value),
+            Value::Date { .. } | Value::DateTime { .. } | Value::Duration { .. } => {
+                self.to_owned().into_evalexpr_value(doc)?
+            }
             Value::List { data, .. } => {
                 let mut values = vec![];
                 for value in data {
@@ -1544,6 +1631,7 @@ impl Value {
                 }
                 ftd::evalexpr::Value::Tuple(values)
             }
+            Value::Map { .. } => self.to_owned().into_evalexpr_value(doc)?,
             Value::Optional { data, .. } => {
                 if let Some(data) = data.as_ref() {
                     data.to_evalexpr_value(doc, line_number)?
@@ -1574,6 +1662,14 @@ impl Value {
             ftd::evalexpr::Value::Boolean(value) if expected_kind.is_boolean() => {
                 Value::Boolean { value }
             }
+            ftd::evalexpr::Value::String(text)
+                if expected_kind.is_date() || expected_kind.is_datetime() =>
+            {
+                Value::from_date_literal(text.as_str(), expected_kind, doc_name, line_number)?
+            }
+            ftd::evalexpr::Value::Int(value) if expected_kind.is_duration() => {
+                Value::Duration { value }
+            }
             ftd::evalexpr::Value::Tuple(data) if expected_kind.is_list() => {
                 let mut values = vec![];
                 let val_kind = expected_kind.list_type(doc_name, line_number)?;
@@ -1589,6 +1685,53 @@ impl Value {
                     kind: ftd::interpreter::KindData::new(val_kind),
                 }
             }
+            ftd::evalexpr::Value::Tuple(data) if expected_kind.is_map() => {
+                let mut values: ftd::Map<ftd::interpreter::PropertyValue> = Default::default();
+                let (key_kind, val_kind) = expected_kind.map_type(doc_name, line_number)?;
+                for entry in data {
+                    let (key, val) = match entry {
+                        ftd::evalexpr::Value::Tuple(entry) if entry.len() == 2 => {
+                            let mut entry = entry.into_iter();
+                            (entry.next().unwrap(), entry.next().unwrap())
+                        }
+                        t => {
+                            return ftd::interpreter::utils::e2(
+                                format!("Expected `(key, value)` map entry, found: `{:?}`", t),
+                                doc_name,
+                                line_number,
+                            )
+                        }
+                    };
+                    let key = match key {
+                        ftd::evalexpr::Value::String(key) => key,
+                        t => t.to_string(),
+                    };
+                    ftd::interpreter::utils::validate_map_key(
+                        key.as_str(),
+                        &key_kind,
+                        doc_name,
+                        line_number,
+                    )?;
+                    values.insert(
+                        key,
+                        ftd::interpreter::PropertyValue::Value {
+                            value: Value::from_evalexpr_value(
+                                val,
+                                &val_kind,
+                                doc_name,
+                                line_number,
+                            )?,
+                            is_mutable: false,
+                            line_number,
+                        },
+                    );
+                }
+                Value::Map {
+                    data: values,
+                    key_kind,
+                    kind: ftd::interpreter::KindData::new(val_kind),
+                }
+            }
             ftd::evalexpr::Value::Empty if expected_kind.is_optional() => Value::Optional {
                 data: Box::new(None),
                 kind: ftd::interpreter::KindData::new(expected_kind.clone()),
@@ -1616,6 +1759,37 @@ impl Value {
         }
     }
 
+    /// Parses the literal form of a `date`, `datetime` or `duration` value, see
+    /// `ftd::interpreter::things::date` for the accepted formats.
+    pub(crate) fn from_date_literal(
+        text: &str,
+        kind: &ftd::interpreter::Kind,
+        doc_name: &str,
+        line_number: usize,
+    ) -> ftd::interpreter::Result<ftd::interpreter::Value> {
+        let value = match kind {
+            ftd::interpreter::Kind::Date => ftd::interpreter::things::date::parse_date(text)
+                .map(|value| ftd::interpreter::Value::Date { value }),
+            ftd::interpreter::Kind::DateTime => {
+                ftd::interpreter::things::date::parse_datetime(text)
+                    .map(|value| ftd::interpreter::Value::DateTime { value })
+            }
+            ftd::interpreter::Kind::Duration => {
+                ftd::interpreter::things::date::parse_duration(text)
+                    .map(|value| ftd::interpreter::Value::Duration { value })
+            }
+            _ => None,
+        };
+        match value {
+            Some(value) => Ok(value),
+            None => ftd::interpreter::utils::e2(
+                format!("Can't parse `{}` as {}", text, kind.get_name()),
+                doc_name,
+                line_number,
+            ),
+        }
+    }
+
     pub(crate) fn new_or_type(
         name: &str,
         variant: &str,
@@ -1639,6 +1813,13 @@ impl Value {
             ftd::interpreter::Value::Integer { value } => Ok(ftd::evalexpr::Value::Int(value)),
             ftd::interpreter::Value::Decimal { value } => Ok(ftd::evalexpr::Value::Float(value)),
             ftd::interpreter::Value::Boolean { value } => Ok(ftd::evalexpr::Value::Boolean(value)),
+            ftd::interpreter::Value::Date { value } => Ok(ftd::evalexpr::Value::String(
+                ftd::interpreter::things::date::date_to_string(&value),
+            )),
+            ftd::interpreter::Value::DateTime { value } => Ok(ftd::evalexpr::Value::String(
+                ftd::interpreter::things::date::datetime_to_string(&value),
+            )),
+            ftd::interpreter::Value::Duration { value } => Ok(ftd::evalexpr::Value::Int(value)),
             ftd::interpreter::Value::Optional { data, .. } => {
                 if let Some(data) = data.as_ref() {
                     data.clone().into_evalexpr_value(doc)
@@ -1665,6 +1846,20 @@ impl Value {
                 }
                 Ok(ftd::evalexpr::Value::Tuple(values))
             }
+            // A map is passed to expressions as a tuple of `(key, value)` tuples,
+            // see `ftd.map_keys`, `ftd.map_values` and `ftd.map_contains`. Keys
+            // are always strings, as they are for JavaScript objects in the browser.
+            ftd::interpreter::Value::Map { data, .. } => {
+                let mut values = vec![];
+                for (key, item) in data {
+                    let line_number = item.line_number();
+                    values.push(ftd::evalexpr::Value::Tuple(vec![
+                        ftd::evalexpr::Value::String(key),
+                        item.resolve(doc, line_number)?.into_evalexpr_value(doc)?,
+                    ]));
+                }
+                Ok(ftd::evalexpr::Value::Tuple(values))
+            }
             t => unimplemented!("{:?}", t),
         }
     }
@@ -1812,6 +2007,13 @@ impl Value {
             Value::Integer { value } => Some(serde_json::json!(value)),
             Value::Decimal { value } => Some(serde_json::json!(value)),
             Value::Boolean { value } => Some(serde_json::Value::Bool(value.to_owned())),
+            Value::Date { value } => Some(serde_json::Value::String(
+                ftd::interpreter::things::date::date_to_string(value),
+            )),
+            Value::DateTime { value } => Some(serde_json::Value::String(
+                ftd::interpreter::things::date::datetime_to_string(value),
+            )),
+            Value::Duration { value } => Some(serde_json::json!(value)),
             Value::Optional { data, .. } => {
                 if let Some(data) = data.as_ref() {
                     data.to_serde_value()
@@ -1852,6 +2054,17 @@ impl Value {
                 }
                 serde_json::to_value(&new_values).ok()
             }
+            Value::Map { data, .. } => {
+                let mut new_values: ftd::Map<serde_json::Value> = Default::default();
+                for (k, v) in data {
+                    if let ftd::interpreter::PropertyValue::Value { value, .. } = v {
+                        if let Some(v) = value.to_serde_value() {
+                            new_values.insert(k.to_owned(), v);
+                        }
+                    }
+                }
+                serde_json::to_value(&new_values).ok()
+            }
             _ => None,
         }
     }
@@ -1862,6 +2075,13 @@ impl Value {
             Value::Integer { value } => Some(value.to_string()),
             Value::Decimal { value } => Some(value.to_string()),
             Value::Boolean { value } => Some(value.to_string()),
+            Value::Date { value } => Some(ftd::interpreter::things::date::date_to_string(value)),
+            Value::DateTime { value } => {
+                Some(ftd::interpreter::things::date::datetime_to_string(value))
+            }
+            Value::Duration { value } => {
+                Some(ftd::interpreter::things::date::duration_to_string(*value))
+            }
             Value::Optional { data, .. } => {
                 if let Some(data) = data.as_ref() {
                     data.to_string()
@@ -1869,9 +2089,10 @@ impl Value {
                     Some("".to_string())
                 }
             }
-            Value::Object { .. } | Value::Record { .. } | Value::List { .. } => {
-                serde_json::to_string(&self.to_serde_value()).ok()
-            }
+            Value::Object { .. }
+            | Value::Record { .. }
+            | Value::List { .. }
+            | Value::Map { .. } => serde_json::to_string(&self.to_serde_value()).ok(),
             _ => None,
         }
     }
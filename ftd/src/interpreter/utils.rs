@@ -308,11 +308,47 @@ pub fn get_argument_for_reference_and_remaining(
                 ftd::interpreter::PropertyValueSource::Loop(loop_name.to_string()),
             )));
         }
+        if name.starts_with(format!("{}#{}", doc.name, ftd::interpreter::FTD_LOOP_KEY).as_str()) {
+            return Ok(Some((
+                ftd::interpreter::Field::default(
+                    ftd::interpreter::FTD_LOOP_KEY,
+                    ftd::interpreter::Kind::string()
+                        .into_optional()
+                        .into_kind_data(),
+                ),
+                None,
+                ftd::interpreter::PropertyValueSource::Loop(loop_name.to_string()),
+            )));
+        }
     }
 
     Ok(None)
 }
 
+/// Map entries are referred to as `<map>.<key>`, so a key can not have a `.` in it
+pub(crate) fn validate_map_key(
+    key: &str,
+    key_kind: &ftd::interpreter::Kind,
+    doc_id: &str,
+    line_number: usize,
+) -> ftd::interpreter::Result<()> {
+    if key_kind.is_integer() && key.parse::<i64>().is_err() {
+        return ftd::interpreter::utils::e2(
+            format!("Expected integer map key, found: `{}`", key),
+            doc_id,
+            line_number,
+        );
+    }
+    if key.contains('.') {
+        return ftd::interpreter::utils::e2(
+            format!("Map key can not contain `.`, found: `{}`", key),
+            doc_id,
+            line_number,
+        );
+    }
+    Ok(())
+}
+
 pub fn validate_variable(
     variable: &ftd::interpreter::Variable,
     doc: &ftd::interpreter::TDoc,
@@ -449,7 +485,13 @@ pub(crate) fn get_value(
             serde_json::to_value(value).ok()
         }
         Some(ftd::interpreter::Value::Decimal { value, .. }) => serde_json::to_value(value).ok(),
-        Some(ftd::interpreter::Value::Record { fields, .. }) => {
+        Some(
+            value @ (ftd::interpreter::Value::Date { .. }
+            | ftd::interpreter::Value::DateTime { .. }
+            | ftd::interpreter::Value::Duration { .. }),
+        ) => value.to_serde_value(),
+        Some(ftd::interpreter::Value::Record { fields, .. })
+        | Some(ftd::interpreter::Value::Map { data: fields, .. }) => {
             let mut value_fields = ftd::Map::new();
             for (k, v) in fields {
                 if let Some(value) = get_value(doc, &v.clone().resolve(doc, v.line_number())?)? {
@@ -520,6 +562,9 @@ pub(crate) fn js_reference_name(s: &str) -> String {
     if s.contains("LOOP.COUNTER") {
         s = "LOOP__COUNTER".to_string();
     }
+    if s.contains("LOOP.KEY") {
+        s = "LOOP__KEY".to_string();
+    }
     s
 }
 
@@ -214,7 +214,7 @@ fn ftd_v2_write(id: &str, s: &str) {
             )
             .replace("__ftd_body_events__", html_ui.outer_events.as_str())
             .replace("__ftd_css__", ftd::css())
-            .replace("__ftd_element_css__", "")
+            .replace("__ftd_element_css__", html_ui.element_css.as_str())
             .as_str(),
     );
     std::fs::create_dir_all("./docs").expect("failed to create docs folder");
@@ -233,6 +233,7 @@ impl ftd::executor::Element {
             ftd::executor::Element::Row(r) => r.to_node(doc_id, anchor_ids),
             ftd::executor::Element::Column(c) => c.to_node(doc_id, anchor_ids),
             ftd::executor::Element::Container(e) => e.to_node(doc_id, anchor_ids),
+            ftd::executor::Element::Grid(g) => g.to_node(doc_id, anchor_ids),
             ftd::executor::Element::Text(t) => t.to_node(doc_id, anchor_ids),
             ftd::executor::Element::Integer(t) => t.to_node(doc_id, anchor_ids),
             ftd::executor::Element::Decimal(t) => t.to_node(doc_id, anchor_ids),
@@ -484,6 +485,93 @@ impl ftd::executor::ContainerElement {
     }
 }
 
+impl ftd::executor::Grid {
+    pub fn to_node(&self, doc_id: &str, anchor_ids: &mut Vec<String>) -> Node {
+        use ftd::node::utils::CheckMap;
+
+        let mut n = Node::from_children(&self.common, &self.children, doc_id, "grid", anchor_ids);
+        n.classes.push(s("ft_grid"));
+        if !self.common.is_not_visible {
+            n.style
+                .insert(s("display"), ftd::node::Value::from_string("grid"));
+        }
+
+        let count = ftd::node::utils::count_children_with_absolute_parent(self.children.as_slice());
+        if count.gt(&0) {
+            n.style
+                .check_and_insert("position", ftd::node::Value::from_string("relative"));
+        }
+
+        n.style.check_and_insert(
+            "grid-template-columns",
+            ftd::node::Value::from_executor_value(
+                self.template_columns.value.to_owned(),
+                self.template_columns.to_owned(),
+                None,
+                doc_id,
+            ),
+        );
+
+        if !n.style.contains_key("grid-template-columns") {
+            n.style.check_and_insert(
+                "grid-template-columns",
+                ftd::node::Value::from_executor_value(
+                    self.columns
+                        .value
+                        .as_ref()
+                        .map(|v| v.to_css_string(&self.common.device)),
+                    self.columns.to_owned(),
+                    Some(ftd::executor::ResponsiveColumns::template_columns_pattern()),
+                    doc_id,
+                ),
+            );
+        }
+
+        n.style.check_and_insert(
+            "grid-template-rows",
+            ftd::node::Value::from_executor_value(
+                self.template_rows.value.to_owned(),
+                self.template_rows.to_owned(),
+                None,
+                doc_id,
+            ),
+        );
+
+        // The style ends up in a double quoted `style` attribute, so the area
+        // names are quoted with `'`, which CSS accepts as well.
+        n.style.check_and_insert(
+            "grid-template-areas",
+            ftd::node::Value::from_executor_value(
+                self.areas.value.as_ref().map(|v| v.replace('"', "'")),
+                self.areas.to_owned(),
+                None,
+                doc_id,
+            ),
+        );
+
+        for (key, length) in [
+            ("gap", &self.gap),
+            ("row-gap", &self.row_gap),
+            ("column-gap", &self.column_gap),
+        ] {
+            n.style.check_and_insert(
+                key,
+                ftd::node::Value::from_executor_value(
+                    length
+                        .value
+                        .as_ref()
+                        .map(|v| v.to_css_string(&self.common.device)),
+                    length.to_owned(),
+                    None,
+                    doc_id,
+                ),
+            );
+        }
+
+        n
+    }
+}
+
 impl ftd::executor::Text {
     pub fn to_node(&self, doc_id: &str, anchor_ids: &mut Vec<String>) -> Node {
         use ftd::node::utils::CheckMap;
@@ -1214,6 +1302,39 @@ impl ftd::executor::Common {
             ),
         );
 
+        d.check_and_insert(
+            "transform",
+            ftd::node::Value::from_executor_value(
+                self.transform
+                    .value
+                    .as_ref()
+                    .map(|v| v.to_css_string(&self.device)),
+                self.transform.to_owned(),
+                Some(ftd::executor::Transform::transform_pattern()),
+                doc_id,
+            ),
+        );
+
+        d.check_and_insert(
+            "transition",
+            ftd::node::Value::from_executor_value(
+                self.transition.value.as_ref().map(|v| v.to_css_string()),
+                self.transition.to_owned(),
+                Some(ftd::executor::Transition::transition_pattern()),
+                doc_id,
+            ),
+        );
+
+        d.check_and_insert(
+            "animation",
+            ftd::node::Value::from_executor_value(
+                self.animation.value.as_ref().map(|v| v.to_css_string()),
+                self.animation.to_owned(),
+                Some(ftd::executor::Animation::animation_pattern()),
+                doc_id,
+            ),
+        );
+
         d.check_and_insert(
             "top",
             ftd::node::Value::from_executor_value(
@@ -1292,6 +1413,36 @@ impl ftd::executor::Common {
             ),
         );
 
+        d.check_and_insert(
+            "grid-column",
+            ftd::node::Value::from_executor_value(
+                self.grid_column.value.to_owned(),
+                self.grid_column.to_owned(),
+                None,
+                doc_id,
+            ),
+        );
+
+        d.check_and_insert(
+            "grid-row",
+            ftd::node::Value::from_executor_value(
+                self.grid_row.value.to_owned(),
+                self.grid_row.to_owned(),
+                None,
+                doc_id,
+            ),
+        );
+
+        d.check_and_insert(
+            "grid-area",
+            ftd::node::Value::from_executor_value(
+                self.grid_area.value.to_owned(),
+                self.grid_area.to_owned(),
+                None,
+                doc_id,
+            ),
+        );
+
         d.check_and_insert(
             "resize",
             ftd::node::Value::from_executor_value(
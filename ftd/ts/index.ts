@@ -154,6 +154,158 @@ window.ftd = (function() {
         return (!str || str.length === 0 );
     }
 
+    exports.format_date = function(value: string, pattern: string) {
+        return format_datetime(value, pattern);
+    }
+
+    exports.format_date_locale = function(value: string, locale: string) {
+        return format_datetime(value, date_locale_pattern(locale, !is_plain_date(value)));
+    }
+
+    exports.format_duration = function(seconds: number) {
+        if (seconds === 0) {
+            return "0s";
+        }
+        let remaining = Math.abs(seconds);
+        let parts = [];
+        for (let [unit, size] of [["d", 86400], ["h", 3600], ["m", 60], ["s", 1]]) {
+            let count = Math.floor(remaining / <number>size);
+            remaining %= <number>size;
+            if (count > 0) {
+                parts.push(count + <string>unit);
+            }
+        }
+        return (seconds < 0 ? "-" : "") + parts.join(" ");
+    }
+
+    exports.date_diff = function(from: string, to: string) {
+        let from_date = parse_datetime(from);
+        let to_date = parse_datetime(to);
+        if (from_date === null || to_date === null) {
+            return null;
+        }
+        return Math.trunc((to_date.getTime() - from_date.getTime()) / 1000);
+    }
+
+    exports.date_add = function(value: string, seconds: number) {
+        let d = parse_datetime(value);
+        if (d === null) {
+            return null;
+        }
+        let result = new Date(d.getTime() + seconds * 1000);
+        return is_plain_date(value)
+            ? result.toISOString().substring(0, 10)
+            : datetime_to_string(result);
+    }
+
+    exports.map_keys = function(map: any) {
+        return !map ? [] : Object.keys(map).sort();
+    }
+
+    exports.map_values = function(map: any) {
+        return !map ? [] : Object.keys(map).sort().map(key => map[key]);
+    }
+
+    exports.map_contains = function(map: any, key: any) {
+        return !!map && Object.prototype.hasOwnProperty.call(map, String(key));
+    }
+
+    exports.map_get = function(map: any, key: any) {
+        return window.ftd.map_contains(map, key) ? map[String(key)] : null;
+    }
+
+    exports.split = function(s: string, separator: string) {
+        return separator === "" ? Array.from(s) : s.split(separator);
+    }
+
+    exports.join = function(list: any, separator: string) {
+        return list_items(list).map(value_to_text).join(separator);
+    }
+
+    exports.replace = function(s: string, from: string, to: string) {
+        return from === "" ? s : s.split(from).join(to);
+    }
+
+    exports.trim = function(s: string) {
+        return s.trim();
+    }
+
+    exports.starts_with = function(s: string, prefix: string) {
+        return s.startsWith(prefix);
+    }
+
+    exports.ends_with = function(s: string, suffix: string) {
+        return s.endsWith(suffix);
+    }
+
+    exports.to_uppercase = function(s: string) {
+        return s.toUpperCase();
+    }
+
+    exports.to_lowercase = function(s: string) {
+        return s.toLowerCase();
+    }
+
+    exports.slice = function(value: any, start: number, end?: any) {
+        end = typeof end === "number" ? end : undefined;
+        if (typeof value === "string") {
+            return Array.from(value).slice(start, end).join("");
+        }
+        return list_items(value).slice(start, end);
+    }
+
+    exports.reverse = function(value: any) {
+        if (typeof value === "string") {
+            return Array.from(value).reverse().join("");
+        }
+        return list_items(value).slice().reverse();
+    }
+
+    exports.sort = function(list: any) {
+        let items = list_items(list).slice();
+        if (items.every((v: any) => typeof v === "number")) {
+            return items.sort((a: number, b: number) => a - b);
+        }
+        return items.sort((a: any, b: any) => {
+            let [x, y] = [value_to_text(a), value_to_text(b)];
+            return x < y ? -1 : x > y ? 1 : 0;
+        });
+    }
+
+    exports.contains = function(value: any, item: any) {
+        if (typeof value === "string") {
+            return value.includes(value_to_text(item));
+        }
+        return list_items(value).some((v: any) => values_equal(v, item));
+    }
+
+    exports.index_of = function(value: any, item: any) {
+        if (typeof value === "string") {
+            let index = value.indexOf(value_to_text(item));
+            return index < 0 ? -1 : Array.from(value.substring(0, index)).length;
+        }
+        return list_items(value).findIndex((v: any) => values_equal(v, item));
+    }
+
+    exports.filter = function(list: any, item: any) {
+        return list_items(list).filter((v: any) => values_equal(v, item));
+    }
+
+    exports.sum = function(list: any) {
+        return list_items(list).reduce((sum: number, v: number) => sum + v, 0);
+    }
+
+    exports.format_number = function(value: number, decimals?: any) {
+        decimals = typeof decimals === "number" ? Math.min(Math.max(Math.trunc(decimals), 0), 15) : 0;
+        let scale = Math.pow(10, decimals);
+        let scaled = Math.floor(Math.abs(value) * scale + 0.5);
+        let result = String(Math.floor(scaled / scale)).replace(/\B(?=(\d{3})+(?!\d))/g, ",");
+        if (decimals > 0) {
+            result += "." + String(scaled % scale).padStart(decimals, "0");
+        }
+        return value < 0 && scaled !== 0 ? "-" + result : result;
+    }
+
     exports.set_list = function(array: any[], value: any[], args: any, data: any, id: string) {
         args["CHANGE_VALUE"]= false;
         window.ftd.clear(array, args, data, id);
@@ -317,6 +469,11 @@ window.ftd = (function() {
         xhr.open(method_name, url);
         xhr.setRequestHeader("Accept", "application/json");
         xhr.setRequestHeader("Content-Type", "application/json");
+        // fastn form handlers expect the `fastn-csrf` cookie to be sent back as a header
+        let csrf_cookie = document.cookie.split("; ").find(c => c.startsWith("fastn-csrf="));
+        if (!!csrf_cookie) {
+            xhr.setRequestHeader("X-FASTN-CSRF", csrf_cookie.substring("fastn-csrf=".length));
+        }
 
         xhr.onreadystatechange = function () {
             if (xhr.readyState !== 4) {
@@ -21,6 +21,31 @@ declare global {
         set_value_by_id(id: string, variable: string, value: any): any;
         get_value(id: string, variable: string): any;
         is_empty(str: any): boolean;
+        format_date(value: string, pattern: string): string | null;
+        format_date_locale(value: string, locale: string): string | null;
+        format_duration(seconds: number): string;
+        date_diff(from: string, to: string): number | null;
+        date_add(value: string, seconds: number): string | null;
+        map_keys(map: any): string[];
+        map_values(map: any): any[];
+        map_contains(map: any, key: any): boolean;
+        map_get(map: any, key: any): any;
+        split(s: string, separator: string): string[];
+        join(list: any, separator: string): string;
+        replace(s: string, from: string, to: string): string;
+        trim(s: string): string;
+        starts_with(s: string, prefix: string): boolean;
+        ends_with(s: string, suffix: string): boolean;
+        to_uppercase(s: string): string;
+        to_lowercase(s: string): string;
+        slice(value: any, start: number, end?: number): any;
+        reverse(value: any): any;
+        sort(list: any): any[];
+        contains(value: any, item: any): boolean;
+        index_of(value: any, item: any): number;
+        filter(list: any, item: any): any[];
+        sum(list: any): number;
+        format_number(value: number, decimals?: number): string;
         set_list(array: any[], value: any[], args: any, data: any, id: string): any[];
         append(array: any[], value: any, args: any, data: any, id: string): any[];
         clear(array: any[], args: any, data: any, id: string): any[];
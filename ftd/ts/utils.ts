@@ -379,6 +379,43 @@ window.ftd.dependencies.eval_box_shadow = function(shadow: any, data: any) {
     }
 }
 
+window.ftd.dependencies.eval_transform = function(transform: any, data: any) {
+    if (typeof transform === 'object' && !!transform) {
+        let functions = [];
+        if (transform["translate-x"] != null) functions.push("translateX(" + transform["translate-x"] + ")");
+        if (transform["translate-y"] != null) functions.push("translateY(" + transform["translate-y"] + ")");
+        if (transform.scale != null) functions.push("scale(" + transform.scale + ")");
+        if (transform.rotate != null) functions.push("rotate(" + transform.rotate + "deg)");
+        if (functions.length === 0) return "none";
+        return functions.join(" ");
+    }
+    else {
+        return null;
+    }
+}
+
+window.ftd.dependencies.eval_transition = function(transition: any, data: any) {
+    if (typeof transition === 'object' && !!transition) {
+        // property, duration, easing, delay
+        return transition.property + " " + transition.duration + "ms " + transition.easing + " " + transition.delay + "ms";
+    }
+    else {
+        return null;
+    }
+}
+
+window.ftd.dependencies.eval_animation = function(animation: any, data: any) {
+    if (typeof animation === 'object' && !!animation) {
+        let iterations = animation.infinite ? "infinite" : animation.iterations;
+        let direction = animation.alternate ? "alternate" : "normal";
+        // name, duration, easing, delay, iterations, direction, fill-mode
+        return animation.keyframes.name + " " + animation.duration + "ms " + animation.easing + " " + animation.delay + "ms " + iterations + " " + direction + " both";
+    }
+    else {
+        return null;
+    }
+}
+
 window.ftd.utils.add_extra_in_id = function (node_id: string) {
     let element = document.querySelector(`[data-id=\"${node_id}\"]`);
     if (element) {
@@ -427,3 +464,131 @@ function updatedID(str: string, flag: boolean, suffix: string) {
         return str.replace(suffix, "");
     }
 }
+
+// Date helpers, these mirror `ftd::interpreter::things::date` so that dates
+// are parsed and formatted identically on the server and in the browser.
+const DATE_MONTHS = ["January", "February", "March", "April", "May", "June", "July",
+    "August", "September", "October", "November", "December"];
+const DATE_WEEKDAYS = ["Sunday", "Monday", "Tuesday", "Wednesday", "Thursday", "Friday",
+    "Saturday"];
+const DATE_REGEX = /^(\d{4})-(\d{1,2})-(\d{1,2})$/;
+const DATETIME_REGEX = /^(\d{4})-(\d{1,2})-(\d{1,2})[T ](\d{1,2}):(\d{1,2})(?::(\d{1,2})(\.\d+)?)?(Z|[+-]\d{2}:\d{2})?$/;
+
+function parse_datetime(value: string): Date | null {
+    value = value.trim();
+    let date = DATE_REGEX.exec(value);
+    if (!!date) {
+        return new Date(Date.UTC(parseInt(date[1]), parseInt(date[2]) - 1, parseInt(date[3])));
+    }
+    let m = DATETIME_REGEX.exec(value);
+    if (!m) {
+        return null;
+    }
+    let ms = Date.UTC(parseInt(m[1]), parseInt(m[2]) - 1, parseInt(m[3]), parseInt(m[4]),
+        parseInt(m[5]), !!m[6] ? parseInt(m[6]) : 0);
+    if (!!m[8] && m[8] !== "Z") {
+        let sign = m[8][0] === "-" ? -1 : 1;
+        let [hours, minutes] = m[8].substring(1).split(":");
+        ms -= sign * (parseInt(hours) * 60 + parseInt(minutes)) * 60000;
+    }
+    return new Date(ms);
+}
+
+function is_plain_date(value: string) {
+    return DATE_REGEX.test(value.trim());
+}
+
+function pad_date_part(n: number, no_pad: boolean) {
+    return no_pad || n >= 10 ? n.toString() : "0" + n;
+}
+
+function format_datetime(value: string, pattern: string): string | null {
+    let d = parse_datetime(value);
+    if (d === null) {
+        return null;
+    }
+    let result = "";
+    for (let i = 0; i < pattern.length; i++) {
+        let c = pattern[i];
+        if (c !== "%") {
+            result += c;
+            continue;
+        }
+        let no_pad = pattern[i + 1] === "-";
+        if (no_pad) {
+            i++;
+        }
+        i++;
+        let hour12 = d.getUTCHours() % 12 === 0 ? 12 : d.getUTCHours() % 12;
+        switch (pattern[i]) {
+            case "Y": result += d.getUTCFullYear(); break;
+            case "y": result += pad_date_part(d.getUTCFullYear() % 100, false); break;
+            case "m": result += pad_date_part(d.getUTCMonth() + 1, no_pad); break;
+            case "d": result += pad_date_part(d.getUTCDate(), no_pad); break;
+            case "e": result += d.getUTCDate(); break;
+            case "H": result += pad_date_part(d.getUTCHours(), no_pad); break;
+            case "I": result += pad_date_part(hour12, no_pad); break;
+            case "M": result += pad_date_part(d.getUTCMinutes(), no_pad); break;
+            case "S": result += pad_date_part(d.getUTCSeconds(), no_pad); break;
+            case "p": result += d.getUTCHours() >= 12 ? "PM" : "AM"; break;
+            case "B": result += DATE_MONTHS[d.getUTCMonth()]; break;
+            case "b": result += DATE_MONTHS[d.getUTCMonth()].substring(0, 3); break;
+            case "A": result += DATE_WEEKDAYS[d.getUTCDay()]; break;
+            case "a": result += DATE_WEEKDAYS[d.getUTCDay()].substring(0, 3); break;
+            case "%": result += "%"; break;
+            default: return null;
+        }
+    }
+    return result;
+}
+
+function date_locale_pattern(locale: string, with_time: boolean) {
+    let normalised = locale.toLowerCase().replace("_", "-");
+    let language = normalised.split("-")[0];
+    if (["en-gb", "en-in", "en-au", "en-nz", "en-ie"].includes(normalised)) {
+        return with_time ? "%-d %b %Y %H:%M" : "%-d %b %Y";
+    }
+    if (language === "en") {
+        return with_time ? "%b %-d, %Y %-I:%M %p" : "%b %-d, %Y";
+    }
+    if (["de", "ru", "pl", "tr", "fi", "nb", "da", "cs"].includes(language)) {
+        return with_time ? "%d.%m.%Y %H:%M" : "%d.%m.%Y";
+    }
+    if (["fr", "es", "it", "pt", "hi"].includes(language)) {
+        return with_time ? "%d/%m/%Y %H:%M" : "%d/%m/%Y";
+    }
+    if (["ja", "zh", "ko"].includes(language)) {
+        return with_time ? "%Y/%m/%d %H:%M" : "%Y/%m/%d";
+    }
+    return with_time ? "%Y-%m-%d %H:%M" : "%Y-%m-%d";
+}
+
+function datetime_to_string(d: Date) {
+    return d.toISOString().substring(0, 19) + "Z";
+}
+
+function list_items(value: any): any[] {
+    if (Array.isArray(value)) {
+        return value;
+    }
+    return value === null || value === undefined ? [] : [value];
+}
+
+// Same as `String()` except that `null` is empty, this is how `ftd.join` and
+// `ftd.sort` write values at render time as well.
+function value_to_text(value: any): string {
+    if (value === null || value === undefined) {
+        return "";
+    }
+    if (Array.isArray(value)) {
+        return value.map(value_to_text).join(",");
+    }
+    return String(value);
+}
+
+function values_equal(a: any, b: any): boolean {
+    if (Array.isArray(a) && Array.isArray(b)) {
+        return a.length === b.length && a.every((v, i) => values_equal(v, b[i]));
+    }
+    return a === b;
+}